@@ -8,6 +8,17 @@ mod m20251030_145305_user_wishlist;
 mod m20251030_150713_retreat_reviews;
 mod m20251103_162943_retreat_gallery;
 mod m20251109_154739_gallery_category;
+mod m20251201_090000_idempotency_keys;
+mod m20251205_090000_webhooks;
+mod m20251205_100000_user_admins;
+mod m20251208_090000_jobs;
+mod m20251210_090000_outbox_events;
+mod m20251212_090000_soft_delete;
+mod m20251215_090000_organizations;
+mod m20251218_090000_translations;
+mod m20251221_090000_currencies;
+mod m20251227_090000_audit_events;
+mod updated_at;
 
 pub struct Migrator;
 
@@ -23,6 +34,16 @@ impl MigratorTrait for Migrator {
             Box::new(m20251030_150713_retreat_reviews::Migration),
             Box::new(m20251103_162943_retreat_gallery::Migration),
             Box::new(m20251109_154739_gallery_category::Migration),
+            Box::new(m20251201_090000_idempotency_keys::Migration),
+            Box::new(m20251205_090000_webhooks::Migration),
+            Box::new(m20251205_100000_user_admins::Migration),
+            Box::new(m20251208_090000_jobs::Migration),
+            Box::new(m20251210_090000_outbox_events::Migration),
+            Box::new(m20251212_090000_soft_delete::Migration),
+            Box::new(m20251215_090000_organizations::Migration),
+            Box::new(m20251218_090000_translations::Migration),
+            Box::new(m20251221_090000_currencies::Migration),
+            Box::new(m20251227_090000_audit_events::Migration),
         ]
     }
 }
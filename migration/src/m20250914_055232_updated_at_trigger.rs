@@ -1,35 +1,18 @@
 use sea_orm_migration::prelude::*;
 
+use crate::updated_at::{create_updated_at_function, drop_updated_at_function};
+
 #[derive(DeriveMigrationName)]
 pub struct Migration;
 
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        // Replace the sample below with your own migration scripts
-        let db = manager.get_connection();
-
         // Create trigger function
-        db.execute_unprepared(
-            r#"
-            CREATE OR REPLACE FUNCTION set_updated_at()
-            RETURNS TRIGGER AS $$
-            BEGIN
-                NEW.updated_at = NOW();
-                RETURN NEW;
-            END;
-            $$ language 'plpgsql';
-            "#
-        ).await?;
-        Ok(())
+        create_updated_at_function(manager).await
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        // Replace the sample below with your own migration scripts
-        let db = manager.get_connection();
-        db.execute_unprepared(
-            r#"DROP FUNCTION IF EXISTS set_updated_at;"#
-        ).await?;
-        Ok(())
+        drop_updated_at_function(manager).await
     }
 }
@@ -1,32 +1,20 @@
 use sea_orm_migration::prelude::*;
 
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
 #[derive(DeriveMigrationName)]
 pub struct Migration;
 
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        // Replace the sample below with your own migration scripts
-        let db = manager.get_connection();
-
         // Attach trigger to user table
-        db.execute_unprepared(
-            r#"
-            CREATE TRIGGER trigger_set_updated_at
-            BEFORE UPDATE ON "users"
-            FOR EACH ROW
-            EXECUTE FUNCTION set_updated_at();
-            "#
-        ).await?;
+        attach_updated_at_trigger(manager, "users").await?;
         Ok(())
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        // Replace the sample below with your own migration scripts
-        let db = manager.get_connection();
-        db.execute_unprepared(
-            r#"DROP TRIGGER IF EXISTS trigger_set_updated_at ON "users";"#
-        ).await?;
+        detach_updated_at_trigger(manager, "users").await?;
         Ok(())
     }
 }
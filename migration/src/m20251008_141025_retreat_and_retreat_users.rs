@@ -1,5 +1,7 @@
 use sea_orm_migration::prelude::*;
 
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
 #[derive(DeriveMigrationName)]
 pub struct Migration;
 
@@ -200,55 +202,24 @@ impl MigrationTrait for Migration {
             )
             .await?;
 
-        let db = manager.get_connection();
-
         // Attach trigger to user table
-        db.execute_unprepared(
-            r#"
-            CREATE TRIGGER trigger_set_updated_at
-            BEFORE UPDATE ON "categories"
-            FOR EACH ROW
-            EXECUTE FUNCTION set_updated_at();
-            "#
-        ).await?;
+        attach_updated_at_trigger(manager, "categories").await?;
 
         // Attach trigger to user table
-        db.execute_unprepared(
-            r#"
-            CREATE TRIGGER trigger_set_updated_at
-            BEFORE UPDATE ON "retreats"
-            FOR EACH ROW
-            EXECUTE FUNCTION set_updated_at();
-            "#
-        ).await?;
+        attach_updated_at_trigger(manager, "retreats").await?;
 
         // Attach trigger to user table
-        db.execute_unprepared(
-            r#"
-            CREATE TRIGGER trigger_set_updated_at
-            BEFORE UPDATE ON "retreat_users"
-            FOR EACH ROW
-            EXECUTE FUNCTION set_updated_at();
-            "#
-        ).await?;
+        attach_updated_at_trigger(manager, "retreat_users").await?;
 
         Ok(())
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        let db = manager.get_connection();
-
-        db.execute_unprepared(
-            r#"DROP TRIGGER IF EXISTS trigger_set_updated_at ON "retreat_users";"#
-        ).await?;
+        detach_updated_at_trigger(manager, "retreat_users").await?;
 
-        db.execute_unprepared(
-            r#"DROP TRIGGER IF EXISTS trigger_set_updated_at ON "retreats";"#
-        ).await?;
+        detach_updated_at_trigger(manager, "retreats").await?;
 
-        db.execute_unprepared(
-            r#"DROP TRIGGER IF EXISTS trigger_set_updated_at ON "categories";"#
-        ).await?;
+        detach_updated_at_trigger(manager, "categories").await?;
 
         // Drop in reverse dependency order
         manager
@@ -1,5 +1,7 @@
 use sea_orm_migration::prelude::*;
 
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
 #[derive(DeriveMigrationName)]
 pub struct Migration;
 
@@ -82,26 +84,13 @@ impl MigrationTrait for Migration {
             )
             .await?;
 
-        let db = manager.get_connection();
-
         // Attach trigger to user table
-        db.execute_unprepared(
-            r#"
-            CREATE TRIGGER trigger_set_updated_at
-            BEFORE UPDATE ON "retreat_reviews"
-            FOR EACH ROW
-            EXECUTE FUNCTION set_updated_at();
-            "#
-        ).await?;
+        attach_updated_at_trigger(manager, "retreat_reviews").await?;
         Ok(())
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        let db = manager.get_connection();
-
-        db.execute_unprepared(
-            r#"DROP TRIGGER IF EXISTS trigger_set_updated_at ON "retreat_reviews";"#
-        ).await?;
+        detach_updated_at_trigger(manager, "retreat_reviews").await?;
 
         manager
             .drop_table(Table::drop().table(RetreatReviews::Table).to_owned())
@@ -1,5 +1,7 @@
 use sea_orm_migration::prelude::*;
 
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
 #[derive(DeriveMigrationName)]
 pub struct Migration;
 
@@ -87,27 +89,14 @@ impl MigrationTrait for Migration {
                     .to_owned(),
             )
             .await?;
-        let db = manager.get_connection();
-
         // Attach trigger to user table
-        db.execute_unprepared(
-            r#"
-            CREATE TRIGGER trigger_set_updated_at
-            BEFORE UPDATE ON "retreat_galleries"
-            FOR EACH ROW
-            EXECUTE FUNCTION set_updated_at();
-            "#
-        ).await?;
+        attach_updated_at_trigger(manager, "retreat_galleries").await?;
 
         Ok(())
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-         let db = manager.get_connection();
-
-        db.execute_unprepared(
-            r#"DROP TRIGGER IF EXISTS trigger_set_updated_at ON "retreat_galleries";"#
-        ).await?;
+        detach_updated_at_trigger(manager, "retreat_galleries").await?;
         manager
             .drop_table(Table::drop().table(RetreatGalleries::Table).to_owned())
             .await
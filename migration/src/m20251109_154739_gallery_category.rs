@@ -1,4 +1,7 @@
 use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DatabaseBackend;
+
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
 
 #[derive(DeriveMigrationName)]
 pub struct Migration;
@@ -60,65 +63,60 @@ impl MigrationTrait for Migration {
             )
             .await?;
 
-        let db = manager.get_connection();
-
         // Attach trigger to user table
-        db.execute_unprepared(
-            r#"
-            CREATE TRIGGER trigger_set_updated_at
-            BEFORE UPDATE ON "gallery_categories"
-            FOR EACH ROW
-            EXECUTE FUNCTION set_updated_at();
-            "#,
-        )
-        .await?;
+        attach_updated_at_trigger(manager, "gallery_categories").await?;
 
         // add gallery_category_id foreign key to retreat_galleries model
+        let mut gallery_category_id: ColumnDef = ColumnDef::new(RetreatGalleries::GalleryCategoryId)
+            .big_integer()
+            .null()
+            .to_owned();
+        if manager.get_database_backend() == DatabaseBackend::Sqlite {
+            // SQLite cannot add a constraint to an existing table, only a column carrying one
+            gallery_category_id.extra(
+                r#"REFERENCES "gallery_categories" ("gallery_category_id") ON DELETE SET NULL ON UPDATE CASCADE"#,
+            );
+        }
         manager
             .alter_table(
                 Table::alter()
                     .table(RetreatGalleries::Table)
-                    .add_column(
-                        ColumnDef::new(RetreatGalleries::GalleryCategoryId)
-                            .big_integer()
-                            .null(),
-                    )
+                    .add_column(gallery_category_id)
                     .to_owned(),
             )
             .await?;
 
-        manager
-            .create_foreign_key(
-                ForeignKey::create()
-                    .from(RetreatGalleries::Table, RetreatGalleries::GalleryCategoryId)
-                    .to(
-                        GalleryCategories::Table,
-                        GalleryCategories::GalleryCategoryId,
-                    )
-                    .on_delete(ForeignKeyAction::SetNull)
-                    .on_update(ForeignKeyAction::Cascade)
-                    .to_owned(),
-            )
-            .await?;
+        if manager.get_database_backend() != DatabaseBackend::Sqlite {
+            manager
+                .create_foreign_key(
+                    ForeignKey::create()
+                        .from(RetreatGalleries::Table, RetreatGalleries::GalleryCategoryId)
+                        .to(
+                            GalleryCategories::Table,
+                            GalleryCategories::GalleryCategoryId,
+                        )
+                        .on_delete(ForeignKeyAction::SetNull)
+                        .on_update(ForeignKeyAction::Cascade)
+                        .to_owned(),
+                )
+                .await?;
+        }
         Ok(())
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        manager
-            .drop_foreign_key(
-                ForeignKey::drop()
-                    .name("retreat_galleries_gallery_category_id_fkey") // name it same as you used
-                    .table(RetreatGalleries::Table)
-                    .to_owned(),
-            )
-            .await?;
-
-        let db = manager.get_connection();
+        if manager.get_database_backend() != DatabaseBackend::Sqlite {
+            manager
+                .drop_foreign_key(
+                    ForeignKey::drop()
+                        .name("retreat_galleries_gallery_category_id_fkey") // name it same as you used
+                        .table(RetreatGalleries::Table)
+                        .to_owned(),
+                )
+                .await?;
+        }
 
-        db.execute_unprepared(
-            r#"DROP TRIGGER IF EXISTS trigger_set_updated_at ON "gallery_categories";"#,
-        )
-        .await?;
+        detach_updated_at_trigger(manager, "gallery_categories").await?;
 
         manager
             .drop_table(Table::drop().table(GalleryCategories::Table).to_owned())
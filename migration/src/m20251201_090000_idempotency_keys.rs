@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdempotencyKeys::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::IdempotencyKeyId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::Key)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(IdempotencyKeys::Scope).string().not_null())
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::RequestFingerprint)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    // NULL while the original request is still being processed
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::ResponseStatusCode)
+                            .small_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::ResponseContentType)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::ResponseBody)
+                            .binary()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("idx_unique_idempotency_key_scope")
+                            .col(IdempotencyKeys::Key)
+                            .col(IdempotencyKeys::Scope),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_idempotency_keys_expires_at")
+                    .table(IdempotencyKeys::Table)
+                    .col(IdempotencyKeys::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdempotencyKeys::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdempotencyKeys {
+    Table,
+    IdempotencyKeyId,
+    Key,
+    Scope,
+    RequestFingerprint,
+    ResponseStatusCode,
+    ResponseContentType,
+    ResponseBody,
+    CreatedAt,
+    ExpiresAt,
+}
@@ -0,0 +1,118 @@
+use sea_orm_migration::prelude::*;
+
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Jobs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Jobs::JobId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Jobs::Queue).string_len(64).not_null())
+                    .col(ColumnDef::new(Jobs::Kind).string_len(64).not_null())
+                    .col(ColumnDef::new(Jobs::Payload).json_binary().not_null())
+                    // queued, running, succeeded, dead or cancelled
+                    .col(
+                        ColumnDef::new(Jobs::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("queued"),
+                    )
+                    .col(
+                        ColumnDef::new(Jobs::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(Jobs::MaxAttempts).integer().not_null())
+                    // Not picked up before this, delayed jobs and retries set it in the future
+                    .col(
+                        ColumnDef::new(Jobs::RunAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Jobs::LockedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Jobs::LockedBy).string().null())
+                    .col(ColumnDef::new(Jobs::LastError).text().null())
+                    .col(
+                        ColumnDef::new(Jobs::FinishedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Jobs::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Jobs::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_jobs_due")
+                    .table(Jobs::Table)
+                    .col(Jobs::Queue)
+                    .col(Jobs::Status)
+                    .col(Jobs::RunAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Attach trigger to jobs table
+        attach_updated_at_trigger(manager, "jobs").await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        detach_updated_at_trigger(manager, "jobs").await?;
+        manager
+            .drop_table(Table::drop().table(Jobs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Jobs {
+    Table,
+    JobId,
+    Queue,
+    Kind,
+    Payload,
+    Status,
+    Attempts,
+    MaxAttempts,
+    RunAt,
+    LockedAt,
+    LockedBy,
+    LastError,
+    FinishedAt,
+    CreatedAt,
+    UpdatedAt,
+}
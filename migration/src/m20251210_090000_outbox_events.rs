@@ -0,0 +1,105 @@
+use sea_orm_migration::prelude::*;
+
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OutboxEvents::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OutboxEvents::OutboxEventId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::Event)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::Payload)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    // pending, dispatched or failed
+                    .col(
+                        ColumnDef::new(OutboxEvents::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(OutboxEvents::LastError).text().null())
+                    .col(
+                        ColumnDef::new(OutboxEvents::DispatchedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_outbox_events_pending")
+                    .table(OutboxEvents::Table)
+                    .col(OutboxEvents::Status)
+                    .col(OutboxEvents::OutboxEventId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Attach trigger to outbox_events table
+        attach_updated_at_trigger(manager, "outbox_events").await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        detach_updated_at_trigger(manager, "outbox_events").await?;
+        manager
+            .drop_table(Table::drop().table(OutboxEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OutboxEvents {
+    Table,
+    OutboxEventId,
+    Event,
+    Payload,
+    Status,
+    Attempts,
+    LastError,
+    DispatchedAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,97 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Tables whose rows are hidden by `deleted_at` before they are purged.
+fn soft_deleted_tables() -> [DynIden; 4] {
+    [
+        Users::Table.into_iden(),
+        Retreats::Table.into_iden(),
+        RetreatReviews::Table.into_iden(),
+        RetreatGalleries::Table.into_iden(),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in soft_deleted_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table.clone())
+                        .add_column(
+                            ColumnDef::new(DeletedAt::DeletedAt)
+                                .timestamp_with_time_zone()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        let db = manager.get_connection();
+
+        // Only deleted rows are indexed, the retention purge is the one looking them up
+        for table in ["users", "retreats", "retreat_reviews", "retreat_galleries"] {
+            db.execute_unprepared(&format!(
+                r#"
+                CREATE INDEX IF NOT EXISTS "idx_{table}_deleted_at"
+                ON "{table}" ("deleted_at")
+                WHERE "deleted_at" IS NOT NULL;
+                "#
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // SQLite refuses to drop an indexed column
+        for table in ["users", "retreats", "retreat_reviews", "retreat_galleries"] {
+            db.execute_unprepared(&format!(r#"DROP INDEX IF EXISTS "idx_{table}_deleted_at";"#))
+                .await?;
+        }
+
+        for table in soft_deleted_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(DeletedAt::DeletedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum DeletedAt {
+    DeletedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Retreats {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum RetreatReviews {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum RetreatGalleries {
+    Table,
+}
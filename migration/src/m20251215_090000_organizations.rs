@@ -0,0 +1,295 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DatabaseBackend;
+
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Organizations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Organizations::OrganizationId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Organizations::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(Organizations::Slug)
+                            .string()
+                            .unique_key()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Organizations::Description).text().null())
+                    .col(ColumnDef::new(Organizations::Email).string().null())
+                    .col(ColumnDef::new(Organizations::Phone).string().null())
+                    .col(
+                        ColumnDef::new(Organizations::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Organizations::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Organizations::CreatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Organizations::UpdatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_organization_created_by")
+                            .from(Organizations::Table, Organizations::CreatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_organization_updated_by")
+                            .from(Organizations::Table, Organizations::UpdatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrganizationUsers::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OrganizationUsers::OrganizationUserId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationUsers::OrganizationId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationUsers::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    // Owners manage the organization and own every retreat in it
+                    .col(
+                        ColumnDef::new(OrganizationUsers::IsOwner)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(OrganizationUsers::Role).string().null())
+                    .col(
+                        ColumnDef::new(OrganizationUsers::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationUsers::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_organization_user_organization")
+                            .from(OrganizationUsers::Table, OrganizationUsers::OrganizationId)
+                            .to(Organizations::Table, Organizations::OrganizationId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_organization_user_user")
+                            .from(OrganizationUsers::Table, OrganizationUsers::UserId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_organization_users_membership")
+                    .table(OrganizationUsers::Table)
+                    .col(OrganizationUsers::OrganizationId)
+                    .col(OrganizationUsers::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_organization_users_user_id")
+                    .table(OrganizationUsers::Table)
+                    .col(OrganizationUsers::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Retreats may belong to one organization, and are kept when it is deleted
+        let mut organization_id: ColumnDef = ColumnDef::new(Retreats::OrganizationId)
+            .big_integer()
+            .null()
+            .to_owned();
+        if manager.get_database_backend() == DatabaseBackend::Sqlite {
+            // SQLite cannot add a constraint to an existing table, only a column carrying one
+            organization_id.extra(
+                r#"REFERENCES "organizations" ("organization_id") ON DELETE SET NULL ON UPDATE CASCADE"#,
+            );
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Retreats::Table)
+                        .add_column(organization_id)
+                        .to_owned(),
+                )
+                .await?;
+        } else {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Retreats::Table)
+                        .add_column(organization_id)
+                        .add_foreign_key(
+                            TableForeignKey::new()
+                                .name("fk_retreat_organization")
+                                .from_tbl(Retreats::Table)
+                                .from_col(Retreats::OrganizationId)
+                                .to_tbl(Organizations::Table)
+                                .to_col(Organizations::OrganizationId)
+                                .on_delete(ForeignKeyAction::SetNull)
+                                .on_update(ForeignKeyAction::Cascade),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_retreats_organization_id")
+                    .table(Retreats::Table)
+                    .col(Retreats::OrganizationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Attach trigger to both tables
+        attach_updated_at_trigger(manager, "organizations").await?;
+
+        attach_updated_at_trigger(manager, "organization_users").await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        detach_updated_at_trigger(manager, "organization_users").await?;
+        detach_updated_at_trigger(manager, "organizations").await?;
+        if manager.get_database_backend() == DatabaseBackend::Sqlite {
+            // The column's constraint goes with it, but its index has to go first
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name("idx_retreats_organization_id")
+                        .table(Retreats::Table)
+                        .to_owned(),
+                )
+                .await?;
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Retreats::Table)
+                        .drop_column(Retreats::OrganizationId)
+                        .to_owned(),
+                )
+                .await?;
+        } else {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Retreats::Table)
+                        .drop_foreign_key(Alias::new("fk_retreat_organization"))
+                        .drop_column(Retreats::OrganizationId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(OrganizationUsers::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Organizations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Organizations {
+    Table,
+    OrganizationId,
+    Name,
+    Slug,
+    Description,
+    Email,
+    Phone,
+    CreatedAt,
+    UpdatedAt,
+    CreatedBy,
+    UpdatedBy,
+}
+
+#[derive(DeriveIden)]
+enum OrganizationUsers {
+    Table,
+    OrganizationUserId,
+    OrganizationId,
+    UserId,
+    IsOwner,
+    Role,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Retreats {
+    Table,
+    OrganizationId,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
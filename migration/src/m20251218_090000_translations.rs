@@ -0,0 +1,375 @@
+use sea_orm_migration::prelude::*;
+
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The source columns hold the default locale, these tables every other one
+        manager
+            .create_table(
+                Table::create()
+                    .table(RetreatTranslations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RetreatTranslations::RetreatTranslationId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RetreatTranslations::RetreatId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RetreatTranslations::Locale)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RetreatTranslations::Name).string().null())
+                    .col(
+                        ColumnDef::new(RetreatTranslations::Description)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(RetreatTranslations::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(RetreatTranslations::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(RetreatTranslations::CreatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(RetreatTranslations::UpdatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_retreat_translation_retreat")
+                            .from(RetreatTranslations::Table, RetreatTranslations::RetreatId)
+                            .to(Retreats::Table, Retreats::RetreatId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_retreat_translation_created_by")
+                            .from(RetreatTranslations::Table, RetreatTranslations::CreatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_retreat_translation_updated_by")
+                            .from(RetreatTranslations::Table, RetreatTranslations::UpdatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_retreat_translations_locale")
+                    .table(RetreatTranslations::Table)
+                    .col(RetreatTranslations::RetreatId)
+                    .col(RetreatTranslations::Locale)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CategoryTranslations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CategoryTranslations::CategoryTranslationId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslations::CategoryId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslations::Locale)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CategoryTranslations::Name).string().null())
+                    .col(
+                        ColumnDef::new(CategoryTranslations::Description)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslations::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslations::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslations::CreatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslations::UpdatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_category_translation_category")
+                            .from(
+                                CategoryTranslations::Table,
+                                CategoryTranslations::CategoryId,
+                            )
+                            .to(Categories::Table, Categories::CategoryId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_category_translation_created_by")
+                            .from(CategoryTranslations::Table, CategoryTranslations::CreatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_category_translation_updated_by")
+                            .from(CategoryTranslations::Table, CategoryTranslations::UpdatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_category_translations_locale")
+                    .table(CategoryTranslations::Table)
+                    .col(CategoryTranslations::CategoryId)
+                    .col(CategoryTranslations::Locale)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(GalleryTranslations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GalleryTranslations::GalleryTranslationId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GalleryTranslations::GalleryId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GalleryTranslations::Locale)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(GalleryTranslations::Caption).string().null())
+                    .col(
+                        ColumnDef::new(GalleryTranslations::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(GalleryTranslations::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(GalleryTranslations::CreatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GalleryTranslations::UpdatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_gallery_translation_gallery")
+                            .from(GalleryTranslations::Table, GalleryTranslations::GalleryId)
+                            .to(RetreatGalleries::Table, RetreatGalleries::GalleryId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_gallery_translation_created_by")
+                            .from(GalleryTranslations::Table, GalleryTranslations::CreatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_gallery_translation_updated_by")
+                            .from(GalleryTranslations::Table, GalleryTranslations::UpdatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_gallery_translations_locale")
+                    .table(GalleryTranslations::Table)
+                    .col(GalleryTranslations::GalleryId)
+                    .col(GalleryTranslations::Locale)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Attach trigger to the three tables
+        for table in [
+            "retreat_translations",
+            "category_translations",
+            "gallery_translations",
+        ] {
+            attach_updated_at_trigger(manager, table).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            "gallery_translations",
+            "category_translations",
+            "retreat_translations",
+        ] {
+            detach_updated_at_trigger(manager, table).await?;
+        }
+        manager
+            .drop_table(Table::drop().table(GalleryTranslations::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CategoryTranslations::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(RetreatTranslations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RetreatTranslations {
+    Table,
+    RetreatTranslationId,
+    RetreatId,
+    Locale,
+    Name,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+    CreatedBy,
+    UpdatedBy,
+}
+
+#[derive(DeriveIden)]
+enum CategoryTranslations {
+    Table,
+    CategoryTranslationId,
+    CategoryId,
+    Locale,
+    Name,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+    CreatedBy,
+    UpdatedBy,
+}
+
+#[derive(DeriveIden)]
+enum GalleryTranslations {
+    Table,
+    GalleryTranslationId,
+    GalleryId,
+    Locale,
+    Caption,
+    CreatedAt,
+    UpdatedAt,
+    CreatedBy,
+    UpdatedBy,
+}
+
+#[derive(DeriveIden)]
+enum Retreats {
+    Table,
+    RetreatId,
+}
+
+#[derive(DeriveIden)]
+enum Categories {
+    Table,
+    CategoryId,
+}
+
+#[derive(DeriveIden)]
+enum RetreatGalleries {
+    Table,
+    GalleryId,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
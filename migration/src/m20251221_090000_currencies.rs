@@ -0,0 +1,158 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DatabaseBackend;
+
+use crate::updated_at::{attach_updated_at_trigger, detach_updated_at_trigger};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Existing budgets were entered as USD
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Retreats::Table)
+                    .add_column(
+                        ColumnDef::new(Retreats::Currency)
+                            .string_len(3)
+                            .not_null()
+                            .default("USD"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // SQLite keeps decimals as reals and rejects a precision above 16
+        let mut rate: ColumnDef = ColumnDef::new(ExchangeRates::Rate);
+        match manager.get_database_backend() {
+            DatabaseBackend::Sqlite => rate.decimal(),
+            _ => rate.decimal_len(20, 8),
+        };
+
+        // A rate is how many units of `currency` one unit of the base currency buys,
+        // from `effective_from` until the next rate of that currency
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExchangeRates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExchangeRates::ExchangeRateId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExchangeRates::Currency)
+                            .string_len(3)
+                            .not_null(),
+                    )
+                    .col(rate.not_null())
+                    .col(
+                        ColumnDef::new(ExchangeRates::EffectiveFrom)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExchangeRates::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ExchangeRates::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ExchangeRates::CreatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExchangeRates::UpdatedBy)
+                            .big_integer()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_exchange_rate_created_by")
+                            .from(ExchangeRates::Table, ExchangeRates::CreatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_exchange_rate_updated_by")
+                            .from(ExchangeRates::Table, ExchangeRates::UpdatedBy)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_exchange_rates_effective_from")
+                    .table(ExchangeRates::Table)
+                    .col(ExchangeRates::Currency)
+                    .col(ExchangeRates::EffectiveFrom)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Attach trigger to exchange_rates table
+        attach_updated_at_trigger(manager, "exchange_rates").await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        detach_updated_at_trigger(manager, "exchange_rates").await?;
+        manager
+            .drop_table(Table::drop().table(ExchangeRates::Table).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Retreats::Table)
+                    .drop_column(Retreats::Currency)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ExchangeRates {
+    Table,
+    ExchangeRateId,
+    Currency,
+    Rate,
+    EffectiveFrom,
+    CreatedAt,
+    UpdatedAt,
+    CreatedBy,
+    UpdatedBy,
+}
+
+#[derive(DeriveIden)]
+enum Retreats {
+    Table,
+    Currency,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
@@ -0,0 +1,114 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Rows are never changed once written, so there is no `updated_at`
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvents::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditEvents::AuditEventId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditEvents::ActorId).big_integer().null())
+                    .col(
+                        ColumnDef::new(AuditEvents::Entity)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AuditEvents::EntityId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    // The retreat the entity belongs to, kept after the retreat is purged
+                    .col(ColumnDef::new(AuditEvents::RetreatId).big_integer().null())
+                    // create, update, delete or restore
+                    .col(
+                        ColumnDef::new(AuditEvents::Action)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuditEvents::Diff).json_binary().not_null())
+                    .col(
+                        ColumnDef::new(AuditEvents::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_audit_event_actor_id")
+                            .from(AuditEvents::Table, AuditEvents::ActorId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_entity")
+                    .table(AuditEvents::Table)
+                    .col(AuditEvents::Entity)
+                    .col(AuditEvents::EntityId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_retreat_id")
+                    .table(AuditEvents::Table)
+                    .col(AuditEvents::RetreatId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_events_actor_id")
+                    .table(AuditEvents::Table)
+                    .col(AuditEvents::ActorId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditEvents {
+    Table,
+    AuditEventId,
+    ActorId,
+    Entity,
+    EntityId,
+    RetreatId,
+    Action,
+    Diff,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
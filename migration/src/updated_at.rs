@@ -0,0 +1,86 @@
+//! Keeps `updated_at` current on every update, whichever backend the schema lives on.
+//!
+//! Postgres shares one `set_updated_at()` function between `BEFORE UPDATE` triggers.
+//! SQLite has no trigger functions and cannot assign to `NEW`, so each table gets its
+//! own `AFTER UPDATE` trigger writing the column back. Recursive triggers are off by
+//! default there, so that write does not fire it again.
+
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DatabaseBackend};
+
+/// Creates `set_updated_at()`, which SQLite has no use for.
+pub async fn create_updated_at_function(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    if manager.get_database_backend() != DatabaseBackend::Postgres {
+        return Ok(());
+    }
+    manager
+        .get_connection()
+        .execute_unprepared(
+            r#"
+            CREATE OR REPLACE FUNCTION set_updated_at()
+            RETURNS TRIGGER AS $$
+            BEGIN
+                NEW.updated_at = NOW();
+                RETURN NEW;
+            END;
+            $$ language 'plpgsql';
+            "#,
+        )
+        .await?;
+    Ok(())
+}
+
+pub async fn drop_updated_at_function(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    if manager.get_database_backend() != DatabaseBackend::Postgres {
+        return Ok(());
+    }
+    manager
+        .get_connection()
+        .execute_unprepared(r#"DROP FUNCTION IF EXISTS set_updated_at;"#)
+        .await?;
+    Ok(())
+}
+
+pub async fn attach_updated_at_trigger(
+    manager: &SchemaManager<'_>,
+    table: &str,
+) -> Result<(), DbErr> {
+    let sql: String = match manager.get_database_backend() {
+        DatabaseBackend::Sqlite => format!(
+            r#"
+            CREATE TRIGGER "trigger_set_updated_at_{table}"
+            AFTER UPDATE ON "{table}"
+            FOR EACH ROW
+            BEGIN
+                UPDATE "{table}"
+                SET "updated_at" = strftime('%Y-%m-%d %H:%M:%f', 'now')
+                WHERE rowid = NEW.rowid;
+            END;
+            "#
+        ),
+        _ => format!(
+            r#"
+            CREATE TRIGGER trigger_set_updated_at
+            BEFORE UPDATE ON "{table}"
+            FOR EACH ROW
+            EXECUTE FUNCTION set_updated_at();
+            "#
+        ),
+    };
+    manager.get_connection().execute_unprepared(&sql).await?;
+    Ok(())
+}
+
+pub async fn detach_updated_at_trigger(
+    manager: &SchemaManager<'_>,
+    table: &str,
+) -> Result<(), DbErr> {
+    let sql: String = match manager.get_database_backend() {
+        DatabaseBackend::Sqlite => {
+            format!(r#"DROP TRIGGER IF EXISTS "trigger_set_updated_at_{table}";"#)
+        }
+        _ => format!(r#"DROP TRIGGER IF EXISTS trigger_set_updated_at ON "{table}";"#),
+    };
+    manager.get_connection().execute_unprepared(&sql).await?;
+    Ok(())
+}
@@ -0,0 +1,268 @@
+//! Change history. Every change to a retreat, its staff, gallery items and reviews, a
+//! category or a user is recorded with who made it and the fields it changed, by the same
+//! transaction as the change itself.
+
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ConnectionTrait, DbErr};
+use serde::Serialize;
+use serde_json::{Map, Value as JsonValue, json};
+
+use crate::{
+    entities_helper::{
+        AuditEventActiveModel, CategoryModel, RetreatGalleriesModel, RetreatModel,
+        RetreatReviewModel, RetreatUserModel, UserModel,
+    },
+    serializers::{
+        categories::ReadCategorySerializer, retreat_galleries::ReadRetreatGallerySerializer,
+        retreat_reviews::ReadRetreatReviewSerializer, retreats::ReadRetreatSerializer,
+        users::ReadUserSerializer,
+    },
+    utils::actor::current_actor,
+};
+
+/// Left out of the diffs, the event records who made the change anyway.
+const UNAUDITED_FIELDS: &[&str] = &[
+    "created_by",
+    "created_by_name",
+    "updated_by",
+    "updated_by_name",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    /// A soft delete as well, its diff then only sets `deleted_at`.
+    Delete,
+    Restore,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+        }
+    }
+}
+
+/// A model whose changes end up in the audit log.
+pub trait Audited {
+    /// The `entity` of its audit events.
+    const ENTITY: &'static str;
+
+    fn entity_id(&self) -> i64;
+
+    /// The retreat whose history shows its changes.
+    fn retreat_id(&self) -> Option<i64>;
+
+    /// The fields the diffs compare, as reads show them.
+    fn snapshot(&self) -> Map<String, JsonValue>;
+}
+
+fn fields<T: Serialize>(serializer: T) -> Map<String, JsonValue> {
+    let mut fields: Map<String, JsonValue> = match serde_json::to_value(serializer) {
+        Ok(JsonValue::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    for field in UNAUDITED_FIELDS {
+        fields.remove(*field);
+    }
+    fields
+}
+
+impl Audited for RetreatModel {
+    const ENTITY: &'static str = "retreat";
+
+    fn entity_id(&self) -> i64 {
+        self.retreat_id
+    }
+
+    fn retreat_id(&self) -> Option<i64> {
+        Some(self.retreat_id)
+    }
+
+    fn snapshot(&self) -> Map<String, JsonValue> {
+        let mut fields = fields(ReadRetreatSerializer::from(self.clone()));
+        fields.insert("deleted_at".to_string(), json!(self.deleted_at));
+        fields
+    }
+}
+
+impl Audited for RetreatUserModel {
+    const ENTITY: &'static str = "staff";
+
+    fn entity_id(&self) -> i64 {
+        self.retreat_user_id
+    }
+
+    fn retreat_id(&self) -> Option<i64> {
+        Some(self.retreat_id)
+    }
+
+    fn snapshot(&self) -> Map<String, JsonValue> {
+        fields(json!({
+            "user_id": self.user_id,
+            "is_owner": self.is_owner,
+            "role": self.role,
+        }))
+    }
+}
+
+impl Audited for RetreatGalleriesModel {
+    const ENTITY: &'static str = "gallery";
+
+    fn entity_id(&self) -> i64 {
+        self.gallery_id
+    }
+
+    fn retreat_id(&self) -> Option<i64> {
+        Some(self.retreat_id)
+    }
+
+    fn snapshot(&self) -> Map<String, JsonValue> {
+        let mut fields = fields(ReadRetreatGallerySerializer::from(self.clone()));
+        fields.insert("image_path".to_string(), json!(self.image_path));
+        fields.insert("deleted_at".to_string(), json!(self.deleted_at));
+        fields
+    }
+}
+
+impl Audited for RetreatReviewModel {
+    const ENTITY: &'static str = "review";
+
+    fn entity_id(&self) -> i64 {
+        self.review_id
+    }
+
+    fn retreat_id(&self) -> Option<i64> {
+        Some(self.retreat_id)
+    }
+
+    fn snapshot(&self) -> Map<String, JsonValue> {
+        let mut fields = fields(ReadRetreatReviewSerializer::from(self.clone()));
+        fields.insert("deleted_at".to_string(), json!(self.deleted_at));
+        fields
+    }
+}
+
+impl Audited for CategoryModel {
+    const ENTITY: &'static str = "category";
+
+    fn entity_id(&self) -> i64 {
+        self.category_id
+    }
+
+    fn retreat_id(&self) -> Option<i64> {
+        None
+    }
+
+    fn snapshot(&self) -> Map<String, JsonValue> {
+        fields(ReadCategorySerializer::from(self.clone()))
+    }
+}
+
+impl Audited for UserModel {
+    const ENTITY: &'static str = "user";
+
+    fn entity_id(&self) -> i64 {
+        self.user_id
+    }
+
+    fn retreat_id(&self) -> Option<i64> {
+        None
+    }
+
+    /// The password hash never ends up in the log.
+    fn snapshot(&self) -> Map<String, JsonValue> {
+        let mut fields = fields(ReadUserSerializer::from(self.clone()));
+        fields.insert("is_admin".to_string(), json!(self.is_admin));
+        fields.insert("deleted_at".to_string(), json!(self.deleted_at));
+        fields
+    }
+}
+
+/// `{"field": {"from": old, "to": new}}` for every field that differs, a missing side
+/// counts as `null`.
+pub fn diff(
+    before: Option<&Map<String, JsonValue>>,
+    after: Option<&Map<String, JsonValue>>,
+) -> Map<String, JsonValue> {
+    let empty: Map<String, JsonValue> = Map::new();
+    let (before, after) = (before.unwrap_or(&empty), after.unwrap_or(&empty));
+    let mut changes: Map<String, JsonValue> = Map::new();
+    for field in before.keys().chain(after.keys()) {
+        let from: &JsonValue = before.get(field).unwrap_or(&JsonValue::Null);
+        let to: &JsonValue = after.get(field).unwrap_or(&JsonValue::Null);
+        if from != to && !changes.contains_key(field) {
+            changes.insert(field.clone(), json!({"from": from, "to": to}));
+        }
+    }
+    changes
+}
+
+/// Writes the change from `before` to `after` to the audit log, acting for the user of
+/// the request. Pass the transaction of the change, so the event is kept if and only if
+/// the change commits. Updates that changed no audited field are not recorded.
+pub async fn record_change<C, M>(
+    database: &C,
+    action: AuditAction,
+    before: Option<&M>,
+    after: Option<&M>,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+    M: Audited,
+{
+    let Some(instance) = after.or(before) else {
+        return Ok(());
+    };
+    let changes: Map<String, JsonValue> = diff(
+        before.map(Audited::snapshot).as_ref(),
+        after.map(Audited::snapshot).as_ref(),
+    );
+    if changes.is_empty() && action == AuditAction::Update {
+        return Ok(());
+    }
+
+    insert_event(database, instance, action, changes).await
+}
+
+/// Password changes leave the snapshot of a user as it was, the hash is never logged, so
+/// they are recorded with a diff of their own that only tells the password changed.
+pub async fn record_password_change<C: ConnectionTrait>(
+    database: &C,
+    user: &UserModel,
+) -> Result<(), DbErr> {
+    let mut changes: Map<String, JsonValue> = Map::new();
+    changes.insert(
+        "password".to_string(),
+        json!({"from": "[redacted]", "to": "[redacted]"}),
+    );
+    insert_event(database, user, AuditAction::Update, changes).await
+}
+
+async fn insert_event<C, M>(
+    database: &C,
+    instance: &M,
+    action: AuditAction,
+    changes: Map<String, JsonValue>,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+    M: Audited,
+{
+    AuditEventActiveModel {
+        actor_id: Set(current_actor()),
+        entity: Set(M::ENTITY.to_string()),
+        entity_id: Set(instance.entity_id()),
+        retreat_id: Set(instance.retreat_id()),
+        action: Set(action.as_str().to_string()),
+        diff: Set(JsonValue::Object(changes)),
+        ..Default::default()
+    }
+    .insert(database)
+    .await?;
+    Ok(())
+}
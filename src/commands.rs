@@ -0,0 +1,435 @@
+//! Operational subcommands of the server binary. Each loads the same config and builds
+//! the same `AppState` as the server, so the image needs nothing else.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QuerySelect,
+};
+use tokio::{
+    fs,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    entities_helper::{
+        CategoryActiveModel, CategoryColumn, CategoryEntity, GalleryCategoriesActiveModel,
+        GalleryCategoriesColumn, GalleryCategoriesEntity, RetreatColumn, RetreatEntity,
+        RetreatGalleriesColumn, RetreatGalleriesEntity, UserColumn, UserEntity, UserModel,
+    },
+    fixtures::{DEMO_PASSWORD, Fixtures, LoadReport, demo_fixtures, load_fixtures, read_fixtures},
+    routes::health::{check_database, check_migrations, check_replica, check_upload_dir},
+    serializers::health::{ComponentHealthSerializer, HealthStatus},
+    services::users,
+    state::AppState,
+    utils::{
+        database::init_statement_logging, password::create_password, soft_delete::SoftDelete,
+    },
+};
+
+pub const USAGE: &str = "\
+Usage: my_retreat_nest [COMMAND]
+
+Commands:
+  serve                       Run the HTTP server (the default)
+  worker                      Run only the background job workers
+  migrate up [STEPS]          Apply pending migrations, all of them unless STEPS is given
+  migrate down [STEPS]        Roll back the last STEPS migrations, one by default
+  migrate status              List migrations and whether they are applied
+  create-admin EMAIL NAME     Create an admin, or promote an existing user, password read from stdin
+  seed [FILE | demo [SCALE]]  Insert the default categories, then load a .json/.toml fixture
+                              file or generate SCALE demo retreats (200 by default)
+  reset-password EMAIL        Set a user's password from stdin, or a generated one if empty
+  gc-uploads [--dry-run]      Remove uploaded files no row refers to any more
+  check-config                Validate the config, database, migrations and upload directory";
+
+/// Retreats generated by `seed demo` without a SCALE.
+const DEMO_SCALE: usize = 200;
+
+/// Sample gallery images of `seed demo`, relative to the working directory.
+const DEMO_IMAGE_DIR: &str = "fixtures/images";
+
+/// Uploads younger than this may belong to a request that has not saved its row yet.
+const GC_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+async fn connect(config: Config) -> Result<AppState, String> {
+    init_statement_logging(&config);
+    AppState::try_new(Arc::new(config))
+        .await
+        .map_err(|e| format!("Failed to connect to the database: {e}"))
+}
+
+/// Reads one line from stdin, prompting on stderr so the output stays pipeable.
+async fn read_line(prompt: &str) -> Result<String, String> {
+    let mut stderr = tokio::io::stderr();
+    stderr.write_all(prompt.as_bytes()).await.ok();
+    stderr.flush().await.ok();
+
+    let mut line: String = String::new();
+    BufReader::new(tokio::io::stdin())
+        .read_line(&mut line)
+        .await
+        .map_err(|e| format!("Failed to read stdin: {e}"))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn parse_steps(steps: Option<&str>) -> Result<Option<u32>, String> {
+    steps
+        .map(|steps| {
+            steps
+                .parse::<u32>()
+                .map_err(|_| format!("STEPS must be a positive number, got `{steps}`"))
+        })
+        .transpose()
+}
+
+pub async fn migrate(config: Config, args: &[&str]) -> Result<(), String> {
+    let state: AppState = connect(config).await?;
+    let database = state.database.primary();
+    match args {
+        ["up"] | ["up", _] => {
+            let steps: Option<u32> = parse_steps(args.get(1).copied())?;
+            Migrator::up(database, steps)
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("Migrations applied.");
+        }
+        ["down"] | ["down", _] => {
+            let steps: u32 = parse_steps(args.get(1).copied())?.unwrap_or(1);
+            Migrator::down(database, Some(steps))
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("Rolled back {steps} migration(s).");
+        }
+        ["status"] => {
+            let migrations = Migrator::get_migration_with_status(database)
+                .await
+                .map_err(|e| e.to_string())?;
+            for migration in migrations {
+                println!("{:<8} {}", migration.status().to_string(), migration.name());
+            }
+        }
+        _ => return Err("Expected `migrate up|down [STEPS]` or `migrate status`.".to_string()),
+    }
+    Ok(())
+}
+
+pub async fn create_admin(config: Config, email: &str, name: &str) -> Result<(), String> {
+    let state: AppState = connect(config).await?;
+    let existing: Option<UserModel> = UserEntity::find_active()
+        .filter(UserColumn::Email.eq(email))
+        .one(&state.database)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // An existing account keeps its password and only gains admin rights
+    if let Some(user) = existing {
+        users::grant_admin(&state.database, user)
+            .await
+            .map_err(|e| e.to_string())?;
+        println!("{email} is now an admin.");
+        return Ok(());
+    }
+
+    let password: String = read_line("Password: ").await?;
+    if password.is_empty() {
+        return Err("The password must not be empty.".to_string());
+    }
+    let hashed_password: String = create_password(&password, &state.config.password_salt)
+        .await
+        .map_err(|e| e.to_string())?;
+    users::create_admin(
+        &state.database,
+        name.to_string(),
+        email.to_string(),
+        hashed_password,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    println!("Admin {email} created.");
+    Ok(())
+}
+
+/// The categories and gallery categories every install starts with.
+async fn seed_defaults(state: &AppState) -> Result<usize, String> {
+    let mut created: usize = 0;
+
+    for (name, description) in [
+        ("Yoga", "Retreats built around daily yoga practice."),
+        ("Meditation", "Silent and guided meditation retreats."),
+        ("Wellness", "Spa, Ayurveda and detox retreats."),
+        (
+            "Trekking",
+            "Retreats combined with treks in the hills and mountains.",
+        ),
+        ("Spiritual", "Monastery and ashram stays."),
+    ] {
+        let exists: bool = CategoryEntity::find()
+            .filter(CategoryColumn::Name.eq(name))
+            .one(&state.database)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some();
+        if !exists {
+            CategoryActiveModel {
+                name: Set(name.to_string()),
+                description: Set(Some(description.to_string())),
+                ..Default::default()
+            }
+            .insert(&state.database)
+            .await
+            .map_err(|e| e.to_string())?;
+            created += 1;
+        }
+    }
+
+    for name in ["Rooms", "Food", "Activities", "Surroundings"] {
+        let exists: bool = GalleryCategoriesEntity::find()
+            .filter(GalleryCategoriesColumn::Name.eq(name))
+            .one(&state.database)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some();
+        if !exists {
+            GalleryCategoriesActiveModel {
+                name: Set(name.to_string()),
+                ..Default::default()
+            }
+            .insert(&state.database)
+            .await
+            .map_err(|e| e.to_string())?;
+            created += 1;
+        }
+    }
+
+    Ok(created)
+}
+
+/// Sample images for the demo profile, the image files directly in `dir`.
+async fn sample_images(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut images: Vec<PathBuf> = Vec::new();
+    if !fs::try_exists(dir).await.unwrap_or(false) {
+        return Ok(images);
+    }
+    let mut entries = fs::read_dir(dir)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?
+    {
+        let path: PathBuf = entry.path();
+        let is_image: bool = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                ["png", "jpg", "jpeg", "webp"].contains(&extension.to_lowercase().as_str())
+            });
+        if is_image {
+            images.push(path);
+        }
+    }
+    // Sorted, so the same scale picks the same images
+    images.sort();
+    Ok(images)
+}
+
+pub async fn seed(config: Config, args: &[&str]) -> Result<(), String> {
+    let (fixtures, base_dir): (Option<Fixtures>, PathBuf) = match args {
+        [] => (None, PathBuf::new()),
+        ["demo"] | ["demo", _] => {
+            let scale: usize = match args.get(1) {
+                Some(scale) => scale
+                    .parse()
+                    .map_err(|_| format!("SCALE must be a positive number, got `{scale}`"))?,
+                None => DEMO_SCALE,
+            };
+            let images: Vec<PathBuf> = sample_images(Path::new(DEMO_IMAGE_DIR)).await?;
+            if images.is_empty() {
+                eprintln!("Warning: no images in {DEMO_IMAGE_DIR}, generating no gallery.");
+            }
+            (Some(demo_fixtures(scale, &images)), PathBuf::new())
+        }
+        [file] => {
+            let path: &Path = Path::new(file);
+            let fixtures: Fixtures = read_fixtures(path).await?;
+            let base_dir: PathBuf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            (Some(fixtures), base_dir)
+        }
+        _ => return Err("Expected `seed [FILE | demo [SCALE]]`.".to_string()),
+    };
+    let state: AppState = connect(config).await?;
+
+    let created: usize = seed_defaults(&state).await?;
+    println!("Seeded {created} default record(s), existing ones were left as they are.");
+    if let Some(fixtures) = fixtures {
+        let report: LoadReport = load_fixtures(&state, &fixtures, &base_dir).await?;
+        println!("Loaded fixtures: {report}, existing ones were left as they are.");
+        if args.first() == Some(&"demo") {
+            println!("Demo users sign in with the password `{DEMO_PASSWORD}`.");
+        }
+    }
+    Ok(())
+}
+
+pub async fn reset_password(config: Config, email: &str) -> Result<(), String> {
+    let state: AppState = connect(config).await?;
+    let user: UserModel = UserEntity::find_active()
+        .filter(UserColumn::Email.eq(email))
+        .one(&state.database)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No user with the email {email}."))?;
+
+    let mut password: String = read_line("New password (leave empty to generate one): ").await?;
+    let generated: bool = password.is_empty();
+    if generated {
+        password = Uuid::new_v4().simple().to_string();
+    }
+    let hashed_password: String = create_password(&password, &state.config.password_salt)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    users::reset_password(&state.database, user, hashed_password)
+        .await
+        .map_err(|e| e.to_string())?;
+    if generated {
+        println!("Password of {email} reset to: {password}");
+    } else {
+        println!("Password of {email} reset.");
+    }
+    Ok(())
+}
+
+/// Every file below `dir`, as paths relative to `root`.
+async fn list_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let mut entries = fs::read_dir(dir)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?
+    {
+        let path: PathBuf = entry.path();
+        let file_type = entry.file_type().await.map_err(|e| e.to_string())?;
+        if file_type.is_dir() {
+            Box::pin(list_files(root, &path, files)).await?;
+        } else if file_type.is_file() {
+            files.push(path.strip_prefix(root).unwrap_or(&path).to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+pub async fn gc_uploads(config: Config, args: &[&str]) -> Result<(), String> {
+    let dry_run: bool = match args {
+        [] => false,
+        ["--dry-run"] => true,
+        _ => return Err("Expected `gc-uploads [--dry-run]`.".to_string()),
+    };
+    let state: AppState = connect(config).await?;
+    let upload_dir: &Path = state.storage.upload_dir();
+
+    // Soft deleted rows still count, restoring them must bring their files back
+    let mut referenced: HashSet<PathBuf> = HashSet::new();
+    let image_paths: Vec<String> = RetreatGalleriesEntity::find()
+        .select_only()
+        .column(RetreatGalleriesColumn::ImagePath)
+        .into_tuple()
+        .all(&state.database)
+        .await
+        .map_err(|e| e.to_string())?;
+    let logos: Vec<Option<String>> = RetreatEntity::find()
+        .select_only()
+        .column(RetreatColumn::Logo)
+        .into_tuple()
+        .all(&state.database)
+        .await
+        .map_err(|e| e.to_string())?;
+    referenced.extend(image_paths.into_iter().map(PathBuf::from));
+    referenced.extend(logos.into_iter().flatten().map(PathBuf::from));
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    if fs::try_exists(upload_dir).await.unwrap_or(false) {
+        list_files(upload_dir, upload_dir, &mut files).await?;
+    }
+
+    let mut removed: usize = 0;
+    for file in files.iter().filter(|file| !referenced.contains(*file)) {
+        let path: PathBuf = upload_dir.join(file);
+        let is_recent: bool = fs::metadata(&path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_none_or(|age| age < GC_MIN_AGE);
+        if is_recent {
+            continue;
+        }
+        if dry_run {
+            println!("Would remove {}", file.display());
+        } else {
+            fs::remove_file(&path)
+                .await
+                .map_err(|e| format!("Failed to remove {}: {e}", file.display()))?;
+            println!("Removed {}", file.display());
+        }
+        removed += 1;
+    }
+
+    let verb: &str = if dry_run {
+        "would be removed"
+    } else {
+        "removed"
+    };
+    println!(
+        "{removed} of {} file(s) {verb}, files younger than an hour are kept.",
+        files.len()
+    );
+    Ok(())
+}
+
+fn report(name: &str, component: &ComponentHealthSerializer) -> bool {
+    let is_up: bool = component.status == HealthStatus::Up;
+    println!(
+        "{:<12} {:<4} {}",
+        name,
+        if is_up { "ok" } else { "FAIL" },
+        component.message.as_deref().unwrap_or_default()
+    );
+    is_up
+}
+
+/// The config itself was validated on load, this checks what it points at.
+pub async fn check_config(config: Config) -> Result<(), String> {
+    println!("config       ok   profile {}", config.profile);
+    let upload_dir: ComponentHealthSerializer = check_upload_dir(&config.upload_dir, &config).await;
+    let upload_dir_ok: bool = report("upload_dir", &upload_dir);
+
+    let state: AppState = connect(config).await?;
+    let database: ComponentHealthSerializer = check_database(state.database.primary()).await;
+    let migrations: ComponentHealthSerializer =
+        check_migrations(state.database.primary()).await;
+    let database_ok: bool = report("database", &database);
+    let replica_ok: bool = match check_replica(state.database.replica()).await {
+        Some(replica) => report("replica", &replica),
+        None => true,
+    };
+    let migrations_ok: bool = report("migrations", &migrations);
+
+    if upload_dir_ok && database_ok && replica_ok && migrations_ok {
+        Ok(())
+    } else {
+        Err("Some checks failed.".to_string())
+    }
+}
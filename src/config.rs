@@ -0,0 +1,768 @@
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use axum::http::{HeaderName, HeaderValue};
+use dotenvy::dotenv;
+
+use crate::utils::currency::is_currency_code;
+
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Every key the config understands, with the default used when no layer sets it.
+/// A `None` default means the key is required (unless the profile supplies one).
+const KEYS: &[(&str, Option<&str>)] = &[
+    ("SERVER_HOST", Some("0.0.0.0")),
+    ("SERVER_PORT", Some("8000")),
+    ("DATABASE_URL", None),
+    ("DATABASE_REPLICA_URL", Some("")),
+    ("DATABASE_MAX_CONNECTIONS", Some("10")),
+    ("DATABASE_MIN_CONNECTIONS", Some("0")),
+    ("DATABASE_CONNECT_TIMEOUT_IN_SEC", Some("8")),
+    ("DATABASE_ACQUIRE_TIMEOUT_IN_SEC", Some("8")),
+    ("DATABASE_IDLE_TIMEOUT_IN_SEC", Some("600")),
+    ("DATABASE_MAX_LIFETIME_IN_SEC", Some("1800")),
+    ("DATABASE_LOG_STATEMENTS", Some("false")),
+    ("DATABASE_SLOW_STATEMENT_IN_MS", Some("0")),
+    ("DATABASE_REPLICA_STICKINESS_IN_SEC", Some("5")),
+    ("PASSWORD_SALT", None),
+    ("JWT_ACCESS_KEY", None),
+    ("JWT_ACCESS_LIFETIME_IN_MIN", Some("10")),
+    ("JWT_REFRESH_KEY", None),
+    ("JWT_REFRESH_LIFETIME_IN_MIN", Some("60")),
+    ("UPLOAD_DIR", None),
+    ("UPLOAD_MIN_FREE_SPACE_IN_MB", Some("100")),
+    ("SHUTDOWN_DRAIN_TIMEOUT_IN_SEC", Some("30")),
+    ("SHUTDOWN_PRE_DRAIN_DELAY_IN_SEC", Some("5")),
+    ("CORS_ALLOWED_ORIGINS", Some("")),
+    ("CORS_ALLOW_CREDENTIALS", Some("false")),
+    ("CORS_EXPOSED_HEADERS", Some("ETag")),
+    ("CORS_MAX_AGE_IN_SEC", Some("3600")),
+    ("HSTS_MAX_AGE_IN_SEC", Some("31536000")),
+    ("FRAME_OPTIONS", Some("DENY")),
+    (
+        "CONTENT_SECURITY_POLICY",
+        Some(
+            "default-src 'self'; img-src 'self' data:; style-src 'self' 'unsafe-inline'; frame-ancestors 'none'",
+        ),
+    ),
+    ("MAX_BODY_SIZE_IN_KB", Some("1024")),
+    ("UPLOAD_MAX_BODY_SIZE_IN_MB", Some("10")),
+    ("REQUEST_TIMEOUT_IN_SEC", Some("30")),
+    ("UPLOAD_REQUEST_TIMEOUT_IN_SEC", Some("120")),
+    ("IDEMPOTENCY_KEY_TTL_IN_HOURS", Some("24")),
+    ("IDEMPOTENCY_CLEANUP_INTERVAL_IN_MIN", Some("60")),
+    ("GRAPHQL_MAX_COMPLEXITY", Some("500")),
+    ("GRAPHQL_MAX_DEPTH", Some("10")),
+    ("GRAPHQL_PLAYGROUND", Some("false")),
+    ("WEBHOOK_MAX_ATTEMPTS", Some("8")),
+    ("WEBHOOK_RETRY_BASE_IN_SEC", Some("30")),
+    ("WEBHOOK_RETRY_MAX_IN_SEC", Some("21600")),
+    ("WEBHOOK_TIMEOUT_IN_SEC", Some("10")),
+    ("WEBHOOK_POLL_INTERVAL_IN_SEC", Some("5")),
+    ("JOB_QUEUES", Some("default:4,media:2")),
+    ("JOB_RUN_IN_SERVER", Some("false")),
+    ("JOB_POLL_INTERVAL_IN_SEC", Some("2")),
+    ("JOB_RETRY_BASE_IN_SEC", Some("10")),
+    ("JOB_RETRY_MAX_IN_SEC", Some("3600")),
+    ("JOB_LOCK_TIMEOUT_IN_SEC", Some("300")),
+    ("OUTBOX_POLL_INTERVAL_IN_SEC", Some("1")),
+    ("OUTBOX_MAX_ATTEMPTS", Some("10")),
+    ("LIVE_CHANNEL_CAPACITY", Some("1024")),
+    ("LIVE_KEEP_ALIVE_IN_SEC", Some("15")),
+    ("LIVE_MAX_TOPICS", Some("20")),
+    ("CACHE_ENABLED", Some("true")),
+    ("CACHE_TTL_IN_SEC", Some("30")),
+    ("CACHE_MAX_ENTRIES", Some("1000")),
+    ("CACHE_MAX_SIZE_IN_MB", Some("32")),
+    ("SOFT_DELETE_RETENTION_IN_DAYS", Some("30")),
+    ("SOFT_DELETE_PURGE_INTERVAL_IN_MIN", Some("60")),
+    ("SUPPORTED_LOCALES", Some("en,ne,hi")),
+    ("DEFAULT_LOCALE", Some("en")),
+    ("BASE_CURRENCY", Some("USD")),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    Dev,
+    Test,
+    Prod,
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::Dev => "dev",
+            Profile::Test => "test",
+            Profile::Prod => "prod",
+        }
+    }
+
+    /// Defaults that only make sense outside production.
+    fn defaults(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Profile::Dev => &[
+                ("SERVER_HOST", "127.0.0.1"),
+                ("UPLOAD_DIR", "uploads"),
+                ("CORS_ALLOWED_ORIGINS", "*"),
+                ("HSTS_MAX_AGE_IN_SEC", "0"),
+                ("GRAPHQL_PLAYGROUND", "true"),
+                ("JOB_RUN_IN_SERVER", "true"),
+            ],
+            Profile::Test => &[
+                ("SERVER_HOST", "127.0.0.1"),
+                ("UPLOAD_DIR", "uploads/test"),
+                ("CORS_ALLOWED_ORIGINS", "*"),
+                ("HSTS_MAX_AGE_IN_SEC", "0"),
+                ("GRAPHQL_PLAYGROUND", "true"),
+                ("JOB_RUN_IN_SERVER", "true"),
+            ],
+            Profile::Prod => &[],
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "dev" | "development" => Ok(Profile::Dev),
+            "test" => Ok(Profile::Test),
+            "prod" | "production" => Ok(Profile::Prod),
+            other => Err(format!(
+                "APP_PROFILE must be one of dev, test or prod, got `{other}`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub profile: Profile,
+    pub server_host: String,
+    pub server_port: u16,
+    pub database_url: String,
+    /// Serves the reads of `GET` requests when set, see `utils::database`.
+    pub database_replica_url: Option<String>,
+    /// Ignored for SQLite, whose pool keeps a single connection.
+    pub database_max_connections: u32,
+    pub database_min_connections: u32,
+    pub database_connect_timeout_in_sec: u64,
+    pub database_acquire_timeout_in_sec: u64,
+    pub database_idle_timeout_in_sec: u64,
+    pub database_max_lifetime_in_sec: u64,
+    /// Print every statement to stderr.
+    pub database_log_statements: bool,
+    /// Print statements slower than this, `0` turns it off.
+    pub database_slow_statement_in_ms: u64,
+    /// How long a caller's reads stay on the primary after they wrote.
+    pub database_replica_stickiness_in_sec: u64,
+    pub password_salt: String,
+    pub jwt_access_key: String,
+    pub jwt_access_lifetime_in_min: u64,
+    pub jwt_refresh_key: String,
+    pub jwt_refresh_lifetime_in_min: u64,
+    pub upload_dir: PathBuf,
+    pub upload_min_free_space_in_mb: u64,
+    pub shutdown_drain_timeout_in_sec: u64,
+    /// How long readiness fails before the listener closes, so load balancers stop
+    /// routing here first.
+    pub shutdown_pre_drain_delay_in_sec: u64,
+    /// `*` allows any origin.
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_allow_credentials: bool,
+    pub cors_exposed_headers: Vec<String>,
+    pub cors_max_age_in_sec: u64,
+    /// `0` disables the `Strict-Transport-Security` header.
+    pub hsts_max_age_in_sec: u64,
+    pub frame_options: String,
+    /// Sent with HTML responses only, routes serving their own may set another.
+    pub content_security_policy: String,
+    pub max_body_size_in_kb: usize,
+    pub upload_max_body_size_in_mb: usize,
+    pub request_timeout_in_sec: u64,
+    pub upload_request_timeout_in_sec: u64,
+    pub idempotency_key_ttl_in_hours: u64,
+    pub idempotency_cleanup_interval_in_min: u64,
+    pub graphql_max_complexity: usize,
+    pub graphql_max_depth: usize,
+    pub graphql_playground: bool,
+    /// Deliveries still failing after this many attempts are marked dead.
+    pub webhook_max_attempts: u32,
+    pub webhook_retry_base_in_sec: u64,
+    pub webhook_retry_max_in_sec: u64,
+    pub webhook_timeout_in_sec: u64,
+    pub webhook_poll_interval_in_sec: u64,
+    /// Queue names with how many jobs of each may run at once.
+    pub job_queues: Vec<(String, usize)>,
+    /// Run the job workers inside the server, instead of a separate `worker` process.
+    pub job_run_in_server: bool,
+    pub job_poll_interval_in_sec: u64,
+    pub job_retry_base_in_sec: u64,
+    pub job_retry_max_in_sec: u64,
+    /// A running job is assumed abandoned once locked this long, and is cut off after it.
+    pub job_lock_timeout_in_sec: u64,
+    pub outbox_poll_interval_in_sec: u64,
+    /// Attempts before an event that keeps failing in a subscriber is no longer relayed.
+    pub outbox_max_attempts: u32,
+    /// Messages buffered per stream, a stream further behind skips ahead.
+    pub live_channel_capacity: usize,
+    pub live_keep_alive_in_sec: u64,
+    /// Topics one stream may subscribe to.
+    pub live_max_topics: usize,
+    /// Cache GET responses of the public catalog in memory.
+    pub cache_enabled: bool,
+    pub cache_ttl_in_sec: u64,
+    pub cache_max_entries: usize,
+    pub cache_max_size_in_mb: usize,
+    /// Deleted rows can be restored for this long, then they are purged for good.
+    pub soft_delete_retention_in_days: u64,
+    pub soft_delete_purge_interval_in_min: u64,
+    /// Locales content can be translated to and requested in, lowercase.
+    pub supported_locales: Vec<String>,
+    /// The locale of the source columns, served when no translation matches.
+    pub default_locale: String,
+    /// Exchange rates are quoted against this currency, uppercase ISO 4217.
+    pub base_currency: String,
+}
+
+/// All problems found while loading the config, reported together.
+#[derive(Debug, Clone)]
+pub struct ConfigError {
+    pub profile: String,
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Invalid configuration (profile: {}), {} problem(s) found:",
+            self.profile,
+            self.problems.len()
+        )?;
+        for problem in &self.problems {
+            writeln!(f, "  - {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Flat `KEY -> value` view of the merged layers, remembering where each value came from.
+#[derive(Debug, Default)]
+struct Layers {
+    values: BTreeMap<String, (String, String)>,
+}
+
+impl Layers {
+    fn set(&mut self, key: &str, value: String, source: &str) {
+        self.values
+            .insert(key.to_string(), (value, source.to_string()));
+    }
+
+    fn get(&self, key: &str) -> Option<&(String, String)> {
+        self.values.get(key)
+    }
+}
+
+impl Config {
+    /// Loads the config from the process environment, layering defaults, the TOML file,
+    /// environment variables and `*_FILE` secrets in that order.
+    pub fn load() -> Result<Self, ConfigError> {
+        // Load .env file (if present)
+        dotenv().ok();
+        Self::from_source(|key| env::var(key).ok())
+    }
+
+    /// Same as `load`, but reads variables through `lookup` instead of the process environment.
+    pub fn from_source<F>(lookup: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut problems: Vec<String> = Vec::new();
+
+        let profile: Profile = match lookup("APP_PROFILE") {
+            Some(value) => value.parse().unwrap_or_else(|e: String| {
+                problems.push(e);
+                Profile::Dev
+            }),
+            None => Profile::Dev,
+        };
+
+        let mut layers: Layers = Layers::default();
+
+        // 1. Defaults, then profile specific defaults
+        for (key, default) in KEYS {
+            if let Some(default) = default {
+                layers.set(key, default.to_string(), "default");
+            }
+        }
+        for (key, default) in profile.defaults() {
+            layers.set(key, default.to_string(), &format!("{profile} default"));
+        }
+
+        // 2. TOML file
+        let (config_file, explicit) = match lookup("APP_CONFIG_FILE") {
+            Some(path) => (PathBuf::from(path), true),
+            None => (PathBuf::from(DEFAULT_CONFIG_FILE), false),
+        };
+        if config_file.exists() {
+            load_file(&config_file, profile, &mut layers, &mut problems);
+        } else if explicit {
+            problems.push(format!(
+                "APP_CONFIG_FILE points to {}, which does not exist",
+                config_file.display()
+            ));
+        }
+
+        // 3. Environment variables, 4. `*_FILE` secrets
+        for (key, _) in KEYS {
+            let file_key = format!("{key}_FILE");
+            match (lookup(key), lookup(&file_key)) {
+                (Some(_), Some(_)) => {
+                    problems.push(format!("{key} and {file_key} are both set, use only one"))
+                }
+                (Some(value), None) => layers.set(key, value, "environment"),
+                (None, Some(path)) => match fs::read_to_string(&path) {
+                    Ok(value) => layers.set(key, value.trim_end().to_string(), &file_key),
+                    Err(e) => problems.push(format!("{file_key}: failed to read {path}: {e}")),
+                },
+                (None, None) => {}
+            }
+        }
+
+        let mut parser = Parser {
+            layers: &layers,
+            problems: &mut problems,
+        };
+        let config = Config {
+            profile,
+            server_host: parser.string("SERVER_HOST"),
+            server_port: parser.parse("SERVER_PORT"),
+            database_url: parser.string("DATABASE_URL"),
+            database_replica_url: Some(parser.string("DATABASE_REPLICA_URL"))
+                .filter(|url| !url.is_empty()),
+            database_max_connections: parser.parse("DATABASE_MAX_CONNECTIONS"),
+            database_min_connections: parser.parse("DATABASE_MIN_CONNECTIONS"),
+            database_connect_timeout_in_sec: parser.parse("DATABASE_CONNECT_TIMEOUT_IN_SEC"),
+            database_acquire_timeout_in_sec: parser.parse("DATABASE_ACQUIRE_TIMEOUT_IN_SEC"),
+            database_idle_timeout_in_sec: parser.parse("DATABASE_IDLE_TIMEOUT_IN_SEC"),
+            database_max_lifetime_in_sec: parser.parse("DATABASE_MAX_LIFETIME_IN_SEC"),
+            database_log_statements: parser.parse("DATABASE_LOG_STATEMENTS"),
+            database_slow_statement_in_ms: parser.parse("DATABASE_SLOW_STATEMENT_IN_MS"),
+            database_replica_stickiness_in_sec: parser.parse(
+                "DATABASE_REPLICA_STICKINESS_IN_SEC",
+            ),
+            password_salt: parser.string("PASSWORD_SALT"),
+            jwt_access_key: parser.string("JWT_ACCESS_KEY"),
+            jwt_access_lifetime_in_min: parser.parse("JWT_ACCESS_LIFETIME_IN_MIN"),
+            jwt_refresh_key: parser.string("JWT_REFRESH_KEY"),
+            jwt_refresh_lifetime_in_min: parser.parse("JWT_REFRESH_LIFETIME_IN_MIN"),
+            upload_dir: PathBuf::from(parser.string("UPLOAD_DIR")),
+            upload_min_free_space_in_mb: parser.parse("UPLOAD_MIN_FREE_SPACE_IN_MB"),
+            shutdown_drain_timeout_in_sec: parser.parse("SHUTDOWN_DRAIN_TIMEOUT_IN_SEC"),
+            shutdown_pre_drain_delay_in_sec: parser.parse("SHUTDOWN_PRE_DRAIN_DELAY_IN_SEC"),
+            cors_allowed_origins: parser.list("CORS_ALLOWED_ORIGINS"),
+            cors_allow_credentials: parser.parse("CORS_ALLOW_CREDENTIALS"),
+            cors_exposed_headers: parser.list("CORS_EXPOSED_HEADERS"),
+            cors_max_age_in_sec: parser.parse("CORS_MAX_AGE_IN_SEC"),
+            hsts_max_age_in_sec: parser.parse("HSTS_MAX_AGE_IN_SEC"),
+            frame_options: parser.string("FRAME_OPTIONS").to_ascii_uppercase(),
+            content_security_policy: parser.string("CONTENT_SECURITY_POLICY"),
+            max_body_size_in_kb: parser.parse("MAX_BODY_SIZE_IN_KB"),
+            upload_max_body_size_in_mb: parser.parse("UPLOAD_MAX_BODY_SIZE_IN_MB"),
+            request_timeout_in_sec: parser.parse("REQUEST_TIMEOUT_IN_SEC"),
+            upload_request_timeout_in_sec: parser.parse("UPLOAD_REQUEST_TIMEOUT_IN_SEC"),
+            idempotency_key_ttl_in_hours: parser.parse("IDEMPOTENCY_KEY_TTL_IN_HOURS"),
+            idempotency_cleanup_interval_in_min: parser.parse(
+                "IDEMPOTENCY_CLEANUP_INTERVAL_IN_MIN",
+            ),
+            graphql_max_complexity: parser.parse("GRAPHQL_MAX_COMPLEXITY"),
+            graphql_max_depth: parser.parse("GRAPHQL_MAX_DEPTH"),
+            graphql_playground: parser.parse("GRAPHQL_PLAYGROUND"),
+            webhook_max_attempts: parser.parse("WEBHOOK_MAX_ATTEMPTS"),
+            webhook_retry_base_in_sec: parser.parse("WEBHOOK_RETRY_BASE_IN_SEC"),
+            webhook_retry_max_in_sec: parser.parse("WEBHOOK_RETRY_MAX_IN_SEC"),
+            webhook_timeout_in_sec: parser.parse("WEBHOOK_TIMEOUT_IN_SEC"),
+            webhook_poll_interval_in_sec: parser.parse("WEBHOOK_POLL_INTERVAL_IN_SEC"),
+            job_queues: parser.queues("JOB_QUEUES"),
+            job_run_in_server: parser.parse("JOB_RUN_IN_SERVER"),
+            job_poll_interval_in_sec: parser.parse("JOB_POLL_INTERVAL_IN_SEC"),
+            job_retry_base_in_sec: parser.parse("JOB_RETRY_BASE_IN_SEC"),
+            job_retry_max_in_sec: parser.parse("JOB_RETRY_MAX_IN_SEC"),
+            job_lock_timeout_in_sec: parser.parse("JOB_LOCK_TIMEOUT_IN_SEC"),
+            outbox_poll_interval_in_sec: parser.parse("OUTBOX_POLL_INTERVAL_IN_SEC"),
+            outbox_max_attempts: parser.parse("OUTBOX_MAX_ATTEMPTS"),
+            live_channel_capacity: parser.parse("LIVE_CHANNEL_CAPACITY"),
+            live_keep_alive_in_sec: parser.parse("LIVE_KEEP_ALIVE_IN_SEC"),
+            live_max_topics: parser.parse("LIVE_MAX_TOPICS"),
+            cache_enabled: parser.parse("CACHE_ENABLED"),
+            cache_ttl_in_sec: parser.parse("CACHE_TTL_IN_SEC"),
+            cache_max_entries: parser.parse("CACHE_MAX_ENTRIES"),
+            cache_max_size_in_mb: parser.parse("CACHE_MAX_SIZE_IN_MB"),
+            soft_delete_retention_in_days: parser.parse("SOFT_DELETE_RETENTION_IN_DAYS"),
+            soft_delete_purge_interval_in_min: parser.parse("SOFT_DELETE_PURGE_INTERVAL_IN_MIN"),
+            supported_locales: parser
+                .list("SUPPORTED_LOCALES")
+                .into_iter()
+                .map(|locale| locale.to_ascii_lowercase())
+                .collect(),
+            default_locale: parser.string("DEFAULT_LOCALE").to_ascii_lowercase(),
+            base_currency: parser.string("BASE_CURRENCY").to_ascii_uppercase(),
+        };
+        config.validate(&layers, &mut problems);
+
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError {
+                profile: profile.to_string(),
+                problems,
+            })
+        }
+    }
+
+    fn validate(&self, layers: &Layers, problems: &mut Vec<String>) {
+        let is_set = |key: &str| layers.get(key).is_some();
+
+        let is_database_url = |url: &str| {
+            url.starts_with("postgres://")
+                || url.starts_with("postgresql://")
+                || (cfg!(feature = "sqlite") && url.starts_with("sqlite:"))
+        };
+        let expected: &str = if cfg!(feature = "sqlite") {
+            "a postgres:// or sqlite: URL"
+        } else {
+            "a postgres:// URL"
+        };
+        if is_set("DATABASE_URL") && !is_database_url(&self.database_url) {
+            problems.push(format!("DATABASE_URL must be {expected}"));
+        }
+        if let Some(url) = &self.database_replica_url
+            && !is_database_url(url)
+        {
+            problems.push(format!("DATABASE_REPLICA_URL must be {expected}"));
+        }
+        if self.database_min_connections > self.database_max_connections {
+            problems.push(
+                "DATABASE_MIN_CONNECTIONS cannot be more than DATABASE_MAX_CONNECTIONS".to_string(),
+            );
+        }
+        if is_set("PASSWORD_SALT") && self.password_salt.len() < 8 {
+            problems.push("PASSWORD_SALT must be at least 8 characters long".to_string());
+        }
+        for (key, value) in [
+            ("JWT_ACCESS_KEY", &self.jwt_access_key),
+            ("JWT_REFRESH_KEY", &self.jwt_refresh_key),
+        ] {
+            if is_set(key) && (value.len() != 64 || !value.chars().all(|c| c.is_ascii_hexdigit())) {
+                problems.push(format!("{key} must be 64 hexadecimal characters"));
+            }
+        }
+        if is_set("JWT_ACCESS_KEY") && self.jwt_access_key == self.jwt_refresh_key {
+            problems.push("JWT_ACCESS_KEY and JWT_REFRESH_KEY must differ".to_string());
+        }
+        for (key, value) in [
+            (
+                "JWT_ACCESS_LIFETIME_IN_MIN",
+                self.jwt_access_lifetime_in_min,
+            ),
+            (
+                "JWT_REFRESH_LIFETIME_IN_MIN",
+                self.jwt_refresh_lifetime_in_min,
+            ),
+        ] {
+            // Tokens older than an hour are rejected on verification.
+            if !(1..=60).contains(&value) {
+                problems.push(format!("{key} must be between 1 and 60"));
+            }
+        }
+        if is_set("UPLOAD_DIR") && self.upload_dir.as_os_str().is_empty() {
+            problems.push("UPLOAD_DIR must not be empty".to_string());
+        }
+
+        let allows_any_origin = self.cors_allowed_origins.iter().any(|origin| origin == "*");
+        if allows_any_origin && self.cors_allowed_origins.len() > 1 {
+            problems.push("CORS_ALLOWED_ORIGINS cannot mix `*` with explicit origins".to_string());
+        }
+        if allows_any_origin && self.cors_allow_credentials {
+            problems.push(
+                "CORS_ALLOW_CREDENTIALS cannot be true while CORS_ALLOWED_ORIGINS is `*`"
+                    .to_string(),
+            );
+        }
+        for origin in self
+            .cors_allowed_origins
+            .iter()
+            .filter(|origin| *origin != "*")
+        {
+            if !(origin.starts_with("http://") || origin.starts_with("https://"))
+                || HeaderValue::from_str(origin).is_err()
+            {
+                problems.push(format!(
+                    "CORS_ALLOWED_ORIGINS contains `{origin}`, which is not an http(s) origin"
+                ));
+            }
+        }
+        for header in &self.cors_exposed_headers {
+            if HeaderName::from_bytes(header.as_bytes()).is_err() {
+                problems.push(format!(
+                    "CORS_EXPOSED_HEADERS contains `{header}`, which is not a valid header name"
+                ));
+            }
+        }
+        if !["DENY", "SAMEORIGIN"].contains(&self.frame_options.as_str()) {
+            problems.push("FRAME_OPTIONS must be DENY or SAMEORIGIN".to_string());
+        }
+        if HeaderValue::from_str(&self.content_security_policy).is_err() {
+            problems.push("CONTENT_SECURITY_POLICY is not a valid header value".to_string());
+        }
+        for locale in &self.supported_locales {
+            let is_tag = locale.split('-').all(|part| {
+                (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+            });
+            if !is_tag {
+                problems.push(format!(
+                    "SUPPORTED_LOCALES contains `{locale}`, which is not a language tag"
+                ));
+            }
+        }
+        if !self.supported_locales.contains(&self.default_locale) {
+            problems.push("DEFAULT_LOCALE must be one of SUPPORTED_LOCALES".to_string());
+        }
+        if !is_currency_code(&self.base_currency) {
+            problems.push("BASE_CURRENCY must be a three letter ISO 4217 code".to_string());
+        }
+        for (key, value) in [
+            (
+                "DATABASE_MAX_CONNECTIONS",
+                self.database_max_connections as u64,
+            ),
+            (
+                "DATABASE_CONNECT_TIMEOUT_IN_SEC",
+                self.database_connect_timeout_in_sec,
+            ),
+            (
+                "DATABASE_ACQUIRE_TIMEOUT_IN_SEC",
+                self.database_acquire_timeout_in_sec,
+            ),
+            (
+                "DATABASE_IDLE_TIMEOUT_IN_SEC",
+                self.database_idle_timeout_in_sec,
+            ),
+            (
+                "DATABASE_MAX_LIFETIME_IN_SEC",
+                self.database_max_lifetime_in_sec,
+            ),
+            ("MAX_BODY_SIZE_IN_KB", self.max_body_size_in_kb as u64),
+            (
+                "UPLOAD_MAX_BODY_SIZE_IN_MB",
+                self.upload_max_body_size_in_mb as u64,
+            ),
+            ("REQUEST_TIMEOUT_IN_SEC", self.request_timeout_in_sec),
+            (
+                "UPLOAD_REQUEST_TIMEOUT_IN_SEC",
+                self.upload_request_timeout_in_sec,
+            ),
+            (
+                "IDEMPOTENCY_KEY_TTL_IN_HOURS",
+                self.idempotency_key_ttl_in_hours,
+            ),
+            (
+                "IDEMPOTENCY_CLEANUP_INTERVAL_IN_MIN",
+                self.idempotency_cleanup_interval_in_min,
+            ),
+            (
+                "GRAPHQL_MAX_COMPLEXITY",
+                self.graphql_max_complexity as u64,
+            ),
+            ("GRAPHQL_MAX_DEPTH", self.graphql_max_depth as u64),
+            ("WEBHOOK_MAX_ATTEMPTS", self.webhook_max_attempts as u64),
+            ("WEBHOOK_RETRY_BASE_IN_SEC", self.webhook_retry_base_in_sec),
+            ("WEBHOOK_RETRY_MAX_IN_SEC", self.webhook_retry_max_in_sec),
+            ("WEBHOOK_TIMEOUT_IN_SEC", self.webhook_timeout_in_sec),
+            (
+                "WEBHOOK_POLL_INTERVAL_IN_SEC",
+                self.webhook_poll_interval_in_sec,
+            ),
+            ("JOB_POLL_INTERVAL_IN_SEC", self.job_poll_interval_in_sec),
+            ("JOB_RETRY_BASE_IN_SEC", self.job_retry_base_in_sec),
+            ("JOB_RETRY_MAX_IN_SEC", self.job_retry_max_in_sec),
+            ("JOB_LOCK_TIMEOUT_IN_SEC", self.job_lock_timeout_in_sec),
+            (
+                "OUTBOX_POLL_INTERVAL_IN_SEC",
+                self.outbox_poll_interval_in_sec,
+            ),
+            ("OUTBOX_MAX_ATTEMPTS", self.outbox_max_attempts as u64),
+            ("LIVE_CHANNEL_CAPACITY", self.live_channel_capacity as u64),
+            ("LIVE_KEEP_ALIVE_IN_SEC", self.live_keep_alive_in_sec),
+            ("LIVE_MAX_TOPICS", self.live_max_topics as u64),
+            ("CACHE_TTL_IN_SEC", self.cache_ttl_in_sec),
+            ("CACHE_MAX_ENTRIES", self.cache_max_entries as u64),
+            ("CACHE_MAX_SIZE_IN_MB", self.cache_max_size_in_mb as u64),
+            (
+                "SOFT_DELETE_RETENTION_IN_DAYS",
+                self.soft_delete_retention_in_days,
+            ),
+            (
+                "SOFT_DELETE_PURGE_INTERVAL_IN_MIN",
+                self.soft_delete_purge_interval_in_min,
+            ),
+        ] {
+            if value == 0 {
+                problems.push(format!("{key} must be greater than 0"));
+            }
+        }
+    }
+}
+
+fn load_file(path: &Path, profile: Profile, layers: &mut Layers, problems: &mut Vec<String>) {
+    let source = path.display().to_string();
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            problems.push(format!("{source}: failed to read: {e}"));
+            return;
+        }
+    };
+    let mut table: toml::Table = match content.parse() {
+        Ok(table) => table,
+        Err(e) => {
+            problems.push(format!("{source}: invalid TOML: {e}"));
+            return;
+        }
+    };
+
+    // Top level keys apply to every profile, `[profile.<name>]` tables override them.
+    let profiles = table.remove("profile");
+    load_table(&table, &source, layers, problems);
+
+    match profiles {
+        Some(toml::Value::Table(mut profiles)) => {
+            if let Some(value) = profiles.remove(profile.as_str()) {
+                match value {
+                    toml::Value::Table(overrides) => load_table(
+                        &overrides,
+                        &format!("{source} [profile.{profile}]"),
+                        layers,
+                        problems,
+                    ),
+                    _ => problems.push(format!("{source}: profile.{profile} must be a table")),
+                }
+            }
+            for name in profiles.keys() {
+                if name.parse::<Profile>().is_err() {
+                    problems.push(format!("{source}: unknown profile `{name}`"));
+                }
+            }
+        }
+        Some(_) => problems.push(format!("{source}: `profile` must be a table")),
+        None => {}
+    }
+}
+
+fn load_table(table: &toml::Table, source: &str, layers: &mut Layers, problems: &mut Vec<String>) {
+    for (name, value) in table {
+        let key = name.to_ascii_uppercase();
+        if !KEYS.iter().any(|(known, _)| *known == key) {
+            problems.push(format!("{source}: unknown key `{name}`"));
+            continue;
+        }
+        let value = match value {
+            toml::Value::String(value) => value.clone(),
+            toml::Value::Integer(value) => value.to_string(),
+            toml::Value::Float(value) => value.to_string(),
+            toml::Value::Boolean(value) => value.to_string(),
+            // Lists are stored comma separated, the same way they are written in the environment.
+            toml::Value::Array(values) if values.iter().all(toml::Value::is_str) => values
+                .iter()
+                .filter_map(toml::Value::as_str)
+                .collect::<Vec<&str>>()
+                .join(","),
+            _ => {
+                problems.push(format!(
+                    "{source}: `{name}` must be a string, number, boolean or list of strings"
+                ));
+                continue;
+            }
+        };
+        layers.set(&key, value, source);
+    }
+}
+
+struct Parser<'a> {
+    layers: &'a Layers,
+    problems: &'a mut Vec<String>,
+}
+
+impl Parser<'_> {
+    fn string(&mut self, key: &str) -> String {
+        match self.layers.get(key) {
+            Some((value, _)) => value.clone(),
+            None => {
+                self.problems.push(format!("{key} is required"));
+                String::new()
+            }
+        }
+    }
+
+    /// Comma separated list, empty entries are dropped.
+    fn list(&mut self, key: &str) -> Vec<String> {
+        self.string(key)
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Comma separated `name:concurrency` pairs, e.g. `default:4,media:2`.
+    fn queues(&mut self, key: &str) -> Vec<(String, usize)> {
+        let mut queues: Vec<(String, usize)> = Vec::new();
+        for entry in self.list(key) {
+            let parsed = entry
+                .split_once(':')
+                .map(|(name, concurrency)| (name.trim(), concurrency.trim().parse::<usize>()));
+            match parsed {
+                Some((name, Ok(concurrency))) if !name.is_empty() && concurrency > 0 => {
+                    queues.push((name.to_string(), concurrency));
+                }
+                _ => self.problems.push(format!(
+                    "{key} entries must look like `name:concurrency` with a concurrency above 0, got `{entry}`"
+                )),
+            }
+        }
+        queues
+    }
+
+    fn parse<T>(&mut self, key: &str) -> T
+    where
+        T: FromStr + Default,
+    {
+        match self.layers.get(key) {
+            Some((value, source)) => value.trim().parse::<T>().unwrap_or_else(|_| {
+                self.problems.push(format!(
+                    "{key} must be a valid {}, got `{value}` (from {source})",
+                    std::any::type_name::<T>()
+                ));
+                T::default()
+            }),
+            None => {
+                self.problems.push(format!("{key} is required"));
+                T::default()
+            }
+        }
+    }
+}
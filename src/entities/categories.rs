@@ -2,6 +2,8 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::utils::actor::stamp_actor;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "categories")]
 pub struct Model {
@@ -18,6 +20,8 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::category_translations::Entity")]
+    CategoryTranslations,
     #[sea_orm(has_many = "super::retreats::Entity")]
     Retreats,
     #[sea_orm(
@@ -38,10 +42,24 @@ pub enum Relation {
     Users1,
 }
 
+impl Related<super::category_translations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CategoryTranslations.def()
+    }
+}
+
 impl Related<super::retreats::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Retreats.def()
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Ok(stamp_actor(self, insert, Column::CreatedBy, Column::UpdatedBy))
+    }
+}
@@ -2,6 +2,8 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::utils::actor::stamp_actor;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "gallery_categories")]
 pub struct Model {
@@ -42,4 +44,12 @@ impl Related<super::retreat_galleries::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Ok(stamp_actor(self, insert, Column::CreatedBy, Column::UpdatedBy))
+    }
+}
@@ -2,11 +2,23 @@
 
 pub mod prelude;
 
+pub mod audit_events;
 pub mod categories;
+pub mod category_translations;
+pub mod exchange_rates;
 pub mod gallery_categories;
+pub mod gallery_translations;
+pub mod idempotency_keys;
+pub mod jobs;
+pub mod organization_users;
+pub mod organizations;
+pub mod outbox_events;
 pub mod retreat_galleries;
 pub mod retreat_reviews;
+pub mod retreat_translations;
 pub mod retreat_users;
 pub mod retreats;
 pub mod users;
+pub mod webhook_deliveries;
+pub mod webhook_subscriptions;
 pub mod wishlists;
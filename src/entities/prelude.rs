@@ -1,10 +1,22 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.14
 #![allow(unused)]
+pub use super::audit_events::Entity as AuditEvents;
 pub use super::categories::Entity as Categories;
+pub use super::category_translations::Entity as CategoryTranslations;
+pub use super::exchange_rates::Entity as ExchangeRates;
 pub use super::gallery_categories::Entity as GalleryCategories;
+pub use super::gallery_translations::Entity as GalleryTranslations;
+pub use super::idempotency_keys::Entity as IdempotencyKeys;
+pub use super::jobs::Entity as Jobs;
+pub use super::organization_users::Entity as OrganizationUsers;
+pub use super::organizations::Entity as Organizations;
+pub use super::outbox_events::Entity as OutboxEvents;
 pub use super::retreat_galleries::Entity as RetreatGalleries;
 pub use super::retreat_reviews::Entity as RetreatReviews;
+pub use super::retreat_translations::Entity as RetreatTranslations;
 pub use super::retreat_users::Entity as RetreatUsers;
 pub use super::retreats::Entity as Retreats;
 pub use super::users::Entity as Users;
+pub use super::webhook_deliveries::Entity as WebhookDeliveries;
+pub use super::webhook_subscriptions::Entity as WebhookSubscriptions;
 pub use super::wishlists::Entity as Wishlists;
@@ -2,6 +2,8 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::utils::actor::stamp_actor;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "retreat_galleries")]
 pub struct Model {
@@ -17,11 +19,14 @@ pub struct Model {
     pub updated_at: DateTimeWithTimeZone,
     pub created_by: Option<i64>,
     pub updated_by: Option<i64>,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
     pub gallery_category_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::gallery_translations::Entity")]
+    GalleryTranslations,
     #[sea_orm(
         belongs_to = "super::gallery_categories::Entity",
         from = "Column::GalleryCategoryId",
@@ -56,6 +61,12 @@ pub enum Relation {
     Users1,
 }
 
+impl Related<super::gallery_translations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GalleryTranslations.def()
+    }
+}
+
 impl Related<super::gallery_categories::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::GalleryCategories.def()
@@ -68,4 +79,12 @@ impl Related<super::retreats::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Ok(stamp_actor(self, insert, Column::CreatedBy, Column::UpdatedBy))
+    }
+}
@@ -2,6 +2,8 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::utils::actor::stamp_actor;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "retreats")]
 pub struct Model {
@@ -28,6 +30,9 @@ pub struct Model {
     pub updated_at: DateTimeWithTimeZone,
     pub created_by: Option<i64>,
     pub updated_by: Option<i64>,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+    pub organization_id: Option<i64>,
+    pub currency: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -40,10 +45,20 @@ pub enum Relation {
         on_delete = "SetNull"
     )]
     Categories,
+    #[sea_orm(
+        belongs_to = "super::organizations::Entity",
+        from = "Column::OrganizationId",
+        to = "super::organizations::Column::OrganizationId",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    Organizations,
     #[sea_orm(has_many = "super::retreat_galleries::Entity")]
     RetreatGalleries,
     #[sea_orm(has_many = "super::retreat_reviews::Entity")]
     RetreatReviews,
+    #[sea_orm(has_many = "super::retreat_translations::Entity")]
+    RetreatTranslations,
     #[sea_orm(has_many = "super::retreat_users::Entity")]
     RetreatUsers,
     #[sea_orm(
@@ -72,6 +87,12 @@ impl Related<super::categories::Entity> for Entity {
     }
 }
 
+impl Related<super::organizations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Organizations.def()
+    }
+}
+
 impl Related<super::retreat_galleries::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::RetreatGalleries.def()
@@ -84,6 +105,12 @@ impl Related<super::retreat_reviews::Entity> for Entity {
     }
 }
 
+impl Related<super::retreat_translations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RetreatTranslations.def()
+    }
+}
+
 impl Related<super::retreat_users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::RetreatUsers.def()
@@ -96,4 +123,12 @@ impl Related<super::wishlists::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Ok(stamp_actor(self, insert, Column::CreatedBy, Column::UpdatedBy))
+    }
+}
@@ -14,16 +14,26 @@ pub struct Model {
     pub phone: Option<String>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+    pub is_admin: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::organization_users::Entity")]
+    OrganizationUsers,
     #[sea_orm(has_many = "super::retreat_reviews::Entity")]
     RetreatReviews,
     #[sea_orm(has_many = "super::wishlists::Entity")]
     Wishlists,
 }
 
+impl Related<super::organization_users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OrganizationUsers.def()
+    }
+}
+
 impl Related<super::retreat_reviews::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::RetreatReviews.def()
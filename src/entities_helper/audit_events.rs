@@ -0,0 +1,4 @@
+pub use crate::entities::audit_events::{
+    ActiveModel as AuditEventActiveModel, Column as AuditEventColumn,
+    Entity as AuditEventEntity, Model as AuditEventModel,
+};
@@ -0,0 +1,4 @@
+pub use crate::entities::category_translations::{
+    ActiveModel as CategoryTranslationActiveModel, Column as CategoryTranslationColumn,
+    Entity as CategoryTranslationEntity, Model as CategoryTranslationModel,
+};
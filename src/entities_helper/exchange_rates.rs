@@ -0,0 +1,4 @@
+pub use crate::entities::exchange_rates::{
+    ActiveModel as ExchangeRateActiveModel, Column as ExchangeRateColumn,
+    Entity as ExchangeRateEntity, Model as ExchangeRateModel,
+};
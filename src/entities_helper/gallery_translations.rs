@@ -0,0 +1,4 @@
+pub use crate::entities::gallery_translations::{
+    ActiveModel as GalleryTranslationActiveModel, Column as GalleryTranslationColumn,
+    Entity as GalleryTranslationEntity, Model as GalleryTranslationModel,
+};
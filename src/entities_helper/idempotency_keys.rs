@@ -0,0 +1,4 @@
+pub use crate::entities::idempotency_keys::{
+    ActiveModel as IdempotencyKeyActiveModel, Column as IdempotencyKeyColumn,
+    Entity as IdempotencyKeyEntity, Model as IdempotencyKeyModel,
+};
@@ -0,0 +1,3 @@
+pub use crate::entities::jobs::{
+    ActiveModel as JobActiveModel, Column as JobColumn, Entity as JobEntity, Model as JobModel,
+};
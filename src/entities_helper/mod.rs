@@ -1,18 +1,58 @@
 #![allow(unused)]
+pub mod audit_events;
 pub mod categories;
+pub mod category_translations;
+pub mod exchange_rates;
 pub mod gallery_categories;
+pub mod gallery_translations;
+pub mod idempotency_keys;
+pub mod jobs;
+pub mod organization_users;
+pub mod organizations;
+pub mod outbox_events;
 pub mod retreat_galleries;
 pub mod retreat_reviews;
+pub mod retreat_translations;
 pub mod retreat_users;
 pub mod retreats;
 pub mod users;
+pub mod webhook_deliveries;
+pub mod webhook_subscriptions;
 pub mod wishlists;
 
+pub use audit_events::{
+    AuditEventActiveModel, AuditEventColumn, AuditEventEntity, AuditEventModel,
+};
 pub use categories::{CategoryActiveModel, CategoryColumn, CategoryEntity, CategoryModel};
+pub use category_translations::{
+    CategoryTranslationActiveModel, CategoryTranslationColumn, CategoryTranslationEntity,
+    CategoryTranslationModel,
+};
+pub use exchange_rates::{
+    ExchangeRateActiveModel, ExchangeRateColumn, ExchangeRateEntity, ExchangeRateModel,
+};
 pub use gallery_categories::{
     GalleryCategoriesActiveModel, GalleryCategoriesColumn, GalleryCategoriesEntity,
     GalleryCategoriesModel,
 };
+pub use gallery_translations::{
+    GalleryTranslationActiveModel, GalleryTranslationColumn, GalleryTranslationEntity,
+    GalleryTranslationModel,
+};
+pub use idempotency_keys::{
+    IdempotencyKeyActiveModel, IdempotencyKeyColumn, IdempotencyKeyEntity, IdempotencyKeyModel,
+};
+pub use jobs::{JobActiveModel, JobColumn, JobEntity, JobModel};
+pub use organization_users::{
+    OrganizationUserActiveModel, OrganizationUserColumn, OrganizationUserEntity,
+    OrganizationUserModel,
+};
+pub use organizations::{
+    OrganizationActiveModel, OrganizationColumn, OrganizationEntity, OrganizationModel,
+};
+pub use outbox_events::{
+    OutboxEventActiveModel, OutboxEventColumn, OutboxEventEntity, OutboxEventModel,
+};
 pub use retreat_galleries::{
     RetreatGalleriesActiveModel, RetreatGalleriesColumn, RetreatGalleriesEntity,
     RetreatGalleriesModel,
@@ -20,9 +60,20 @@ pub use retreat_galleries::{
 pub use retreat_reviews::{
     RetreatReviewActiveModel, RetreatReviewColumn, RetreatReviewEntity, RetreatReviewModel,
 };
+pub use retreat_translations::{
+    RetreatTranslationActiveModel, RetreatTranslationColumn, RetreatTranslationEntity,
+    RetreatTranslationModel,
+};
 pub use retreat_users::{
     RetreatUserActiveModel, RetreatUserColumn, RetreatUserEntity, RetreatUserModel,
 };
 pub use retreats::{RetreatActiveModel, RetreatColumn, RetreatEntity, RetreatModel};
 pub use users::{UserActiveModel, UserColumn, UserEntity, UserModel};
+pub use webhook_deliveries::{
+    WebhookDeliveryActiveModel, WebhookDeliveryColumn, WebhookDeliveryEntity, WebhookDeliveryModel,
+};
+pub use webhook_subscriptions::{
+    WebhookSubscriptionActiveModel, WebhookSubscriptionColumn, WebhookSubscriptionEntity,
+    WebhookSubscriptionModel,
+};
 pub use wishlists::{WishlistActiveModel, WishlistColumn, WishlistEntity, WishlistModel};
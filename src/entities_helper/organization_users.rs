@@ -0,0 +1,4 @@
+pub use crate::entities::organization_users::{
+    ActiveModel as OrganizationUserActiveModel, Column as OrganizationUserColumn,
+    Entity as OrganizationUserEntity, Model as OrganizationUserModel,
+};
@@ -0,0 +1,4 @@
+pub use crate::entities::organizations::{
+    ActiveModel as OrganizationActiveModel, Column as OrganizationColumn,
+    Entity as OrganizationEntity, Model as OrganizationModel,
+};
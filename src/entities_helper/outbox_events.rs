@@ -0,0 +1,4 @@
+pub use crate::entities::outbox_events::{
+    ActiveModel as OutboxEventActiveModel, Column as OutboxEventColumn,
+    Entity as OutboxEventEntity, Model as OutboxEventModel,
+};
@@ -0,0 +1,4 @@
+pub use crate::entities::retreat_translations::{
+    ActiveModel as RetreatTranslationActiveModel, Column as RetreatTranslationColumn,
+    Entity as RetreatTranslationEntity, Model as RetreatTranslationModel,
+};
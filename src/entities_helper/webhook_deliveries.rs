@@ -0,0 +1,4 @@
+pub use crate::entities::webhook_deliveries::{
+    ActiveModel as WebhookDeliveryActiveModel, Column as WebhookDeliveryColumn,
+    Entity as WebhookDeliveryEntity, Model as WebhookDeliveryModel,
+};
@@ -0,0 +1,4 @@
+pub use crate::entities::webhook_subscriptions::{
+    ActiveModel as WebhookSubscriptionActiveModel, Column as WebhookSubscriptionColumn,
+    Entity as WebhookSubscriptionEntity, Model as WebhookSubscriptionModel,
+};
@@ -0,0 +1,125 @@
+mod relay;
+
+use std::{future::Future, pin::Pin};
+
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ConnectionTrait, DatabaseTransaction, DbErr};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    entities_helper::OutboxEventActiveModel, live::publish_live_updates, state::AppState,
+    webhooks::queue_webhooks,
+};
+
+pub(crate) use relay::relay_outbox_events;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_DISPATCHED: &str = "dispatched";
+/// Failed in a subscriber on every attempt, no longer relayed.
+pub const STATUS_FAILED: &str = "failed";
+
+/// Something that happened to the domain, recorded in the outbox by the same transaction
+/// as the change itself. Snapshots are the REST representation at the time of the change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum DomainEvent {
+    RetreatCreated {
+        retreat_id: i64,
+        retreat: JsonValue,
+    },
+    RetreatUpdated {
+        retreat_id: i64,
+        retreat: JsonValue,
+    },
+    RetreatPublished {
+        retreat_id: i64,
+        retreat: JsonValue,
+    },
+    RetreatDeleted {
+        retreat_id: i64,
+        retreat: JsonValue,
+    },
+    RetreatRestored {
+        retreat_id: i64,
+        retreat: JsonValue,
+    },
+    ReviewPosted {
+        retreat_id: i64,
+        review: JsonValue,
+    },
+    StaffAdded {
+        retreat_id: i64,
+        user_id: i64,
+        role: String,
+    },
+    GalleryCreated {
+        retreat_id: i64,
+        gallery: JsonValue,
+    },
+    GalleryUpdated {
+        retreat_id: i64,
+        gallery: JsonValue,
+    },
+    GalleryDeleted {
+        retreat_id: i64,
+        gallery: JsonValue,
+    },
+    GalleryRestored {
+        retreat_id: i64,
+        gallery: JsonValue,
+    },
+    WishlistAdded {
+        retreat_id: i64,
+        user_id: i64,
+    },
+}
+
+impl DomainEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::RetreatCreated { .. } => "retreat_created",
+            DomainEvent::RetreatUpdated { .. } => "retreat_updated",
+            DomainEvent::RetreatPublished { .. } => "retreat_published",
+            DomainEvent::RetreatDeleted { .. } => "retreat_deleted",
+            DomainEvent::RetreatRestored { .. } => "retreat_restored",
+            DomainEvent::ReviewPosted { .. } => "review_posted",
+            DomainEvent::StaffAdded { .. } => "staff_added",
+            DomainEvent::GalleryCreated { .. } => "gallery_created",
+            DomainEvent::GalleryUpdated { .. } => "gallery_updated",
+            DomainEvent::GalleryDeleted { .. } => "gallery_deleted",
+            DomainEvent::GalleryRestored { .. } => "gallery_restored",
+            DomainEvent::WishlistAdded { .. } => "wishlist_added",
+        }
+    }
+}
+
+/// Writes `event` to the outbox. Pass the transaction of the change, so the event is
+/// relayed if and only if the change commits.
+pub async fn record_event<C>(database: &C, event: &DomainEvent) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let payload: JsonValue =
+        serde_json::to_value(event).map_err(|e| DbErr::Custom(e.to_string()))?;
+    OutboxEventActiveModel {
+        event: Set(event.name().to_string()),
+        payload: Set(payload),
+        ..Default::default()
+    }
+    .insert(database)
+    .await?;
+    Ok(())
+}
+
+pub type SubscriberFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// Handles every committed event. Database writes should go through the given transaction,
+/// it commits together with marking the event dispatched and rolls back if any subscriber
+/// fails, so the event is handled again as a whole.
+pub type Subscriber =
+    for<'a> fn(&'a AppState, &'a DatabaseTransaction, &'a DomainEvent) -> SubscriberFuture<'a>;
+
+/// Everything the relay hands committed events to, in order.
+pub fn subscribers() -> Vec<(&'static str, Subscriber)> {
+    vec![("webhooks", queue_webhooks), ("live", publish_live_updates)]
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait, DatabaseTransaction, DbErr, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+    sea_query::{LockBehavior, LockType},
+};
+
+use crate::{
+    entities_helper::{
+        OutboxEventActiveModel, OutboxEventColumn, OutboxEventEntity, OutboxEventModel,
+    },
+    events::{
+        DomainEvent, STATUS_DISPATCHED, STATUS_FAILED, STATUS_PENDING, Subscriber, subscribers,
+    },
+    state::AppState,
+};
+
+/// Relayed per tick at most, the rest waits for the next one.
+const RELAY_BATCH_SIZE: usize = 100;
+
+/// Hands committed outbox events to the subscribers until shutdown.
+pub(crate) async fn relay_outbox_events(state: AppState) {
+    let subscribers: Vec<(&'static str, Subscriber)> = subscribers();
+    let period = Duration::from_secs(state.config.outbox_poll_interval_in_sec);
+    let mut interval = tokio::time::interval(period);
+    loop {
+        tokio::select! {
+            _ = state.shutdown.triggered() => break,
+            _ = interval.tick() => {}
+        }
+        if let Err(e) = relay_pending(&state, &subscribers).await {
+            eprintln!("Warning: failed to relay outbox events: {e}");
+        }
+    }
+}
+
+/// Relays pending events oldest first, each in its own transaction. `SKIP LOCKED` keeps
+/// several server instances from relaying the same event. SQLite has no row locks and
+/// leaves the clause out, its single writer serialises the relays instead.
+async fn relay_pending(
+    state: &AppState,
+    subscribers: &[(&'static str, Subscriber)],
+) -> Result<(), DbErr> {
+    // Events that fail are retried on the next tick, not again in this one
+    let mut after: i64 = 0;
+    for _ in 0..RELAY_BATCH_SIZE {
+        let transaction = state.database.begin().await?;
+        let Some(outbox_event) = OutboxEventEntity::find()
+            .filter(OutboxEventColumn::Status.eq(STATUS_PENDING))
+            .filter(OutboxEventColumn::OutboxEventId.gt(after))
+            .order_by_asc(OutboxEventColumn::OutboxEventId)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .one(&transaction)
+            .await?
+        else {
+            break;
+        };
+        after = outbox_event.outbox_event_id;
+        let attempts: i32 = outbox_event.attempts + 1;
+
+        match dispatch(state, &transaction, subscribers, &outbox_event).await {
+            Ok(()) => {
+                let mut active_model: OutboxEventActiveModel = outbox_event.into_active_model();
+                active_model.status = Set(STATUS_DISPATCHED.to_string());
+                active_model.attempts = Set(attempts);
+                active_model.last_error = Set(None);
+                active_model.dispatched_at = Set(Some(state.clock.now_fixed()));
+                active_model.update(&transaction).await?;
+                transaction.commit().await?;
+            }
+            Err(error) => {
+                // Undo whatever the subscribers before the failing one wrote
+                transaction.rollback().await?;
+                eprintln!(
+                    "Warning: outbox event {} ({}) failed: {error}",
+                    outbox_event.outbox_event_id, outbox_event.event
+                );
+
+                let status: &str = if attempts >= state.config.outbox_max_attempts as i32 {
+                    STATUS_FAILED
+                } else {
+                    STATUS_PENDING
+                };
+                let mut active_model: OutboxEventActiveModel = outbox_event.into_active_model();
+                active_model.status = Set(status.to_string());
+                active_model.attempts = Set(attempts);
+                active_model.last_error = Set(Some(error));
+                active_model.update(&state.database).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn dispatch(
+    state: &AppState,
+    transaction: &DatabaseTransaction,
+    subscribers: &[(&'static str, Subscriber)],
+    outbox_event: &OutboxEventModel,
+) -> Result<(), String> {
+    let event: DomainEvent = serde_json::from_value(outbox_event.payload.clone())
+        .map_err(|e| format!("Invalid event payload: {e}"))?;
+    for (name, subscriber) in subscribers {
+        subscriber(state, transaction, &event)
+            .await
+            .map_err(|e| format!("{name}: {e}"))?;
+    }
+    Ok(())
+}
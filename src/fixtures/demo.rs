@@ -0,0 +1,182 @@
+//! The `demo` profile, generated rather than read from a file so the volume can be picked
+//! for load testing. The same scale always generates the same records.
+
+use std::path::PathBuf;
+
+use sea_orm::prelude::Decimal;
+use serde_json::json;
+
+use super::{
+    Fixtures, GalleryFixture, RetreatFixture, ReviewFixture, StaffFixture, UserFixture,
+    WishlistFixture,
+};
+
+/// Shared by every demo user, so it is hashed once however many there are.
+pub const DEMO_PASSWORD: &str = "demo-password";
+
+const CATEGORIES: [&str; 5] = ["Yoga", "Meditation", "Wellness", "Trekking", "Spiritual"];
+const GALLERY_CATEGORIES: [&str; 4] = ["Rooms", "Food", "Activities", "Surroundings"];
+const PLACES: [(&str, f64, f64); 8] = [
+    ("Pokhara", 28.2096, 83.9856),
+    ("Nagarkot", 27.7152, 85.5203),
+    ("Rishikesh", 30.0869, 78.2676),
+    ("Dharamshala", 32.2190, 76.3234),
+    ("Lumbini", 27.4840, 83.2760),
+    ("Bandipur", 27.9380, 84.4060),
+    ("Goa", 15.2993, 74.1240),
+    ("Kerala", 10.8505, 76.2711),
+];
+const STYLES: [&str; 6] = [
+    "Lakeside",
+    "Hilltop",
+    "Forest",
+    "Riverside",
+    "Garden",
+    "Valley",
+];
+const KINDS: [&str; 5] = ["Retreat", "Ashram", "Sanctuary", "Lodge", "Center"];
+/// Currency and the rough nightly budget range in it.
+const BUDGETS: [(&str, i64, i64); 3] =
+    [("USD", 30, 400), ("NPR", 3000, 40000), ("INR", 2000, 30000)];
+const FIRST_NAMES: [&str; 10] = [
+    "Aarav", "Sita", "Maya", "Rohan", "Anita", "Bikash", "Priya", "Tenzing", "Laxmi", "Arjun",
+];
+const LAST_NAMES: [&str; 8] = [
+    "Sharma", "Gurung", "Thapa", "Rai", "Shrestha", "Patel", "Lama", "Karki",
+];
+const REVIEWS: [&str; 6] = [
+    "Peaceful place, the morning sessions were the highlight.",
+    "Friendly staff and simple, tasty food.",
+    "Rooms were smaller than the photos suggest.",
+    "Great views, a bit hard to reach without a car.",
+    "Would come back for a longer stay.",
+    "Good value for the price.",
+];
+const CAPTIONS: [&str; 5] = [
+    "Morning view",
+    "Dining hall",
+    "Practice room",
+    "Garden path",
+    "Guest room",
+];
+
+/// xorshift64, enough for plausible looking data without a dependency.
+struct Generator(u64);
+
+impl Generator {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `from..=to`.
+    fn between(&mut self, from: i64, to: i64) -> i64 {
+        from + (self.next() % (to - from + 1) as u64) as i64
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.next() as usize % items.len()]
+    }
+}
+
+/// `scale` retreats with four users each on average, each retreat with an owner, up to
+/// eight reviews and, given sample `images`, up to three gallery images.
+pub fn demo_fixtures(scale: usize, images: &[PathBuf]) -> Fixtures {
+    let mut generator: Generator = Generator(0x2545_f491_4f6c_dd1d);
+    let mut fixtures: Fixtures = Fixtures::default();
+    let user_count: usize = scale * 4;
+
+    for index in 0..user_count {
+        fixtures.users.push(UserFixture {
+            name: format!(
+                "{} {}",
+                generator.pick(&FIRST_NAMES),
+                generator.pick(&LAST_NAMES)
+            ),
+            email: format!("demo-user-{index}@example.com"),
+            password: DEMO_PASSWORD.to_string(),
+            phone: Some(format!("98{:08}", generator.between(0, 99_999_999))),
+            is_admin: false,
+        });
+    }
+
+    for index in 0..scale {
+        let slug: String = format!("demo-retreat-{index}");
+        let (place, latitude, longitude) = *generator.pick(&PLACES);
+        let (currency, low, high) = *generator.pick(&BUDGETS);
+        let budget_min: i64 = generator.between(low, (low + high) / 2);
+        let budget_max: i64 = generator.between(budget_min, high);
+        // Spread the retreats around the town, a few kilometres at most
+        let offset = |generator: &mut Generator| generator.between(-300, 300) as f64 / 10_000.0;
+        let latitude: f64 = latitude + offset(&mut generator);
+        let longitude: f64 = longitude + offset(&mut generator);
+        fixtures.retreats.push(RetreatFixture {
+            name: format!(
+                "{} {} {}",
+                place,
+                generator.pick(&STYLES),
+                generator.pick(&KINDS)
+            ),
+            slug: slug.clone(),
+            category: generator.pick(&CATEGORIES).to_string(),
+            description: Some(format!("A demo retreat near {place}.")),
+            email: Some(format!("{slug}@example.com")),
+            phone: None,
+            address: Some(place.to_string()),
+            latitude: Decimal::from_f64_retain(latitude).map(|value| value.round_dp(6)),
+            longitude: Decimal::from_f64_retain(longitude).map(|value| value.round_dp(6)),
+            budget_min: Some(Decimal::from(budget_min)),
+            budget_max: Some(Decimal::from(budget_max)),
+            currency: Some(currency.to_string()),
+            // Leave some unpublished, as in a real catalog
+            is_published: generator.between(0, 9) != 0,
+            social_links: json!({}),
+        });
+
+        let owner: usize = generator.between(0, user_count as i64 - 1) as usize;
+        fixtures.staff.push(StaffFixture {
+            retreat: slug.clone(),
+            user: format!("demo-user-{owner}@example.com"),
+            is_owner: true,
+            role: Some("Owner".to_string()),
+        });
+
+        // The loader skips a second review by the same user
+        for _ in 0..generator.between(0, 8) {
+            let user: usize = generator.between(0, user_count as i64 - 1) as usize;
+            fixtures.reviews.push(ReviewFixture {
+                retreat: slug.clone(),
+                user: format!("demo-user-{user}@example.com"),
+                rating: generator.between(2, 10) as f64 / 2.0,
+                review: Some(generator.pick(&REVIEWS).to_string()),
+            });
+        }
+
+        if !images.is_empty() {
+            for order in 0..generator.between(1, 3) {
+                fixtures.gallery.push(GalleryFixture {
+                    retreat: slug.clone(),
+                    image: generator.pick(images).clone(),
+                    caption: Some(generator.pick(&CAPTIONS).to_string()),
+                    order: Some(order as i32),
+                    gallery_category: Some(generator.pick(&GALLERY_CATEGORIES).to_string()),
+                });
+            }
+        }
+    }
+
+    if scale > 0 {
+        for index in 0..user_count {
+            for _ in 0..generator.between(0, 5) {
+                let retreat: i64 = generator.between(0, scale as i64 - 1);
+                fixtures.wishlists.push(WishlistFixture {
+                    user: format!("demo-user-{index}@example.com"),
+                    retreat: format!("demo-retreat-{retreat}"),
+                });
+            }
+        }
+    }
+    fixtures
+}
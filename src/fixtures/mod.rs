@@ -0,0 +1,599 @@
+mod demo;
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    path::{Path, PathBuf},
+};
+
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter, QuerySelect, TransactionTrait, TryGetableMany, prelude::Decimal,
+    sea_query::ValueType,
+};
+use serde::Deserialize;
+use serde_json::{Value as JsonValue, json};
+use tokio::fs;
+
+use crate::{
+    entities_helper::{
+        CategoryActiveModel, CategoryColumn, CategoryEntity, GalleryCategoriesActiveModel,
+        GalleryCategoriesColumn, GalleryCategoriesEntity, RetreatActiveModel, RetreatColumn,
+        RetreatEntity, RetreatGalleriesActiveModel, RetreatGalleriesColumn, RetreatGalleriesEntity,
+        RetreatReviewActiveModel, RetreatReviewColumn, RetreatReviewEntity, RetreatUserActiveModel,
+        RetreatUserColumn, RetreatUserEntity, UserActiveModel, UserColumn, UserEntity,
+        WishlistActiveModel, WishlistColumn, WishlistEntity,
+    },
+    state::AppState,
+    utils::password::create_password,
+};
+
+pub use demo::{DEMO_PASSWORD, demo_fixtures};
+
+/// Rows per `INSERT`, well below the Postgres bind parameter limit.
+const BATCH_SIZE: usize = 500;
+
+/// Records to load, referring to each other by natural key: users by email, categories
+/// and gallery categories by name, retreats by slug. A reference may also point at a row
+/// that is already in the database.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Fixtures {
+    pub users: Vec<UserFixture>,
+    pub categories: Vec<CategoryFixture>,
+    pub gallery_categories: Vec<GalleryCategoryFixture>,
+    pub retreats: Vec<RetreatFixture>,
+    pub staff: Vec<StaffFixture>,
+    pub reviews: Vec<ReviewFixture>,
+    pub gallery: Vec<GalleryFixture>,
+    pub wishlists: Vec<WishlistFixture>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserFixture {
+    pub name: String,
+    pub email: String,
+    pub password: String,
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub is_admin: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryFixture {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GalleryCategoryFixture {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetreatFixture {
+    pub name: String,
+    pub slug: String,
+    /// Name of the category.
+    pub category: String,
+    pub description: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub latitude: Option<Decimal>,
+    pub longitude: Option<Decimal>,
+    pub budget_min: Option<Decimal>,
+    pub budget_max: Option<Decimal>,
+    /// The base currency if left out.
+    pub currency: Option<String>,
+    #[serde(default = "default_true")]
+    pub is_published: bool,
+    #[serde(default = "default_social_links")]
+    pub social_links: JsonValue,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StaffFixture {
+    pub retreat: String,
+    pub user: String,
+    #[serde(default)]
+    pub is_owner: bool,
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReviewFixture {
+    pub retreat: String,
+    pub user: String,
+    pub rating: f64,
+    pub review: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GalleryFixture {
+    pub retreat: String,
+    /// Relative to the fixture file, copied into `UPLOAD_DIR`.
+    pub image: PathBuf,
+    pub caption: Option<String>,
+    pub order: Option<i32>,
+    /// Name of the gallery category.
+    pub gallery_category: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WishlistFixture {
+    pub user: String,
+    pub retreat: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_social_links() -> JsonValue {
+    json!({})
+}
+
+/// How many rows each section inserted, records that already existed are skipped.
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    pub users: usize,
+    pub categories: usize,
+    pub gallery_categories: usize,
+    pub retreats: usize,
+    pub staff: usize,
+    pub reviews: usize,
+    pub gallery: usize,
+    pub wishlists: usize,
+}
+
+impl std::fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} user(s), {} categorie(s), {} gallery categorie(s), {} retreat(s), {} staff, \
+             {} review(s), {} gallery image(s) and {} wishlist item(s) created",
+            self.users,
+            self.categories,
+            self.gallery_categories,
+            self.retreats,
+            self.staff,
+            self.reviews,
+            self.gallery,
+            self.wishlists
+        )
+    }
+}
+
+/// Reads a fixture file, `.json` or `.toml`.
+pub async fn read_fixtures(path: &Path) -> Result<Fixtures, String> {
+    let content: String = fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        Some("toml") => toml::from_str(&content).map_err(|e| e.to_string()),
+        _ => Err(format!(
+            "{} is neither a .json nor a .toml file",
+            path.display()
+        )),
+    }
+    .map_err(|e| format!("Invalid fixtures in {}: {e}", path.display()))
+}
+
+/// Ids of the rows whose `key` column is one of `keys`.
+async fn ids_by_key<E, C, K>(
+    database: &C,
+    key: E::Column,
+    id: E::Column,
+    keys: Vec<K>,
+) -> Result<HashMap<K, i64>, String>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+    K: Into<sea_orm::Value> + ValueType + TryGetableMany + Eq + Hash + Clone,
+    (K, i64): TryGetableMany,
+{
+    let mut ids: HashMap<K, i64> = HashMap::new();
+    for chunk in keys.chunks(BATCH_SIZE) {
+        let rows: Vec<(K, i64)> = E::find()
+            .select_only()
+            .column(key)
+            .column(id)
+            .filter(key.is_in(chunk.to_vec()))
+            .into_tuple()
+            .all(database)
+            .await
+            .map_err(|e| e.to_string())?;
+        ids.extend(rows);
+    }
+    Ok(ids)
+}
+
+/// The `(retreat_id, user_id)` pairs already linked in a table.
+async fn linked_pairs<E, C>(
+    database: &C,
+    retreat: E::Column,
+    user: E::Column,
+    retreat_ids: Vec<i64>,
+) -> Result<HashSet<(i64, i64)>, String>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let mut pairs: HashSet<(i64, i64)> = HashSet::new();
+    for chunk in retreat_ids.chunks(BATCH_SIZE) {
+        let rows: Vec<(i64, i64)> = E::find()
+            .select_only()
+            .column(retreat)
+            .column(user)
+            .filter(retreat.is_in(chunk.to_vec()))
+            .into_tuple()
+            .all(database)
+            .await
+            .map_err(|e| e.to_string())?;
+        pairs.extend(rows);
+    }
+    Ok(pairs)
+}
+
+async fn insert_batches<A, C>(database: &C, active_models: Vec<A>) -> Result<usize, String>
+where
+    A: ActiveModelTrait,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+    C: ConnectionTrait,
+{
+    let count: usize = active_models.len();
+    let mut active_models = active_models.into_iter().peekable();
+    while active_models.peek().is_some() {
+        let batch: Vec<A> = active_models.by_ref().take(BATCH_SIZE).collect();
+        A::Entity::insert_many(batch)
+            .exec_without_returning(database)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(count)
+}
+
+fn resolve(ids: &HashMap<String, i64>, kind: &str, key: &str, by: &str) -> Result<i64, String> {
+    ids.get(key)
+        .copied()
+        .ok_or_else(|| format!("{kind} `{key}` referenced by {by} does not exist"))
+}
+
+fn unique_keys<'a>(keys: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let keys: HashSet<&String> = keys.collect();
+    keys.into_iter().cloned().collect()
+}
+
+/// Loads the fixtures in one transaction. Records whose natural key already exists are
+/// left as they are, so loading the same fixtures again changes nothing. Gallery images
+/// are read relative to `base_dir`.
+pub async fn load_fixtures(
+    state: &AppState,
+    fixtures: &Fixtures,
+    base_dir: &Path,
+) -> Result<LoadReport, String> {
+    let mut report: LoadReport = LoadReport::default();
+    let transaction = state.database.begin().await.map_err(|e| e.to_string())?;
+
+    // Users, hashing each distinct password once
+    let emails: Vec<String> = unique_keys(fixtures.users.iter().map(|user| &user.email));
+    let mut user_ids =
+        ids_by_key::<UserEntity, _, _>(&transaction, UserColumn::Email, UserColumn::UserId, emails)
+            .await?;
+    let mut hashes: HashMap<&str, String> = HashMap::new();
+    let mut active_models: Vec<UserActiveModel> = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    for user in &fixtures.users {
+        if user_ids.contains_key(&user.email) || !seen.insert(&user.email) {
+            continue;
+        }
+        if !hashes.contains_key(user.password.as_str()) {
+            let hashed_password: String =
+                create_password(&user.password, &state.config.password_salt)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            hashes.insert(&user.password, hashed_password);
+        }
+        active_models.push(UserActiveModel {
+            name: Set(user.name.clone()),
+            email: Set(user.email.clone()),
+            password: Set(hashes[user.password.as_str()].clone()),
+            phone: Set(user.phone.clone()),
+            is_admin: Set(user.is_admin),
+            ..Default::default()
+        });
+    }
+    report.users = insert_batches(&transaction, active_models).await?;
+
+    // Categories
+    let names: Vec<String> = unique_keys(
+        fixtures
+            .categories
+            .iter()
+            .map(|category| &category.name)
+            .chain(fixtures.retreats.iter().map(|retreat| &retreat.category)),
+    );
+    let category_ids = ids_by_key::<CategoryEntity, _, _>(
+        &transaction,
+        CategoryColumn::Name,
+        CategoryColumn::CategoryId,
+        names.clone(),
+    )
+    .await?;
+    let mut seen: HashSet<&str> = HashSet::new();
+    let active_models: Vec<CategoryActiveModel> = fixtures
+        .categories
+        .iter()
+        .filter(|category| {
+            !category_ids.contains_key(&category.name) && seen.insert(&category.name)
+        })
+        .map(|category| CategoryActiveModel {
+            name: Set(category.name.clone()),
+            description: Set(category.description.clone()),
+            ..Default::default()
+        })
+        .collect();
+    report.categories = insert_batches(&transaction, active_models).await?;
+    let category_ids = ids_by_key::<CategoryEntity, _, _>(
+        &transaction,
+        CategoryColumn::Name,
+        CategoryColumn::CategoryId,
+        names,
+    )
+    .await?;
+
+    // Gallery categories
+    let names: Vec<String> = unique_keys(
+        fixtures
+            .gallery_categories
+            .iter()
+            .map(|category| &category.name)
+            .chain(
+                fixtures
+                    .gallery
+                    .iter()
+                    .filter_map(|gallery| gallery.gallery_category.as_ref()),
+            ),
+    );
+    let gallery_category_ids = ids_by_key::<GalleryCategoriesEntity, _, _>(
+        &transaction,
+        GalleryCategoriesColumn::Name,
+        GalleryCategoriesColumn::GalleryCategoryId,
+        names.clone(),
+    )
+    .await?;
+    let mut seen: HashSet<&str> = HashSet::new();
+    let active_models: Vec<GalleryCategoriesActiveModel> = fixtures
+        .gallery_categories
+        .iter()
+        .filter(|category| {
+            !gallery_category_ids.contains_key(&category.name) && seen.insert(&category.name)
+        })
+        .map(|category| GalleryCategoriesActiveModel {
+            name: Set(category.name.clone()),
+            ..Default::default()
+        })
+        .collect();
+    report.gallery_categories = insert_batches(&transaction, active_models).await?;
+    let gallery_category_ids = ids_by_key::<GalleryCategoriesEntity, _, _>(
+        &transaction,
+        GalleryCategoriesColumn::Name,
+        GalleryCategoriesColumn::GalleryCategoryId,
+        names,
+    )
+    .await?;
+
+    // Retreats
+    let slugs: Vec<String> = unique_keys(
+        fixtures
+            .retreats
+            .iter()
+            .map(|retreat| &retreat.slug)
+            .chain(fixtures.staff.iter().map(|staff| &staff.retreat))
+            .chain(fixtures.reviews.iter().map(|review| &review.retreat))
+            .chain(fixtures.gallery.iter().map(|gallery| &gallery.retreat))
+            .chain(fixtures.wishlists.iter().map(|wishlist| &wishlist.retreat)),
+    );
+    let retreat_ids = ids_by_key::<RetreatEntity, _, _>(
+        &transaction,
+        RetreatColumn::Slug,
+        RetreatColumn::RetreatId,
+        slugs.clone(),
+    )
+    .await?;
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut active_models: Vec<RetreatActiveModel> = Vec::new();
+    for (index, retreat) in fixtures.retreats.iter().enumerate() {
+        if retreat_ids.contains_key(&retreat.slug) || !seen.insert(&retreat.slug) {
+            continue;
+        }
+        let by: String = format!("retreats[{index}]");
+        active_models.push(RetreatActiveModel {
+            name: Set(retreat.name.clone()),
+            slug: Set(retreat.slug.clone()),
+            category_id: Set(resolve(&category_ids, "Category", &retreat.category, &by)?),
+            description: Set(retreat.description.clone()),
+            email: Set(retreat.email.clone()),
+            phone: Set(retreat.phone.clone()),
+            address: Set(retreat.address.clone()),
+            latitude: Set(retreat.latitude),
+            longitude: Set(retreat.longitude),
+            budget_min: Set(retreat.budget_min),
+            budget_max: Set(retreat.budget_max),
+            currency: Set(retreat
+                .currency
+                .clone()
+                .unwrap_or_else(|| state.config.base_currency.clone())),
+            is_published: Set(retreat.is_published),
+            social_links: Set(retreat.social_links.clone()),
+            ..Default::default()
+        });
+    }
+    report.retreats = insert_batches(&transaction, active_models).await?;
+    let retreat_ids = ids_by_key::<RetreatEntity, _, _>(
+        &transaction,
+        RetreatColumn::Slug,
+        RetreatColumn::RetreatId,
+        slugs,
+    )
+    .await?;
+
+    // Every user referenced from here on, the ones loaded above included
+    let emails: Vec<String> = unique_keys(
+        fixtures
+            .staff
+            .iter()
+            .map(|staff| &staff.user)
+            .chain(fixtures.reviews.iter().map(|review| &review.user))
+            .chain(fixtures.wishlists.iter().map(|wishlist| &wishlist.user)),
+    );
+    user_ids.extend(
+        ids_by_key::<UserEntity, _, _>(&transaction, UserColumn::Email, UserColumn::UserId, emails)
+            .await?,
+    );
+    let all_retreat_ids: Vec<i64> = retreat_ids.values().copied().collect();
+
+    // Staff
+    let mut linked = linked_pairs::<RetreatUserEntity, _>(
+        &transaction,
+        RetreatUserColumn::RetreatId,
+        RetreatUserColumn::UserId,
+        all_retreat_ids.clone(),
+    )
+    .await?;
+    let mut active_models: Vec<RetreatUserActiveModel> = Vec::new();
+    for (index, staff) in fixtures.staff.iter().enumerate() {
+        let by: String = format!("staff[{index}]");
+        let retreat_id: i64 = resolve(&retreat_ids, "Retreat", &staff.retreat, &by)?;
+        let user_id: i64 = resolve(&user_ids, "User", &staff.user, &by)?;
+        if linked.insert((retreat_id, user_id)) {
+            active_models.push(RetreatUserActiveModel {
+                retreat_id: Set(retreat_id),
+                user_id: Set(user_id),
+                is_owner: Set(staff.is_owner),
+                role: Set(staff.role.clone()),
+                ..Default::default()
+            });
+        }
+    }
+    report.staff = insert_batches(&transaction, active_models).await?;
+
+    // Reviews, one per user and retreat
+    let mut linked = linked_pairs::<RetreatReviewEntity, _>(
+        &transaction,
+        RetreatReviewColumn::RetreatId,
+        RetreatReviewColumn::UserId,
+        all_retreat_ids.clone(),
+    )
+    .await?;
+    let mut active_models: Vec<RetreatReviewActiveModel> = Vec::new();
+    for (index, review) in fixtures.reviews.iter().enumerate() {
+        let by: String = format!("reviews[{index}]");
+        let retreat_id: i64 = resolve(&retreat_ids, "Retreat", &review.retreat, &by)?;
+        let user_id: i64 = resolve(&user_ids, "User", &review.user, &by)?;
+        if linked.insert((retreat_id, user_id)) {
+            active_models.push(RetreatReviewActiveModel {
+                retreat_id: Set(retreat_id),
+                user_id: Set(user_id),
+                rating: Set(review.rating),
+                review: Set(review.review.clone()),
+                ..Default::default()
+            });
+        }
+    }
+    report.reviews = insert_batches(&transaction, active_models).await?;
+
+    // Gallery, each image stored under a path derived from the retreat and file name
+    let mut image_paths: HashMap<String, (i64, &GalleryFixture)> = HashMap::new();
+    for (index, gallery) in fixtures.gallery.iter().enumerate() {
+        let by: String = format!("gallery[{index}]");
+        let retreat_id: i64 = resolve(&retreat_ids, "Retreat", &gallery.retreat, &by)?;
+        let file_name: &str = gallery
+            .image
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .ok_or_else(|| format!("{by} has no image file name"))?;
+        let image_path: String =
+            format!("retreat/gallery/fixtures/{}/{file_name}", gallery.retreat);
+        image_paths
+            .entry(image_path)
+            .or_insert((retreat_id, gallery));
+    }
+    let existing = ids_by_key::<RetreatGalleriesEntity, _, _>(
+        &transaction,
+        RetreatGalleriesColumn::ImagePath,
+        RetreatGalleriesColumn::GalleryId,
+        image_paths.keys().cloned().collect(),
+    )
+    .await?;
+    let mut active_models: Vec<RetreatGalleriesActiveModel> = Vec::new();
+    for (image_path, (retreat_id, gallery)) in image_paths {
+        if existing.contains_key(&image_path) {
+            continue;
+        }
+        let gallery_category_id: Option<i64> = match &gallery.gallery_category {
+            Some(name) => Some(resolve(
+                &gallery_category_ids,
+                "Gallery category",
+                name,
+                &format!("the gallery image {image_path}"),
+            )?),
+            None => None,
+        };
+        let target: PathBuf = state.storage.upload_dir().join(&image_path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let source: PathBuf = base_dir.join(&gallery.image);
+        fs::copy(&source, &target)
+            .await
+            .map_err(|e| format!("Failed to copy {}: {e}", source.display()))?;
+        active_models.push(RetreatGalleriesActiveModel {
+            retreat_id: Set(retreat_id),
+            image_path: Set(image_path),
+            caption: Set(gallery.caption.clone()),
+            order: Set(gallery.order),
+            gallery_category_id: Set(gallery_category_id),
+            ..Default::default()
+        });
+    }
+    report.gallery = insert_batches(&transaction, active_models).await?;
+
+    // Wishlists
+    let mut linked = linked_pairs::<WishlistEntity, _>(
+        &transaction,
+        WishlistColumn::RetreatId,
+        WishlistColumn::UserId,
+        all_retreat_ids,
+    )
+    .await?;
+    let mut active_models: Vec<WishlistActiveModel> = Vec::new();
+    for (index, wishlist) in fixtures.wishlists.iter().enumerate() {
+        let by: String = format!("wishlists[{index}]");
+        let retreat_id: i64 = resolve(&retreat_ids, "Retreat", &wishlist.retreat, &by)?;
+        let user_id: i64 = resolve(&user_ids, "User", &wishlist.user, &by)?;
+        if linked.insert((retreat_id, user_id)) {
+            active_models.push(WishlistActiveModel {
+                retreat_id: Set(retreat_id),
+                user_id: Set(user_id),
+                ..Default::default()
+            });
+        }
+    }
+    report.wishlists = insert_batches(&transaction, active_models).await?;
+
+    transaction.commit().await.map_err(|e| e.to_string())?;
+    Ok(report)
+}
@@ -0,0 +1,177 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::{Context, dataloader::Loader};
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    entities_helper::{
+        CategoryColumn, CategoryEntity, RetreatColumn, RetreatEntity, RetreatGalleriesColumn,
+        RetreatGalleriesEntity, RetreatReviewColumn, RetreatReviewEntity, UserColumn, UserEntity,
+    },
+    graphql::types::{CategoryObject, GalleryObject, RetreatObject, ReviewObject, UserObject},
+    utils::{database::ReplicatedDatabase, soft_delete::SoftDelete},
+};
+
+pub type DataLoader = async_graphql::dataloader::DataLoader<DatabaseLoader>;
+
+/// Batches relation lookups made while resolving one request into a query per relation.
+pub struct DatabaseLoader {
+    database: ReplicatedDatabase,
+}
+
+impl DatabaseLoader {
+    pub fn new(database: ReplicatedDatabase) -> Self {
+        Self { database }
+    }
+}
+
+pub fn loader<'a>(ctx: &Context<'a>) -> &'a DataLoader {
+    ctx.data_unchecked::<DataLoader>()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UserKey(pub i64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CategoryKey(pub i64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RetreatKey(pub i64);
+
+/// Retreats of a category.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CategoryRetreatsKey(pub i64);
+
+/// Galleries of a retreat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RetreatGalleriesKey(pub i64);
+
+/// Reviews of a retreat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RetreatReviewsKey(pub i64);
+
+impl Loader<UserKey> for DatabaseLoader {
+    type Value = UserObject;
+    type Error = Arc<DbErr>;
+
+    async fn load(&self, keys: &[UserKey]) -> Result<HashMap<UserKey, UserObject>, Arc<DbErr>> {
+        let instances = UserEntity::find_active()
+            .filter(UserColumn::UserId.is_in(keys.iter().map(|key| key.0)))
+            .all(&self.database)
+            .await?;
+        Ok(instances
+            .into_iter()
+            .map(|model| (UserKey(model.user_id), model.into()))
+            .collect())
+    }
+}
+
+impl Loader<CategoryKey> for DatabaseLoader {
+    type Value = CategoryObject;
+    type Error = Arc<DbErr>;
+
+    async fn load(
+        &self,
+        keys: &[CategoryKey],
+    ) -> Result<HashMap<CategoryKey, CategoryObject>, Arc<DbErr>> {
+        let instances = CategoryEntity::find()
+            .filter(CategoryColumn::CategoryId.is_in(keys.iter().map(|key| key.0)))
+            .all(&self.database)
+            .await?;
+        Ok(instances
+            .into_iter()
+            .map(|model| (CategoryKey(model.category_id), model.into()))
+            .collect())
+    }
+}
+
+impl Loader<RetreatKey> for DatabaseLoader {
+    type Value = RetreatObject;
+    type Error = Arc<DbErr>;
+
+    async fn load(
+        &self,
+        keys: &[RetreatKey],
+    ) -> Result<HashMap<RetreatKey, RetreatObject>, Arc<DbErr>> {
+        let instances = RetreatEntity::find_active()
+            .filter(RetreatColumn::RetreatId.is_in(keys.iter().map(|key| key.0)))
+            .all(&self.database)
+            .await?;
+        Ok(instances
+            .into_iter()
+            .map(|model| (RetreatKey(model.retreat_id), model.into()))
+            .collect())
+    }
+}
+
+impl Loader<CategoryRetreatsKey> for DatabaseLoader {
+    type Value = Vec<RetreatObject>;
+    type Error = Arc<DbErr>;
+
+    async fn load(
+        &self,
+        keys: &[CategoryRetreatsKey],
+    ) -> Result<HashMap<CategoryRetreatsKey, Vec<RetreatObject>>, Arc<DbErr>> {
+        let instances = RetreatEntity::find_active()
+            .filter(RetreatColumn::CategoryId.is_in(keys.iter().map(|key| key.0)))
+            .order_by_asc(RetreatColumn::RetreatId)
+            .all(&self.database)
+            .await?;
+        let mut grouped: HashMap<CategoryRetreatsKey, Vec<RetreatObject>> = HashMap::new();
+        for model in instances {
+            grouped
+                .entry(CategoryRetreatsKey(model.category_id))
+                .or_default()
+                .push(model.into());
+        }
+        Ok(grouped)
+    }
+}
+
+impl Loader<RetreatGalleriesKey> for DatabaseLoader {
+    type Value = Vec<GalleryObject>;
+    type Error = Arc<DbErr>;
+
+    async fn load(
+        &self,
+        keys: &[RetreatGalleriesKey],
+    ) -> Result<HashMap<RetreatGalleriesKey, Vec<GalleryObject>>, Arc<DbErr>> {
+        let instances = RetreatGalleriesEntity::find_active()
+            .filter(RetreatGalleriesColumn::RetreatId.is_in(keys.iter().map(|key| key.0)))
+            .order_by_asc(RetreatGalleriesColumn::GalleryId)
+            .all(&self.database)
+            .await?;
+        let mut grouped: HashMap<RetreatGalleriesKey, Vec<GalleryObject>> = HashMap::new();
+        for model in instances {
+            grouped
+                .entry(RetreatGalleriesKey(model.retreat_id))
+                .or_default()
+                .push(model.into());
+        }
+        Ok(grouped)
+    }
+}
+
+impl Loader<RetreatReviewsKey> for DatabaseLoader {
+    type Value = Vec<ReviewObject>;
+    type Error = Arc<DbErr>;
+
+    async fn load(
+        &self,
+        keys: &[RetreatReviewsKey],
+    ) -> Result<HashMap<RetreatReviewsKey, Vec<ReviewObject>>, Arc<DbErr>> {
+        let instances = RetreatReviewEntity::find_active()
+            .filter(RetreatReviewColumn::RetreatId.is_in(keys.iter().map(|key| key.0)))
+            .order_by_asc(RetreatReviewColumn::ReviewId)
+            .all(&self.database)
+            .await?;
+        let mut grouped: HashMap<RetreatReviewsKey, Vec<ReviewObject>> = HashMap::new();
+        for model in instances {
+            grouped
+                .entry(RetreatReviewsKey(model.retreat_id))
+                .or_default()
+                .push(model.into());
+        }
+        Ok(grouped)
+    }
+}
@@ -0,0 +1,115 @@
+pub mod loaders;
+pub mod mutation;
+pub mod query;
+pub mod types;
+
+use async_graphql::{
+    Context, EmptySubscription, Error, ErrorExtensions, OutputType, Result, Schema,
+    connection::{self, Connection, Edge, OpaqueCursor},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Select};
+
+use crate::{
+    config::Config,
+    entities_helper::UserModel,
+    graphql::{mutation::MutationRoot, query::QueryRoot},
+    services::ServiceError,
+    state::AppState,
+    utils::database::ReplicatedDatabase,
+};
+
+pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// The authenticated user making the request, if any.
+pub struct Viewer(pub Option<UserModel>);
+
+pub fn build_schema(config: &Config) -> AppSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_complexity(config.graphql_max_complexity)
+        .limit_depth(config.graphql_max_depth)
+        .finish()
+}
+
+pub fn state<'a>(ctx: &Context<'a>) -> &'a AppState {
+    ctx.data_unchecked::<AppState>()
+}
+
+pub fn database<'a>(ctx: &Context<'a>) -> &'a ReplicatedDatabase {
+    &state(ctx).database
+}
+
+/// Same rule as the `AuthUser` extractor on the REST side.
+pub fn require_user<'a>(ctx: &Context<'a>) -> Result<&'a UserModel> {
+    ctx.data_unchecked::<Viewer>().0.as_ref().ok_or_else(|| {
+        Error::new("Authentication required.").extend_with(|_, e| e.set("code", "UNAUTHENTICATED"))
+    })
+}
+
+/// The signed in user's id, `None` for anonymous requests.
+pub fn viewer_id(ctx: &Context<'_>) -> Option<i64> {
+    ctx.data_unchecked::<Viewer>().0.as_ref().map(|user| user.user_id)
+}
+
+pub fn invalid_input<E: std::fmt::Display>(e: E) -> Error {
+    Error::new(e.to_string()).extend_with(|_, e| e.set("code", "BAD_USER_INPUT"))
+}
+
+/// The same failures the REST side answers with 404, 400, 403, 412 and 409.
+pub fn service_error(e: ServiceError) -> Error {
+    let code: &str = match e {
+        ServiceError::NotFound(_) => "NOT_FOUND",
+        ServiceError::Invalid(_) => "BAD_USER_INPUT",
+        ServiceError::Forbidden(_) => "FORBIDDEN",
+        ServiceError::Modified | ServiceError::Conflict(_) => "CONFLICT",
+        ServiceError::Database(_) => "INTERNAL_SERVER_ERROR",
+    };
+    Error::new(e.to_string()).extend_with(|_, e| e.set("code", code))
+}
+
+/// Forward cursor pagination ordered by primary key, the cursor is the last id seen.
+pub async fn paginate<E, N, F>(
+    database: &ReplicatedDatabase,
+    select: Select<E>,
+    id_column: E::Column,
+    id_of: F,
+    after: Option<String>,
+    first: Option<i32>,
+) -> Result<Connection<OpaqueCursor<i64>, N>>
+where
+    E: EntityTrait,
+    N: From<E::Model> + OutputType,
+    F: Fn(&E::Model) -> i64,
+{
+    connection::query(
+        after,
+        None,
+        first,
+        None,
+        |after: Option<OpaqueCursor<i64>>,
+         _before: Option<OpaqueCursor<i64>>,
+         first: Option<usize>,
+         _last: Option<usize>| async move {
+            let limit: usize = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+            let mut select = select.order_by_asc(id_column);
+            if let Some(after) = &after {
+                select = select.filter(id_column.gt(after.0));
+            }
+            // One extra row tells whether there is a next page.
+            let mut instances = select.limit(limit as u64 + 1).all(database).await?;
+            let has_next_page: bool = instances.len() > limit;
+            instances.truncate(limit);
+
+            let mut connection = Connection::new(after.is_some(), has_next_page);
+            connection.edges.extend(
+                instances
+                    .into_iter()
+                    .map(|model| Edge::new(OpaqueCursor(id_of(&model)), N::from(model))),
+            );
+            Ok::<_, Error>(connection)
+        },
+    )
+    .await
+}
@@ -0,0 +1,324 @@
+use async_graphql::{Context, InputObject, Json, MaybeUndefined, Object, Result};
+use sea_orm::prelude::Decimal;
+use serde_json::Value as JsonValue;
+use validator::Validate;
+
+use crate::{
+    entities_helper::{CategoryModel, RetreatModel, RetreatReviewModel},
+    graphql::{
+        database, invalid_input, require_user, service_error, state,
+        types::{CategoryObject, RetreatObject, ReviewObject, WishlistObject},
+        viewer_id,
+    },
+    serializers::{
+        categories::{CreateCategorySerializer, UpdateCategorySerializer},
+        retreat_reviews::{CreateRetreatReviewSerializer, UpdateRetreatReviewSerializer},
+        retreats::{CreateRetreatSerializer, UpdateRetreatSerializer},
+    },
+    services::{categories, retreats, reviews, wishlists},
+    utils::cache::CacheTag,
+};
+
+#[derive(InputObject)]
+pub struct CreateCategoryInput {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl From<CreateCategoryInput> for CreateCategorySerializer {
+    fn from(value: CreateCategoryInput) -> Self {
+        CreateCategorySerializer {
+            name: value.name,
+            description: value.description,
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub struct UpdateCategoryInput {
+    pub name: Option<String>,
+    pub description: MaybeUndefined<String>,
+}
+
+impl From<UpdateCategoryInput> for UpdateCategorySerializer {
+    fn from(value: UpdateCategoryInput) -> Self {
+        UpdateCategorySerializer {
+            name: value.name,
+            description: value.description.into(),
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub struct CreateRetreatInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub category_id: i64,
+    pub slug: String,
+    pub social_links: Json<JsonValue>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub latitude: Option<Decimal>,
+    pub longitude: Option<Decimal>,
+    pub address: Option<String>,
+    pub organization_id: Option<i64>,
+    pub currency: Option<String>,
+}
+
+impl From<CreateRetreatInput> for CreateRetreatSerializer {
+    fn from(value: CreateRetreatInput) -> Self {
+        CreateRetreatSerializer {
+            name: value.name,
+            description: value.description,
+            category_id: value.category_id,
+            slug: value.slug,
+            social_links: value.social_links.0,
+            email: value.email,
+            phone: value.phone,
+            latitude: value.latitude,
+            longitude: value.longitude,
+            address: value.address,
+            organization_id: value.organization_id,
+            currency: value.currency,
+        }
+    }
+}
+
+/// Omitted fields are left as they are, `null` clears a nullable field.
+#[derive(InputObject)]
+pub struct UpdateRetreatInput {
+    pub name: Option<String>,
+    pub description: MaybeUndefined<String>,
+    pub category_id: Option<i64>,
+    pub slug: Option<String>,
+    pub social_links: Option<Json<JsonValue>>,
+    pub email: MaybeUndefined<String>,
+    pub phone: MaybeUndefined<String>,
+    pub latitude: MaybeUndefined<Decimal>,
+    pub longitude: MaybeUndefined<Decimal>,
+    pub address: MaybeUndefined<String>,
+    pub budget_min: MaybeUndefined<Decimal>,
+    pub budget_max: MaybeUndefined<Decimal>,
+    pub currency: Option<String>,
+    pub is_published: Option<bool>,
+    pub organization_id: MaybeUndefined<i64>,
+}
+
+impl From<UpdateRetreatInput> for UpdateRetreatSerializer {
+    fn from(value: UpdateRetreatInput) -> Self {
+        UpdateRetreatSerializer {
+            name: value.name,
+            description: value.description.into(),
+            category_id: value.category_id,
+            slug: value.slug,
+            social_links: value.social_links.map(|links| links.0),
+            email: value.email.into(),
+            phone: value.phone.into(),
+            latitude: value.latitude.into(),
+            longitude: value.longitude.into(),
+            address: value.address.into(),
+            budget_min: value.budget_min.into(),
+            budget_max: value.budget_max.into(),
+            currency: value.currency,
+            is_published: value.is_published,
+            organization_id: value.organization_id.into(),
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub struct CreateReviewInput {
+    pub rating: f64,
+    pub review: Option<String>,
+}
+
+impl From<CreateReviewInput> for CreateRetreatReviewSerializer {
+    fn from(value: CreateReviewInput) -> Self {
+        CreateRetreatReviewSerializer {
+            rating: value.rating,
+            review: value.review,
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub struct UpdateReviewInput {
+    pub rating: Option<f64>,
+    pub review: MaybeUndefined<String>,
+}
+
+impl From<UpdateReviewInput> for UpdateRetreatReviewSerializer {
+    fn from(value: UpdateReviewInput) -> Self {
+        UpdateRetreatReviewSerializer {
+            rating: value.rating,
+            review: value.review.into(),
+        }
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object(name = "Mutation")]
+impl MutationRoot {
+    async fn create_category(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateCategoryInput,
+    ) -> Result<CategoryObject> {
+        let payload: CreateCategorySerializer = input.into();
+        payload.validate().map_err(invalid_input)?;
+        let instance: CategoryModel = categories::create_category(database(ctx), payload)
+            .await
+            .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Categories);
+        Ok(instance.into())
+    }
+
+    async fn update_category(
+        &self,
+        ctx: &Context<'_>,
+        category_id: i64,
+        input: UpdateCategoryInput,
+    ) -> Result<CategoryObject> {
+        let payload: UpdateCategorySerializer = input.into();
+        payload.validate().map_err(invalid_input)?;
+        let instance: CategoryModel = find_category(ctx, category_id).await?;
+        let instance: CategoryModel =
+            categories::update_category(database(ctx), instance, payload)
+                .await
+                .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Categories);
+        Ok(instance.into())
+    }
+
+    async fn delete_category(&self, ctx: &Context<'_>, category_id: i64) -> Result<bool> {
+        let instance: CategoryModel = find_category(ctx, category_id).await?;
+        categories::delete_category(database(ctx), instance)
+            .await
+            .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Categories);
+        Ok(true)
+    }
+
+    async fn create_retreat(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateRetreatInput,
+    ) -> Result<RetreatObject> {
+        let payload: CreateRetreatSerializer = input.into();
+        payload.validate().map_err(invalid_input)?;
+        let instance: RetreatModel = retreats::create_retreat(
+            database(ctx),
+            payload,
+            &state(ctx).config.base_currency,
+            viewer_id(ctx),
+        )
+        .await
+        .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Retreats);
+        Ok(instance.into())
+    }
+
+    async fn update_retreat(
+        &self,
+        ctx: &Context<'_>,
+        retreat_id: i64,
+        input: UpdateRetreatInput,
+    ) -> Result<RetreatObject> {
+        let payload: UpdateRetreatSerializer = input.into();
+        payload.validate().map_err(invalid_input)?;
+        let instance: RetreatModel = find_retreat(ctx, retreat_id).await?;
+        let instance: RetreatModel =
+            retreats::update_retreat(database(ctx), instance, payload, viewer_id(ctx))
+                .await
+                .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Retreats);
+        Ok(instance.into())
+    }
+
+    async fn delete_retreat(&self, ctx: &Context<'_>, retreat_id: i64) -> Result<bool> {
+        let instance: RetreatModel = find_retreat(ctx, retreat_id).await?;
+        retreats::delete_retreat(database(ctx), instance, state(ctx).clock.now_fixed())
+            .await
+            .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Retreats);
+        Ok(true)
+    }
+
+    async fn create_review(
+        &self,
+        ctx: &Context<'_>,
+        retreat_id: i64,
+        input: CreateReviewInput,
+    ) -> Result<ReviewObject> {
+        let user = require_user(ctx)?;
+        let payload: CreateRetreatReviewSerializer = input.into();
+        payload.validate().map_err(invalid_input)?;
+        let instance: RetreatReviewModel =
+            reviews::post_review(database(ctx), retreat_id, user.user_id, payload)
+                .await
+                .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Reviews);
+        Ok(instance.into())
+    }
+
+    async fn update_review(
+        &self,
+        ctx: &Context<'_>,
+        review_id: i64,
+        input: UpdateReviewInput,
+    ) -> Result<ReviewObject> {
+        let payload: UpdateRetreatReviewSerializer = input.into();
+        payload.validate().map_err(invalid_input)?;
+        let instance: RetreatReviewModel = find_own_review(ctx, review_id).await?;
+        let instance: RetreatReviewModel = reviews::update_review(database(ctx), instance, payload)
+            .await
+            .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Reviews);
+        Ok(instance.into())
+    }
+
+    async fn delete_review(&self, ctx: &Context<'_>, review_id: i64) -> Result<bool> {
+        let instance: RetreatReviewModel = find_own_review(ctx, review_id).await?;
+        reviews::delete_review(database(ctx), instance, state(ctx).clock.now_fixed())
+            .await
+            .map_err(service_error)?;
+        state(ctx).cache.invalidate(CacheTag::Reviews);
+        Ok(true)
+    }
+
+    async fn add_to_wishlist(&self, ctx: &Context<'_>, retreat_id: i64) -> Result<WishlistObject> {
+        let user = require_user(ctx)?;
+        let instance = wishlists::add_to_wishlist(database(ctx), retreat_id, user.user_id)
+            .await
+            .map_err(service_error)?;
+        Ok(instance.into())
+    }
+
+    async fn remove_from_wishlist(&self, ctx: &Context<'_>, retreat_id: i64) -> Result<bool> {
+        let user = require_user(ctx)?;
+        wishlists::remove_from_wishlist(database(ctx), retreat_id, user.user_id)
+            .await
+            .map_err(service_error)?;
+        Ok(true)
+    }
+}
+
+async fn find_category(ctx: &Context<'_>, category_id: i64) -> Result<CategoryModel> {
+    categories::find_category(database(ctx), category_id)
+        .await
+        .map_err(service_error)
+}
+
+async fn find_retreat(ctx: &Context<'_>, retreat_id: i64) -> Result<RetreatModel> {
+    retreats::find_retreat(database(ctx), retreat_id)
+        .await
+        .map_err(service_error)
+}
+
+async fn find_own_review(ctx: &Context<'_>, review_id: i64) -> Result<RetreatReviewModel> {
+    let user = require_user(ctx)?;
+    reviews::find_own_review(database(ctx), None, review_id, user.user_id)
+        .await
+        .map_err(service_error)
+}
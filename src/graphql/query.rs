@@ -0,0 +1,153 @@
+use async_graphql::{
+    Context, Object, Result,
+    connection::{Connection, OpaqueCursor},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+use crate::{
+    entities_helper::{
+        CategoryColumn, CategoryEntity, RetreatColumn, RetreatEntity, RetreatReviewColumn,
+        RetreatReviewEntity, UserColumn, UserEntity, WishlistColumn, WishlistEntity,
+    },
+    graphql::{
+        DEFAULT_PAGE_SIZE, database,
+        loaders::{CategoryKey, RetreatKey, UserKey, loader},
+        paginate, require_user,
+        types::{CategoryObject, RetreatObject, ReviewObject, UserObject, WishlistObject},
+    },
+    utils::soft_delete::SoftDelete,
+};
+
+pub struct QueryRoot;
+
+#[Object(name = "Query")]
+impl QueryRoot {
+    async fn me(&self, ctx: &Context<'_>) -> Result<UserObject> {
+        Ok(require_user(ctx)?.clone().into())
+    }
+
+    async fn user(&self, ctx: &Context<'_>, user_id: i64) -> Result<Option<UserObject>> {
+        Ok(loader(ctx).load_one(UserKey(user_id)).await?)
+    }
+
+    #[graphql(
+        complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE as i32).max(0) as usize * child_complexity"
+    )]
+    async fn users(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<OpaqueCursor<i64>, UserObject>> {
+        paginate(
+            database(ctx),
+            UserEntity::find_active(),
+            UserColumn::UserId,
+            |model| model.user_id,
+            after,
+            first,
+        )
+        .await
+    }
+
+    async fn category(
+        &self,
+        ctx: &Context<'_>,
+        category_id: i64,
+    ) -> Result<Option<CategoryObject>> {
+        Ok(loader(ctx).load_one(CategoryKey(category_id)).await?)
+    }
+
+    #[graphql(
+        complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE as i32).max(0) as usize * child_complexity"
+    )]
+    async fn categories(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<OpaqueCursor<i64>, CategoryObject>> {
+        paginate(
+            database(ctx),
+            CategoryEntity::find(),
+            CategoryColumn::CategoryId,
+            |model| model.category_id,
+            after,
+            first,
+        )
+        .await
+    }
+
+    async fn retreat(&self, ctx: &Context<'_>, retreat_id: i64) -> Result<Option<RetreatObject>> {
+        Ok(loader(ctx).load_one(RetreatKey(retreat_id)).await?)
+    }
+
+    #[graphql(
+        complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE as i32).max(0) as usize * child_complexity"
+    )]
+    async fn retreats(
+        &self,
+        ctx: &Context<'_>,
+        category_id: Option<i64>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<OpaqueCursor<i64>, RetreatObject>> {
+        let mut select = RetreatEntity::find_active();
+        if let Some(category_id) = category_id {
+            select = select.filter(RetreatColumn::CategoryId.eq(category_id));
+        }
+        paginate(
+            database(ctx),
+            select,
+            RetreatColumn::RetreatId,
+            |model| model.retreat_id,
+            after,
+            first,
+        )
+        .await
+    }
+
+    #[graphql(
+        complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE as i32).max(0) as usize * child_complexity"
+    )]
+    async fn reviews(
+        &self,
+        ctx: &Context<'_>,
+        retreat_id: i64,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<OpaqueCursor<i64>, ReviewObject>> {
+        paginate(
+            database(ctx),
+            RetreatReviewEntity::find_active()
+                .filter(RetreatReviewColumn::RetreatId.eq(retreat_id)),
+            RetreatReviewColumn::ReviewId,
+            |model| model.review_id,
+            after,
+            first,
+        )
+        .await
+    }
+
+    /// Wishlist of the authenticated user.
+    #[graphql(
+        complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE as i32).max(0) as usize * child_complexity"
+    )]
+    async fn wishlist(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<OpaqueCursor<i64>, WishlistObject>> {
+        let user = require_user(ctx)?;
+        paginate(
+            database(ctx),
+            WishlistEntity::find().filter(WishlistColumn::UserId.eq(user.user_id)),
+            WishlistColumn::WishlistId,
+            |model| model.wishlist_id,
+            after,
+            first,
+        )
+        .await
+    }
+}
@@ -0,0 +1,219 @@
+use async_graphql::{ComplexObject, Context, Json, Result, SimpleObject};
+use sea_orm::prelude::{DateTimeWithTimeZone, Decimal};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    entities_helper::{
+        CategoryModel, RetreatGalleriesModel, RetreatModel, RetreatReviewModel, UserModel,
+        WishlistModel,
+    },
+    graphql::{
+        DEFAULT_PAGE_SIZE,
+        loaders::{
+            CategoryKey, CategoryRetreatsKey, RetreatGalleriesKey, RetreatKey, RetreatReviewsKey,
+            UserKey, loader,
+        },
+    },
+    map_fields,
+};
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(name = "User")]
+pub struct UserObject {
+    pub user_id: i64,
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+}
+
+impl From<UserModel> for UserObject {
+    fn from(value: UserModel) -> Self {
+        map_fields!(value, UserObject, {
+            user_id,
+            name,
+            email,
+            phone
+        })
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(name = "Category", complex)]
+pub struct CategoryObject {
+    pub category_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl From<CategoryModel> for CategoryObject {
+    fn from(value: CategoryModel) -> Self {
+        map_fields!(value, CategoryObject, {
+            category_id,
+            name,
+            description
+        })
+    }
+}
+
+#[ComplexObject]
+impl CategoryObject {
+    // Unpaged lists are costed as a default page of their items
+    #[graphql(complexity = "DEFAULT_PAGE_SIZE * child_complexity")]
+    async fn retreats(&self, ctx: &Context<'_>) -> Result<Vec<RetreatObject>> {
+        let retreats = loader(ctx)
+            .load_one(CategoryRetreatsKey(self.category_id))
+            .await?;
+        Ok(retreats.unwrap_or_default())
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(name = "Retreat", complex)]
+pub struct RetreatObject {
+    pub retreat_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub category_id: i64,
+    pub slug: String,
+    pub social_links: Json<JsonValue>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub latitude: Option<Decimal>,
+    pub longitude: Option<Decimal>,
+    pub address: Option<String>,
+    pub budget_min: Option<Decimal>,
+    pub budget_max: Option<Decimal>,
+    pub currency: String,
+    pub is_published: bool,
+    pub organization_id: Option<i64>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl From<RetreatModel> for RetreatObject {
+    fn from(value: RetreatModel) -> Self {
+        RetreatObject {
+            retreat_id: value.retreat_id,
+            name: value.name,
+            description: value.description,
+            category_id: value.category_id,
+            slug: value.slug,
+            social_links: Json(value.social_links),
+            email: value.email,
+            phone: value.phone,
+            latitude: value.latitude,
+            longitude: value.longitude,
+            address: value.address,
+            budget_min: value.budget_min,
+            budget_max: value.budget_max,
+            currency: value.currency,
+            is_published: value.is_published,
+            organization_id: value.organization_id,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[ComplexObject]
+impl RetreatObject {
+    async fn category(&self, ctx: &Context<'_>) -> Result<Option<CategoryObject>> {
+        Ok(loader(ctx).load_one(CategoryKey(self.category_id)).await?)
+    }
+
+    #[graphql(complexity = "DEFAULT_PAGE_SIZE * child_complexity")]
+    async fn galleries(&self, ctx: &Context<'_>) -> Result<Vec<GalleryObject>> {
+        let galleries = loader(ctx)
+            .load_one(RetreatGalleriesKey(self.retreat_id))
+            .await?;
+        Ok(galleries.unwrap_or_default())
+    }
+
+    #[graphql(complexity = "DEFAULT_PAGE_SIZE * child_complexity")]
+    async fn reviews(&self, ctx: &Context<'_>) -> Result<Vec<ReviewObject>> {
+        let reviews = loader(ctx)
+            .load_one(RetreatReviewsKey(self.retreat_id))
+            .await?;
+        Ok(reviews.unwrap_or_default())
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(name = "Gallery")]
+pub struct GalleryObject {
+    pub gallery_id: i64,
+    pub retreat_id: i64,
+    pub caption: Option<String>,
+    pub order: Option<i32>,
+    pub gallery_category_id: Option<i64>,
+}
+
+impl From<RetreatGalleriesModel> for GalleryObject {
+    fn from(value: RetreatGalleriesModel) -> Self {
+        map_fields!(value, GalleryObject, {
+            gallery_id,
+            retreat_id,
+            caption,
+            order,
+            gallery_category_id
+        })
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(name = "Review", complex)]
+pub struct ReviewObject {
+    pub review_id: i64,
+    pub retreat_id: i64,
+    pub user_id: i64,
+    pub rating: f64,
+    pub review: Option<String>,
+}
+
+impl From<RetreatReviewModel> for ReviewObject {
+    fn from(value: RetreatReviewModel) -> Self {
+        map_fields!(value, ReviewObject, {
+            review_id,
+            retreat_id,
+            user_id,
+            rating,
+            review
+        })
+    }
+}
+
+#[ComplexObject]
+impl ReviewObject {
+    async fn user(&self, ctx: &Context<'_>) -> Result<Option<UserObject>> {
+        Ok(loader(ctx).load_one(UserKey(self.user_id)).await?)
+    }
+
+    async fn retreat(&self, ctx: &Context<'_>) -> Result<Option<RetreatObject>> {
+        Ok(loader(ctx).load_one(RetreatKey(self.retreat_id)).await?)
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(name = "Wishlist", complex)]
+pub struct WishlistObject {
+    pub wishlist_id: i64,
+    pub retreat_id: i64,
+    pub user_id: i64,
+}
+
+impl From<WishlistModel> for WishlistObject {
+    fn from(value: WishlistModel) -> Self {
+        map_fields!(value, WishlistObject, {
+            wishlist_id,
+            retreat_id,
+            user_id
+        })
+    }
+}
+
+#[ComplexObject]
+impl WishlistObject {
+    async fn retreat(&self, ctx: &Context<'_>) -> Result<Option<RetreatObject>> {
+        Ok(loader(ctx).load_one(RetreatKey(self.retreat_id)).await?)
+    }
+}
@@ -0,0 +1,57 @@
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, sea_query::Expr};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities_helper::{UserColumn, UserEntity},
+    jobs::Job,
+    state::AppState,
+    utils::{password::create_password, storage::remove_retreat_gallery},
+};
+
+/// Password invited staff sign in with until they change it.
+const INVITED_USER_PASSWORD: &str = "tempPassword";
+
+/// Removes a gallery image that is no longer referenced by any row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveGalleryImage {
+    pub image_path: String,
+}
+
+impl Job for RemoveGalleryImage {
+    const KIND: &'static str = "remove_gallery_image";
+    const QUEUE: &'static str = "media";
+
+    async fn run(self, state: AppState) -> Result<(), String> {
+        remove_retreat_gallery(state.storage.upload_dir(), &self.image_path)
+            .await
+            .map_err(|e| format!("Failed to remove {}: {e}", self.image_path))
+    }
+}
+
+/// Hashes the initial password of a user invited as retreat staff. Hashing is slow on
+/// purpose, so it stays out of the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetInvitedUserPassword {
+    pub user_id: i64,
+}
+
+impl Job for SetInvitedUserPassword {
+    const KIND: &'static str = "set_invited_user_password";
+
+    async fn run(self, state: AppState) -> Result<(), String> {
+        let hashed_password: String =
+            create_password(INVITED_USER_PASSWORD, &state.config.password_salt)
+                .await
+                .map_err(|e| e.to_string())?;
+
+        // Only while the password is still unset, never overwrite one the user chose
+        UserEntity::update_many()
+            .col_expr(UserColumn::Password, Expr::value(hashed_password))
+            .filter(UserColumn::UserId.eq(self.user_id))
+            .filter(UserColumn::Password.eq(""))
+            .exec(&state.database)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+pub mod handlers;
+mod worker;
+
+use std::{collections::HashMap, future::Future, pin::Pin};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ConnectionTrait, DbErr};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    entities_helper::{JobActiveModel, JobModel},
+    state::{AppState, Clock},
+};
+
+pub use worker::run_job_workers;
+
+pub const STATUS_QUEUED: &str = "queued";
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_SUCCEEDED: &str = "succeeded";
+/// Failed on every attempt, only a manual retry runs it again.
+pub const STATUS_DEAD: &str = "dead";
+pub const STATUS_CANCELLED: &str = "cancelled";
+
+/// A unit of background work. The job itself is the payload, stored as JSON until a
+/// worker of its queue picks it up.
+pub trait Job: Serialize + DeserializeOwned + Send + 'static {
+    /// Stored with the job to find its handler again, must be unique and never change.
+    const KIND: &'static str;
+    const QUEUE: &'static str = "default";
+    const MAX_ATTEMPTS: i32 = 5;
+
+    /// An `Err` is retried with backoff until `MAX_ATTEMPTS` is reached.
+    fn run(self, state: AppState) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+type JobFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+type JobHandler = fn(JsonValue, AppState) -> JobFuture;
+
+fn handle<J: Job>(payload: JsonValue, state: AppState) -> JobFuture {
+    Box::pin(async move {
+        let job: J = serde_json::from_value(payload)
+            .map_err(|e| format!("Invalid {} payload: {e}", J::KIND))?;
+        job.run(state).await
+    })
+}
+
+/// Maps stored job kinds back to their typed handlers.
+#[derive(Default)]
+pub struct JobRegistry {
+    handlers: HashMap<&'static str, (&'static str, JobHandler)>,
+}
+
+impl JobRegistry {
+    pub fn register<J: Job>(mut self) -> Self {
+        self.handlers.insert(J::KIND, (J::QUEUE, handle::<J>));
+        self
+    }
+
+    fn handler(&self, kind: &str) -> Option<JobHandler> {
+        self.handlers.get(kind).map(|(_, handler)| *handler)
+    }
+
+    fn queues(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.handlers
+            .iter()
+            .map(|(kind, (queue, _))| (*kind, *queue))
+    }
+}
+
+/// Every job the workers know how to run.
+pub fn registry() -> JobRegistry {
+    JobRegistry::default()
+        .register::<handlers::RemoveGalleryImage>()
+        .register::<handlers::SetInvitedUserPassword>()
+}
+
+/// Queues `job` to run as soon as a worker is free, by the same `clock` the workers claim
+/// jobs by. Pass a transaction to queue it only if the surrounding change commits.
+pub async fn enqueue<J, C>(database: &C, clock: &Clock, job: &J) -> Result<JobModel, DbErr>
+where
+    J: Job,
+    C: ConnectionTrait,
+{
+    enqueue_at(database, job, clock.now()).await
+}
+
+/// Queues `job` to run no earlier than `run_at`.
+pub async fn enqueue_at<J, C>(
+    database: &C,
+    job: &J,
+    run_at: DateTime<Utc>,
+) -> Result<JobModel, DbErr>
+where
+    J: Job,
+    C: ConnectionTrait,
+{
+    let payload: JsonValue = serde_json::to_value(job).map_err(|e| DbErr::Custom(e.to_string()))?;
+    JobActiveModel {
+        queue: Set(J::QUEUE.to_string()),
+        kind: Set(J::KIND.to_string()),
+        payload: Set(payload),
+        max_attempts: Set(J::MAX_ATTEMPTS),
+        run_at: Set(run_at.fixed_offset()),
+        ..Default::default()
+    }
+    .insert(database)
+    .await
+}
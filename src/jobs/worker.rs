@@ -0,0 +1,245 @@
+use std::{sync::Arc, time::Duration};
+
+use sea_orm::{
+    ActiveValue::Set,
+    ColumnTrait, DbErr, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, QuerySelect,
+    TransactionTrait,
+    prelude::DateTimeWithTimeZone,
+    sea_query::{Expr, LockBehavior, LockType},
+};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    task::JoinSet,
+};
+use uuid::Uuid;
+
+use crate::{
+    entities_helper::{JobActiveModel, JobColumn, JobEntity, JobModel},
+    jobs::{JobRegistry, STATUS_DEAD, STATUS_QUEUED, STATUS_RUNNING, STATUS_SUCCEEDED, registry},
+    state::AppState,
+    utils::backoff::retry_delay,
+};
+
+/// Runs the workers of every queue in `JOB_QUEUES` until shutdown.
+pub async fn run_job_workers(state: AppState) {
+    let registry: Arc<JobRegistry> = Arc::new(registry());
+    for (kind, queue) in registry.queues() {
+        if !state
+            .config
+            .job_queues
+            .iter()
+            .any(|(name, _)| name == queue)
+        {
+            eprintln!(
+                "Warning: jobs of kind `{kind}` go to queue `{queue}`, which JOB_QUEUES does not list."
+            );
+        }
+    }
+
+    // Shows up in `locked_by`, to tell which process is running a job
+    let worker_id: String = format!("{}-{}", std::process::id(), Uuid::new_v4().simple());
+    let mut queues: JoinSet<()> = JoinSet::new();
+    for (queue, concurrency) in state.config.job_queues.clone() {
+        queues.spawn(run_queue(
+            state.clone(),
+            registry.clone(),
+            worker_id.clone(),
+            queue,
+            concurrency,
+        ));
+    }
+    while queues.join_next().await.is_some() {}
+}
+
+async fn run_queue(
+    state: AppState,
+    registry: Arc<JobRegistry>,
+    worker_id: String,
+    queue: String,
+    concurrency: usize,
+) {
+    let slots: Arc<Semaphore> = Arc::new(Semaphore::new(concurrency));
+    let mut running: JoinSet<()> = JoinSet::new();
+    let period = Duration::from_secs(state.config.job_poll_interval_in_sec);
+    let mut interval = tokio::time::interval(period);
+    loop {
+        tokio::select! {
+            _ = state.shutdown.triggered() => break,
+            _ = interval.tick() => {}
+        }
+        while running.try_join_next().is_some() {}
+
+        if let Err(e) = requeue_abandoned(&state, &queue).await {
+            eprintln!("Warning: failed to requeue abandoned jobs of queue `{queue}`: {e}");
+        }
+
+        let free: usize = slots.available_permits();
+        if free == 0 {
+            continue;
+        }
+        let jobs: Vec<JobModel> = match claim(&state, &queue, &worker_id, free as u64).await {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                eprintln!("Warning: failed to claim jobs of queue `{queue}`: {e}");
+                continue;
+            }
+        };
+        for job in jobs {
+            let Ok(slot) = slots.clone().acquire_owned().await else {
+                break;
+            };
+            running.spawn(execute(state.clone(), registry.clone(), job, slot));
+        }
+    }
+
+    // Let running jobs finish, the shutdown drain timeout bounds how long this takes.
+    while running.join_next().await.is_some() {}
+}
+
+/// Marks up to `limit` due jobs as running. `SKIP LOCKED` lets every worker claim its own
+/// batch without waiting on, or double claiming, the rows another worker is claiming.
+/// SQLite leaves the clause out, as it only ever lets one transaction write at a time.
+async fn claim(
+    state: &AppState,
+    queue: &str,
+    worker_id: &str,
+    limit: u64,
+) -> Result<Vec<JobModel>, DbErr> {
+    let now: DateTimeWithTimeZone = state.clock.now_fixed();
+    let transaction = state.database.begin().await?;
+    let mut jobs: Vec<JobModel> = JobEntity::find()
+        .filter(JobColumn::Queue.eq(queue))
+        .filter(JobColumn::Status.eq(STATUS_QUEUED))
+        .filter(JobColumn::RunAt.lte(now))
+        .order_by_asc(JobColumn::RunAt)
+        .limit(limit)
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .all(&transaction)
+        .await?;
+    if !jobs.is_empty() {
+        JobEntity::update_many()
+            .col_expr(JobColumn::Status, Expr::value(STATUS_RUNNING))
+            .col_expr(JobColumn::Attempts, Expr::col(JobColumn::Attempts).add(1))
+            .col_expr(JobColumn::LockedAt, Expr::value(now))
+            .col_expr(JobColumn::LockedBy, Expr::value(worker_id))
+            .filter(JobColumn::JobId.is_in(jobs.iter().map(|job| job.job_id)))
+            .exec(&transaction)
+            .await?;
+    }
+    transaction.commit().await?;
+
+    for job in &mut jobs {
+        job.status = STATUS_RUNNING.to_string();
+        job.attempts += 1;
+        job.locked_at = Some(now);
+        job.locked_by = Some(worker_id.to_string());
+    }
+    Ok(jobs)
+}
+
+/// A worker that died mid-run leaves its jobs `running`. Once their lock is older than any
+/// job may take, they are queued again, or given up on if that was their last attempt.
+async fn requeue_abandoned(state: &AppState, queue: &str) -> Result<(), DbErr> {
+    let lock_timeout = Duration::from_secs(state.config.job_lock_timeout_in_sec);
+    let cutoff: DateTimeWithTimeZone = (state.clock.now() - lock_timeout).fixed_offset();
+    let abandoned = || {
+        JobEntity::update_many()
+            .col_expr(
+                JobColumn::LockedAt,
+                Expr::value(None::<DateTimeWithTimeZone>),
+            )
+            .col_expr(JobColumn::LockedBy, Expr::value(None::<String>))
+            .filter(JobColumn::Queue.eq(queue))
+            .filter(JobColumn::Status.eq(STATUS_RUNNING))
+            .filter(JobColumn::LockedAt.lt(cutoff))
+    };
+    abandoned()
+        .col_expr(JobColumn::Status, Expr::value(STATUS_DEAD))
+        .col_expr(
+            JobColumn::LastError,
+            Expr::value("Worker stopped during the last attempt."),
+        )
+        .col_expr(
+            JobColumn::FinishedAt,
+            Expr::value(state.clock.now_fixed()),
+        )
+        .filter(Expr::col(JobColumn::Attempts).gte(Expr::col(JobColumn::MaxAttempts)))
+        .exec(&state.database)
+        .await?;
+    abandoned()
+        .col_expr(JobColumn::Status, Expr::value(STATUS_QUEUED))
+        .exec(&state.database)
+        .await?;
+    Ok(())
+}
+
+async fn execute(
+    state: AppState,
+    registry: Arc<JobRegistry>,
+    job: JobModel,
+    _slot: OwnedSemaphorePermit,
+) {
+    let result: Result<(), String> = match registry.handler(&job.kind) {
+        // Possibly queued by a newer release, retry until a worker knows the kind
+        None => Err(format!(
+            "No handler registered for job kind `{}`.",
+            job.kind
+        )),
+        Some(handler) => {
+            // Bounded by the lock timeout, so the job is not requeued while still running.
+            // Its own task, so a panic fails the job instead of the worker.
+            let timeout = Duration::from_secs(state.config.job_lock_timeout_in_sec);
+            let task = tokio::spawn(tokio::time::timeout(
+                timeout,
+                handler(job.payload.clone(), state.clone()),
+            ));
+            match task.await {
+                Ok(Ok(result)) => result,
+                Ok(Err(_)) => Err(format!("Timed out after {} seconds.", timeout.as_secs())),
+                Err(e) => Err(format!("Job panicked: {e}")),
+            }
+        }
+    };
+
+    let job_id: i64 = job.job_id;
+    let attempts: i32 = job.attempts;
+    let max_attempts: i32 = job.max_attempts;
+    let locked_by: Option<String> = job.locked_by.clone();
+    let now: DateTimeWithTimeZone = state.clock.now_fixed();
+
+    let mut active_model: JobActiveModel = job.into_active_model();
+    active_model.locked_at = Set(None);
+    active_model.locked_by = Set(None);
+    match result {
+        Ok(()) => {
+            active_model.status = Set(STATUS_SUCCEEDED.to_string());
+            active_model.last_error = Set(None);
+            active_model.finished_at = Set(Some(now));
+        }
+        Err(error) => {
+            active_model.last_error = Set(Some(error));
+            if attempts >= max_attempts {
+                active_model.status = Set(STATUS_DEAD.to_string());
+                active_model.finished_at = Set(Some(now));
+            } else {
+                let delay: Duration = retry_delay(
+                    attempts as u32,
+                    Duration::from_secs(state.config.job_retry_base_in_sec),
+                    Duration::from_secs(state.config.job_retry_max_in_sec),
+                );
+                active_model.status = Set(STATUS_QUEUED.to_string());
+                active_model.run_at = Set(now + delay);
+            }
+        }
+    }
+
+    // Only while this worker still holds the job
+    let result = JobEntity::update(active_model)
+        .filter(JobColumn::Status.eq(STATUS_RUNNING))
+        .filter(JobColumn::LockedBy.eq(locked_by))
+        .exec(&state.database)
+        .await;
+    if let Err(e) = result {
+        eprintln!("Warning: failed to record the outcome of job {job_id}: {e}");
+    }
+}
@@ -1,43 +1,181 @@
+mod audit;
+pub mod commands;
+pub mod config;
 mod entities;
 mod entities_helper;
-mod env;
+mod events;
+mod fixtures;
+mod graphql;
+mod jobs;
+mod live;
 mod routes;
 mod serializers;
-mod state;
+pub mod services;
+pub mod state;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod utils;
+pub mod webhooks;
 
-use axum::Router;
-use tokio::net::TcpListener;
-use tower_http::{catch_panic::CatchPanicLayer, compression::CompressionLayer, cors::{Any, CorsLayer}};
+use std::{future::IntoFuture, sync::Arc, time::Duration};
 
-use crate::{state::AppState, utils::middlewares::panic::handle_panic};
+use axum::{Router, middleware::from_fn_with_state};
+use tokio::net::TcpListener;
+use tower_http::{catch_panic::CatchPanicLayer, compression::CompressionLayer};
 
-pub async fn run() {
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+use crate::{
+    config::Config,
+    events::relay_outbox_events,
+    jobs::run_job_workers,
+    state::AppState,
+    utils::{
+        actor::track_actor,
+        database::{init_statement_logging, route_reads},
+        middlewares::{
+            cache::cache_responses,
+            idempotency::expire_idempotency_keys,
+            panic::handle_panic,
+            security::{cors_layer, with_request_limits, with_security_headers},
+        },
+        shutdown::wait_for_signal,
+        soft_delete::purge_soft_deleted,
+    },
+    webhooks::dispatch_webhooks,
+};
 
-    let app_state: AppState = AppState::new().await;
+/// The full HTTP app over `state`, everything `run` serves minus the background tasks.
+pub fn build_app(config: &Config, state: AppState) -> Router {
     let router = Router::new()
         .merge(routes::health::health_check_router())
         .merge(routes::auth::auth_router())
         .merge(routes::users::users_router())
         .merge(routes::categories::category_router())
-        .merge(routes::retreats::retreat_router())
-        .merge(routes::retreat_reviews::retreat_review_router())
+        .merge(routes::retreats::retreat_router(&state))
+        .merge(routes::organizations::organization_router())
+        .merge(routes::translations::translation_router())
+        .merge(routes::exchange_rates::exchange_rate_router())
+        .merge(routes::retreat_reviews::retreat_review_router(&state))
         .merge(routes::gallery_categories::gallery_category_router())
-        .merge(routes::retreat_galleries::retreat_gallery_router())
         .merge(routes::wishlists::wishlist_router())
+        .merge(routes::webhooks::webhook_router())
+        .merge(routes::jobs::job_router())
+        .merge(routes::audit::audit_router())
+        .merge(routes::cache::cache_router())
+        .merge(routes::live::live_router())
+        .merge(routes::graphql::graphql_router(config));
+    let router = with_request_limits(
+        router,
+        config.max_body_size_in_kb * 1024,
+        Duration::from_secs(config.request_timeout_in_sec),
+    );
+
+    // Gallery uploads get their own, larger limits.
+    let gallery_router = with_request_limits(
+        routes::retreat_galleries::retreat_gallery_router(&state),
+        config.upload_max_body_size_in_mb * 1024 * 1024,
+        Duration::from_secs(config.upload_request_timeout_in_sec),
+    );
+
+    with_security_headers(router.merge(gallery_router), config)
         .layer(CatchPanicLayer::custom(handle_panic))
+        .layer(from_fn_with_state(state.clone(), route_reads))
+        .layer(from_fn_with_state(state.clone(), track_actor))
+        // Inside compression, so bodies are stored uncompressed and encoded per request.
+        .layer(from_fn_with_state(state.clone(), cache_responses))
         .layer(CompressionLayer::new())
-        .layer(cors)
-        .with_state(app_state);
+        .layer(cors_layer(config))
+        .with_state(state)
+}
 
-    let server_host = &env::ENV.server_host;
-    let server_port = &env::ENV.server_port;
+pub async fn run(config: Config) {
+    init_statement_logging(&config);
+    let config: Arc<Config> = Arc::new(config);
+    let app_state: AppState = AppState::new(config.clone()).await;
+    let shutdown = app_state.shutdown.clone();
+    let database = app_state.database.clone();
+    shutdown.spawn(expire_idempotency_keys(app_state.clone()));
+    shutdown.spawn(relay_outbox_events(app_state.clone()));
+    shutdown.spawn(dispatch_webhooks(app_state.clone()));
+    shutdown.spawn(purge_soft_deleted(app_state.clone()));
+    if config.job_run_in_server {
+        shutdown.spawn(run_job_workers(app_state.clone()));
+    }
+
+    let router: Router = build_app(&config, app_state);
+
+    let server_host = &config.server_host;
+    let server_port = &config.server_port;
     let server_address: String = format!("{}:{}", server_host, server_port);
 
     let listener: TcpListener = TcpListener::bind(server_address).await.unwrap();
-    axum::serve(listener, router).await.unwrap();
+
+    // Flip readiness to failing as soon as a signal arrives, then keep accepting connections
+    // for the pre-drain delay, so load balancers see the failing probe before refusals.
+    let signal_shutdown = shutdown.clone();
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        println!("Shutdown signal received, failing readiness before draining connections.");
+        signal_shutdown.trigger();
+    });
+
+    let pre_drain_delay = Duration::from_secs(config.shutdown_pre_drain_delay_in_sec);
+    let graceful_shutdown = shutdown.clone();
+    let server = axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            graceful_shutdown.triggered().await;
+            tokio::time::sleep(pre_drain_delay).await;
+            println!("Draining connections.");
+        })
+        .into_future();
+
+    let drain_timeout = Duration::from_secs(config.shutdown_drain_timeout_in_sec);
+    let drain_deadline = async {
+        shutdown.triggered().await;
+        tokio::time::sleep(pre_drain_delay + drain_timeout).await;
+    };
+
+    tokio::select! {
+        result = server => result.unwrap(),
+        _ = drain_deadline => {
+            eprintln!("Warning: drain timeout reached, dropping remaining connections.");
+        }
+    }
+
+    if tokio::time::timeout(drain_timeout, shutdown.wait_for_workers())
+        .await
+        .is_err()
+    {
+        eprintln!("Warning: background workers did not stop within the drain timeout.");
+    }
+
+    if let Err(e) = database.close().await {
+        eprintln!("Warning: failed to close database pool: {e}");
+    }
+}
+
+/// Runs only the background job workers, for deployments that keep them apart from the server.
+pub async fn run_worker(config: Config) {
+    init_statement_logging(&config);
+    let config: Arc<Config> = Arc::new(config);
+    let app_state: AppState = AppState::new(config.clone()).await;
+    let shutdown = app_state.shutdown.clone();
+    let database = app_state.database.clone();
+    shutdown.spawn(run_job_workers(app_state));
+    println!("Job workers started.");
+
+    wait_for_signal().await;
+    println!("Shutdown signal received, waiting for running jobs.");
+    shutdown.trigger();
+
+    let drain_timeout = Duration::from_secs(config.shutdown_drain_timeout_in_sec);
+    if tokio::time::timeout(drain_timeout, shutdown.wait_for_workers())
+        .await
+        .is_err()
+    {
+        eprintln!("Warning: running jobs did not finish within the drain timeout.");
+    }
+
+    if let Err(e) = database.close().await {
+        eprintln!("Warning: failed to close database pool: {e}");
+    }
 }
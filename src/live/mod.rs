@@ -0,0 +1,165 @@
+use std::{fmt, str::FromStr, sync::Arc};
+
+use sea_orm::DatabaseTransaction;
+use serde::Serialize;
+use serde_json::{Value as JsonValue, json};
+use tokio::sync::broadcast;
+
+use crate::{
+    events::{DomainEvent, SubscriberFuture},
+    state::AppState,
+};
+
+/// What a client listens to. Retreat topics are open to its staff, user topics to the
+/// user themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    Retreat(i64),
+    User(i64),
+}
+
+impl FromStr for Topic {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || format!("Unknown topic `{value}`, expected `retreat:<id>` or `user:<id>`.");
+        let (kind, id) = value.split_once(':').ok_or_else(invalid)?;
+        let id: i64 = id.parse().map_err(|_| invalid())?;
+        match kind {
+            "retreat" => Ok(Topic::Retreat(id)),
+            "user" => Ok(Topic::User(id)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topic::Retreat(retreat_id) => write!(f, "retreat:{retreat_id}"),
+            Topic::User(user_id) => write!(f, "user:{user_id}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveMessage {
+    #[serde(serialize_with = "serialize_topic")]
+    pub topic: Topic,
+    pub event: &'static str,
+    pub data: JsonValue,
+}
+
+fn serialize_topic<S: serde::Serializer>(topic: &Topic, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(topic)
+}
+
+/// Fans live messages out to every open stream of this instance.
+///
+/// Messages only reach the instance that relayed the event. To span instances, `publish`
+/// would `NOTIFY` instead, and a `LISTEN`ing task would hand what arrives to `deliver`.
+#[derive(Debug, Clone)]
+pub struct LiveHub {
+    sender: broadcast::Sender<Arc<LiveMessage>>,
+}
+
+impl LiveHub {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn publish(&self, message: LiveMessage) {
+        self.deliver(message);
+    }
+
+    fn deliver(&self, message: LiveMessage) {
+        // Nobody listening is not an error
+        let _ = self.sender.send(Arc::new(message));
+    }
+
+    /// Every message, streams filter down to their own topics.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<LiveMessage>> {
+        self.sender.subscribe()
+    }
+}
+
+/// Outbox subscriber, pushes committed events to the streams of the retreat and user
+/// they concern.
+pub(crate) fn publish_live_updates<'a>(
+    state: &'a AppState,
+    _transaction: &'a DatabaseTransaction,
+    event: &'a DomainEvent,
+) -> SubscriberFuture<'a> {
+    Box::pin(async move {
+        let (retreat_id, data): (i64, JsonValue) = match event {
+            DomainEvent::RetreatCreated { .. } => return Ok(()),
+            DomainEvent::RetreatUpdated {
+                retreat_id,
+                retreat,
+            }
+            | DomainEvent::RetreatPublished {
+                retreat_id,
+                retreat,
+            }
+            | DomainEvent::RetreatDeleted {
+                retreat_id,
+                retreat,
+            }
+            | DomainEvent::RetreatRestored {
+                retreat_id,
+                retreat,
+            } => (*retreat_id, retreat.clone()),
+            DomainEvent::ReviewPosted { retreat_id, review } => (*retreat_id, review.clone()),
+            DomainEvent::GalleryCreated {
+                retreat_id,
+                gallery,
+            }
+            | DomainEvent::GalleryUpdated {
+                retreat_id,
+                gallery,
+            }
+            | DomainEvent::GalleryDeleted {
+                retreat_id,
+                gallery,
+            }
+            | DomainEvent::GalleryRestored {
+                retreat_id,
+                gallery,
+            } => (*retreat_id, gallery.clone()),
+            DomainEvent::StaffAdded {
+                retreat_id,
+                user_id,
+                role,
+            } => {
+                let data: JsonValue =
+                    json!({ "retreat_id": retreat_id, "user_id": user_id, "role": role });
+                state.live.publish(LiveMessage {
+                    topic: Topic::User(*user_id),
+                    event: event.name(),
+                    data: data.clone(),
+                });
+                (*retreat_id, data)
+            }
+            DomainEvent::WishlistAdded {
+                retreat_id,
+                user_id,
+            } => {
+                let data: JsonValue = json!({ "retreat_id": retreat_id, "user_id": user_id });
+                state.live.publish(LiveMessage {
+                    topic: Topic::User(*user_id),
+                    event: event.name(),
+                    data: data.clone(),
+                });
+                (*retreat_id, data)
+            }
+        };
+        state.live.publish(LiveMessage {
+            topic: Topic::Retreat(retreat_id),
+            event: event.name(),
+            data,
+        });
+        Ok(())
+    })
+}
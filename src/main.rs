@@ -1,4 +1,4 @@
-use my_retreat_nest::run;
+use my_retreat_nest::{commands, config::Config, run, run_worker};
 
 #[cfg(feature = "with-jemalloc")]
 use jemallocator::Jemalloc;
@@ -9,5 +9,34 @@ static GLOBAL: Jemalloc = Jemalloc;
 
 #[tokio::main]
 async fn main() {
-    run().await;
+    let config: Config = Config::load().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result: Result<(), String> = match args.as_slice() {
+        [] | ["serve"] => {
+            run(config).await;
+            Ok(())
+        }
+        ["worker"] => {
+            run_worker(config).await;
+            Ok(())
+        }
+        ["migrate", args @ ..] => commands::migrate(config, args).await,
+        ["create-admin", email, name] => commands::create_admin(config, email, name).await,
+        ["seed", args @ ..] => commands::seed(config, args).await,
+        ["reset-password", email] => commands::reset_password(config, email).await,
+        ["gc-uploads", args @ ..] => commands::gc_uploads(config, args).await,
+        ["check-config"] => commands::check_config(config).await,
+        _ => {
+            eprintln!("{}", commands::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Response,
+    routing::get,
+};
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Select};
+use serde::Deserialize;
+
+use crate::{
+    entities_helper::{
+        AuditEventColumn, AuditEventEntity, AuditEventModel, RetreatEntity, UserColumn, UserEntity,
+    },
+    serializers::audit::ReadAuditEventSerializer,
+    state::AppState,
+    utils::{
+        extractors::auth::{AuthAdmin, AuthUser},
+        permissions::is_retreat_staff,
+        response::{CustomResponse, to_error_response, to_error_response_with_message},
+    },
+};
+
+/// Audit lists return at most this many events, the most recent first. Older ones are
+/// paged through with `before`, the smallest `audit_event_id` seen.
+const AUDIT_LIST_LIMIT: u64 = 100;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryQuery {
+    pub entity: Option<String>,
+    pub action: Option<String>,
+    pub before: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditSearchQuery {
+    pub actor_id: Option<i64>,
+    pub entity: Option<String>,
+    pub entity_id: Option<i64>,
+    pub retreat_id: Option<i64>,
+    pub action: Option<String>,
+    pub since: Option<DateTime<FixedOffset>>,
+    pub until: Option<DateTime<FixedOffset>>,
+    pub before: Option<i64>,
+}
+
+/// Runs `select`, newest first, and names the actors with one more query.
+async fn list_events(
+    state: &AppState,
+    mut select: Select<AuditEventEntity>,
+    before: Option<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    if let Some(before) = before {
+        select = select.filter(AuditEventColumn::AuditEventId.lt(before));
+    }
+    let instances: Vec<AuditEventModel> = select
+        .order_by_desc(AuditEventColumn::AuditEventId)
+        .limit(AUDIT_LIST_LIMIT)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    let mut actor_ids: Vec<i64> = instances.iter().filter_map(|model| model.actor_id).collect();
+    actor_ids.sort_unstable();
+    actor_ids.dedup();
+    // Deleted users still made the change, so they are looked up too
+    let names: HashMap<i64, String> = UserEntity::find()
+        .filter(UserColumn::UserId.is_in(actor_ids))
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .into_iter()
+        .map(|user| (user.user_id, user.name))
+        .collect();
+
+    // Convert model to serializer
+    let serializers: Vec<ReadAuditEventSerializer> = instances
+        .into_iter()
+        .map(|model| {
+            let actor_name: Option<String> = model.actor_id.and_then(|id| names.get(&id).cloned());
+            ReadAuditEventSerializer {
+                actor_name,
+                ..model.into()
+            }
+        })
+        .collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+/// Changes to the retreat and its staff, gallery and reviews, for its staff and admins.
+/// Deleted retreats keep their history.
+async fn retreat_history(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(retreat_id): Path<i64>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Response<Body>, Response<Body>> {
+    RetreatEntity::find_by_id(retreat_id)
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| {
+            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
+        })?;
+
+    let is_staff: bool = user.is_admin
+        || is_retreat_staff(&state.database, retreat_id, user.user_id)
+            .await
+            .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    if !is_staff {
+        return Err(to_error_response_with_message(
+            "Only the retreat staff can see its history.",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    let mut select = AuditEventEntity::find().filter(AuditEventColumn::RetreatId.eq(retreat_id));
+    if let Some(entity) = query.entity {
+        select = select.filter(AuditEventColumn::Entity.eq(entity));
+    }
+    if let Some(action) = query.action {
+        select = select.filter(AuditEventColumn::Action.eq(action));
+    }
+    list_events(&state, select, query.before).await
+}
+
+async fn search_audit_events(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Query(query): Query<AuditSearchQuery>,
+) -> Result<Response<Body>, Response<Body>> {
+    let mut select = AuditEventEntity::find();
+    if let Some(actor_id) = query.actor_id {
+        select = select.filter(AuditEventColumn::ActorId.eq(actor_id));
+    }
+    if let Some(entity) = query.entity {
+        select = select.filter(AuditEventColumn::Entity.eq(entity));
+    }
+    if let Some(entity_id) = query.entity_id {
+        select = select.filter(AuditEventColumn::EntityId.eq(entity_id));
+    }
+    if let Some(retreat_id) = query.retreat_id {
+        select = select.filter(AuditEventColumn::RetreatId.eq(retreat_id));
+    }
+    if let Some(action) = query.action {
+        select = select.filter(AuditEventColumn::Action.eq(action));
+    }
+    if let Some(since) = query.since {
+        select = select.filter(AuditEventColumn::CreatedAt.gte(since));
+    }
+    if let Some(until) = query.until {
+        select = select.filter(AuditEventColumn::CreatedAt.lt(until));
+    }
+    list_events(&state, select, query.before).await
+}
+
+pub fn audit_router() -> Router<AppState> {
+    Router::new()
+        .route("/retreats/{retreat_id}/history/", get(retreat_history))
+        .route("/admin/audit-events/", get(search_audit_events))
+}
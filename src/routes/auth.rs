@@ -5,7 +5,7 @@ use axum::{
     http::{Response, StatusCode},
     routing::post,
 };
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::{ColumnTrait, QueryFilter};
 use validator::Validate;
 
 use crate::{
@@ -16,6 +16,7 @@ use crate::{
         jwt::{generate_access_token, generate_refresh_token, get_refresh_token_claim},
         password::check_password,
         response::{to_error_response, to_error_response_with_message, CustomResponse},
+        soft_delete::SoftDelete,
     },
 };
 
@@ -26,7 +27,7 @@ async fn login(
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
-    let instance: UserModel = UserEntity::find()
+    let instance: UserModel = UserEntity::find_active()
         .filter(UserColumn::Email.eq(payload.email))
         .one(&state.database)
         .await
@@ -49,11 +50,11 @@ async fn login(
         email: instance.email,
     };
 
-    let access_token: String = generate_access_token(token_claim.clone())
+    let access_token: String = generate_access_token(&state.config, token_claim.clone())
         .await
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
 
-    let refresh_token: String = generate_refresh_token(token_claim)
+    let refresh_token: String = generate_refresh_token(&state.config, token_claim)
         .await
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
 
@@ -75,7 +76,7 @@ async fn refresh(
 
     let refresh_token: String = payload.refresh_token;
 
-    let claims: TokenClaim = get_refresh_token_claim(&refresh_token).await.map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    let claims: TokenClaim = get_refresh_token_claim(&state.config, &refresh_token).await.map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
 
     let token_claim: TokenClaim = claims.clone();
 
@@ -83,7 +84,7 @@ async fn refresh(
     let user_id: i64 = claims.user_id;
     let name: String = claims.name;
 
-    UserEntity::find()
+    UserEntity::find_active()
         .filter(UserColumn::Email.eq(email))
         .filter(UserColumn::UserId.eq(user_id))
         .filter(UserColumn::Name.eq(name))
@@ -92,11 +93,11 @@ async fn refresh(
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
         .ok_or_else(|| to_error_response_with_message("User not found.", StatusCode::NOT_FOUND))?;
 
-    let access_token: String = generate_access_token(token_claim.clone())
+    let access_token: String = generate_access_token(&state.config, token_claim.clone())
         .await
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
 
-    let refresh_token: String = generate_refresh_token(token_claim)
+    let refresh_token: String = generate_refresh_token(&state.config, token_claim)
         .await
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
 
@@ -0,0 +1,38 @@
+use axum::{
+    Router,
+    body::Body,
+    extract::State,
+    http::StatusCode,
+    response::Response,
+    routing::{delete, get},
+};
+
+use crate::{
+    state::AppState,
+    utils::{cache::CacheStats, extractors::auth::AuthAdmin, response::CustomResponse},
+};
+
+async fn get_cache_stats(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+) -> Result<Response<Body>, Response<Body>> {
+    let stats: CacheStats = state.cache.stats();
+    Ok(CustomResponse::builder(stats).build())
+}
+
+async fn clear_cache(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+) -> Result<Response<Body>, Response<Body>> {
+    state.cache.clear();
+    Ok(CustomResponse::builder({})
+        .message("Cache cleared successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
+
+pub fn cache_router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/cache/", get(get_cache_stats))
+        .route("/admin/cache/", delete(clear_cache))
+}
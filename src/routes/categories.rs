@@ -2,20 +2,26 @@ use axum::{
     Json, Router,
     body::Body,
     extract::{Path, State},
-    http::{Response, StatusCode},
+    http::{HeaderMap, Response, StatusCode},
     routing::{delete, get, patch, post},
 };
-use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
-    TryIntoModel,
-};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
 use validator::Validate;
 
 use crate::{
-    entities_helper::{CategoryActiveModel, CategoryColumn, CategoryEntity, CategoryModel}, serializers::categories::{
+    entities_helper::{CategoryColumn, CategoryEntity, CategoryModel}, serializers::categories::{
         CreateCategorySerializer, ReadCategorySerializer, UpdateCategorySerializer,
-    }, set_active_model_fields, set_fields, state::AppState, utils::response::{to_error_response, to_error_response_with_message, CustomResponse}
+    }, services::categories, state::AppState, utils::{
+        actor::load_actor_names,
+        cache::CacheTag,
+        etag::{
+            ETag, check_if_match, collection_etag, content_etag, is_not_modified, not_modified,
+            with_etag,
+        },
+        i18n::{Language, translate_categories, with_language},
+        response::{to_error_response, to_error_response_with_message, CustomResponse},
+    },
 };
 
 async fn create_category(
@@ -25,44 +31,66 @@ async fn create_category(
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
-    let active_model: CategoryActiveModel = set_active_model_fields!(payload, CategoryActiveModel, {
-        name,
-        description
-    });
-    // save category
-    let active_model: CategoryActiveModel = active_model
-        .save(&state.database)
+    let instance: CategoryModel = categories::create_category(&state.database, payload).await?;
+    state.cache.invalidate(CacheTag::Categories);
+    // convert to ReadCategorySerializer serializer
+    let mut serializer: ReadCategorySerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-    // convert to ReadCategorySerializer serializer
-    let serializer: ReadCategorySerializer = active_model
-        .try_into_model()
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .into();
     Ok(CustomResponse::builder(serializer)
         .message("Category created successfully.")
         .status_code(StatusCode::CREATED)
         .build())
 }
 
-async fn list_categories(State(state): State<AppState>) -> Result<Response<Body>, Response<Body>> {
+async fn list_categories(
+    State(state): State<AppState>,
+    language: Language,
+    headers: HeaderMap,
+) -> Result<Response<Body>, Response<Body>> {
     // Query a single record
-    let instances: Vec<CategoryModel> = CategoryEntity::find()
+    let mut instances: Vec<CategoryModel> = CategoryEntity::find()
         .all(&state.database)
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    translate_categories(&state.database, &mut instances, &language)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Translations change independently of the categories, so hash the body instead
+    let etag: String = if language.is_default() {
+        collection_etag(&instances)
+    } else {
+        let serializers: Vec<ReadCategorySerializer> =
+            instances.iter().cloned().map(|model| model.into()).collect();
+        content_etag(&serializers)
+    };
+    if is_not_modified(&headers, &etag) {
+        return Ok(with_language(not_modified(&etag), &language, &state.config));
+    }
+
     // Convert model to serializer
-    let serializers: Vec<ReadCategorySerializer> =
+    let mut serializers: Vec<ReadCategorySerializer> =
         instances.into_iter().map(|model| model.into()).collect();
-    Ok(CustomResponse::builder(serializers).build())
+    load_actor_names(&state.database, serializers.iter_mut().map(|s| &mut s.audit))
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    Ok(with_language(
+        with_etag(CustomResponse::builder(serializers).build(), &etag),
+        &language,
+        &state.config,
+    ))
 }
 
 async fn get_category(
     State(state): State<AppState>,
     Path(category_id): Path<i64>,
+    language: Language,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
     // Query a single record
-    let instance = CategoryEntity::find()
+    let mut instance = CategoryEntity::find()
         .filter(CategoryColumn::CategoryId.eq(category_id))
         .one(&state.database)
         .await
@@ -70,75 +98,76 @@ async fn get_category(
         .ok_or_else(|| {
             to_error_response_with_message("Category not found.", StatusCode::NOT_FOUND)
         })?;
+    translate_categories(&state.database, [&mut instance], &language)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Translations change independently of the category, so hash the body instead
+    let etag: String = if language.is_default() {
+        instance.etag()
+    } else {
+        content_etag(&ReadCategorySerializer::from(instance.clone()))
+    };
+    if is_not_modified(&headers, &etag) {
+        return Ok(with_language(not_modified(&etag), &language, &state.config));
+    }
 
     // Convert model to serializer
-    let serializer: ReadCategorySerializer = instance.into();
-    Ok(CustomResponse::builder(serializer).build())
+    let mut serializer: ReadCategorySerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    Ok(with_language(
+        with_etag(CustomResponse::builder(serializer).build(), &etag),
+        &language,
+        &state.config,
+    ))
 }
 
 async fn update_category(
     State(state): State<AppState>,
     Path(category_id): Path<i64>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateCategorySerializer>,
 ) -> Result<Response<Body>, Response<Body>> {
     payload.validate().map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
     // Find existing category
-    let instance = CategoryEntity::find()
-        .filter(CategoryColumn::CategoryId.eq(category_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Category not found.", StatusCode::NOT_FOUND)
-        })?;
-
-    // Convert to ActiveModel for editing
-    let mut active_model: CategoryActiveModel = instance.into_active_model();
+    let instance: CategoryModel = categories::find_category(&state.database, category_id).await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    set_fields!(
-        active_model,
-        payload,
-        name,
-        description
-    );
+    // Save the updated category, unless someone else changed it meanwhile
+    let instance: CategoryModel =
+        categories::update_category(&state.database, instance, payload).await?;
+    state.cache.invalidate(CacheTag::Categories);
+    let etag: String = instance.etag();
 
-    // Save the updated category
-    let instance = active_model
-        .update(&state.database)
+    // Convert to serializer
+    let mut serializer: ReadCategorySerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-    // Convert to serializer
-    let serializer: ReadCategorySerializer = instance.into();
-
     // Return success
-    Ok(CustomResponse::builder(serializer)
-        .message("Category updated successfully.")
-        .status_code(StatusCode::OK)
-        .build())
+    Ok(with_etag(
+        CustomResponse::builder(serializer)
+            .message("Category updated successfully.")
+            .status_code(StatusCode::OK)
+            .build(),
+        &etag,
+    ))
 }
 
 async fn delete_category(
     State(state): State<AppState>,
     Path(category_id): Path<i64>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
     // Query a single record
-    let instance = CategoryEntity::find()
-        .filter(CategoryColumn::CategoryId.eq(category_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Category not found.", StatusCode::NOT_FOUND)
-        })?;
-
-    // Convert to ActiveModel for editing
-    let active_model: CategoryActiveModel = instance.into_active_model();
+    let instance: CategoryModel = categories::find_category(&state.database, category_id).await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    active_model
-        .delete(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    categories::delete_category(&state.database, instance).await?;
+    state.cache.invalidate(CacheTag::Categories);
 
     // Convert model to serializer
     Ok(CustomResponse::builder({})
@@ -0,0 +1,181 @@
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, State},
+    http::{Response, StatusCode},
+    routing::{delete, get, patch, post},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, prelude::Date,
+};
+use validator::Validate;
+
+use crate::{
+    entities_helper::{
+        ExchangeRateActiveModel, ExchangeRateColumn, ExchangeRateEntity, ExchangeRateModel,
+    },
+    serializers::exchange_rates::{
+        CreateExchangeRateSerializer, ReadExchangeRateSerializer, UpdateExchangeRateSerializer,
+    },
+    set_fields,
+    state::AppState,
+    utils::{
+        cache::CacheTag,
+        extractors::auth::AuthAdmin,
+        response::{CustomResponse, to_error_response, to_error_response_with_message},
+    },
+};
+
+async fn find_exchange_rate(
+    state: &AppState,
+    exchange_rate_id: i64,
+) -> Result<ExchangeRateModel, Response<Body>> {
+    ExchangeRateEntity::find_by_id(exchange_rate_id)
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| {
+            to_error_response_with_message("Exchange rate not found.", StatusCode::NOT_FOUND)
+        })
+}
+
+/// A currency has at most one rate taking effect on a given day.
+async fn ensure_effective_date_free(
+    state: &AppState,
+    currency: &str,
+    effective_from: Date,
+) -> Result<(), Response<Body>> {
+    let existing: Option<ExchangeRateModel> = ExchangeRateEntity::find()
+        .filter(ExchangeRateColumn::Currency.eq(currency))
+        .filter(ExchangeRateColumn::EffectiveFrom.eq(effective_from))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    if existing.is_some() {
+        return Err(to_error_response_with_message(
+            &format!("A {currency} rate taking effect on {effective_from} already exists."),
+            StatusCode::CONFLICT,
+        ));
+    }
+    Ok(())
+}
+
+async fn create_exchange_rate(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Json(payload): Json<CreateExchangeRateSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    if payload.currency == state.config.base_currency {
+        return Err(to_error_response_with_message(
+            "The base currency always has a rate of 1.",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    ensure_effective_date_free(&state, &payload.currency, payload.effective_from).await?;
+
+    let active_model = ExchangeRateActiveModel {
+        currency: Set(payload.currency),
+        rate: Set(payload.rate),
+        effective_from: Set(payload.effective_from),
+        ..Default::default()
+    };
+    let instance: ExchangeRateModel = active_model
+        .insert(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    // Converted budgets and budget filters depend on the rates
+    state.cache.invalidate(CacheTag::Retreats);
+
+    let serializer: ReadExchangeRateSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Exchange rate created successfully.")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+async fn list_exchange_rates(
+    State(state): State<AppState>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instances: Vec<ExchangeRateModel> = ExchangeRateEntity::find()
+        .order_by_asc(ExchangeRateColumn::Currency)
+        .order_by_desc(ExchangeRateColumn::EffectiveFrom)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadExchangeRateSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn update_exchange_rate(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(exchange_rate_id): Path<i64>,
+    Json(payload): Json<UpdateExchangeRateSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    let instance: ExchangeRateModel = find_exchange_rate(&state, exchange_rate_id).await?;
+    if let Some(effective_from) = payload.effective_from
+        && effective_from != instance.effective_from
+    {
+        ensure_effective_date_free(&state, &instance.currency, effective_from).await?;
+    }
+
+    // Convert to ActiveModel for editing
+    let mut active_model: ExchangeRateActiveModel = instance.into_active_model();
+    set_fields!(active_model, payload, rate, effective_from);
+
+    let instance: ExchangeRateModel = active_model
+        .update(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    state.cache.invalidate(CacheTag::Retreats);
+
+    let serializer: ReadExchangeRateSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Exchange rate updated successfully.")
+        .status_code(StatusCode::OK)
+        .build())
+}
+
+async fn delete_exchange_rate(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(exchange_rate_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: ExchangeRateModel = find_exchange_rate(&state, exchange_rate_id).await?;
+    instance
+        .into_active_model()
+        .delete(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    state.cache.invalidate(CacheTag::Retreats);
+
+    Ok(CustomResponse::builder({})
+        .message("Exchange rate deleted successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
+
+pub fn exchange_rate_router() -> Router<AppState> {
+    let router = Router::new()
+        .route("/exchange-rates/", post(create_exchange_rate))
+        .route("/exchange-rates/", get(list_exchange_rates))
+        .route(
+            "/exchange-rates/{exchange_rate_id}/",
+            patch(update_exchange_rate),
+        )
+        .route(
+            "/exchange-rates/{exchange_rate_id}/",
+            delete(delete_exchange_rate),
+        );
+    return router;
+}
@@ -24,6 +24,7 @@ use crate::{
     set_active_model_fields, set_fields,
     state::AppState,
     utils::{
+        actor::load_actor_names,
         extractors::auth::{AuthAdmin},
         response::{CustomResponse, to_error_response, to_error_response_with_message},
     },
@@ -31,18 +32,16 @@ use crate::{
 
 async fn create_gallery_category(
     State(state): State<AppState>,
-    AuthAdmin(user): AuthAdmin,
+    AuthAdmin(_): AuthAdmin,
     Json(payload): Json<CreateGalleryCategorySerializer>,
 ) -> Result<Response<Body>, Response<Body>> {
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
 
-    let mut active_model: GalleryCategoriesActiveModel = set_active_model_fields!(payload, GalleryCategoriesActiveModel, {
+    let active_model: GalleryCategoriesActiveModel = set_active_model_fields!(payload, GalleryCategoriesActiveModel, {
         name,
     });
-    active_model.created_by = Set(Some(user.user_id));
-    active_model.updated_by = Set(Some(user.user_id));
 
     // save Retreat
     let active_model: GalleryCategoriesActiveModel = active_model
@@ -50,10 +49,13 @@ async fn create_gallery_category(
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
     // convert to ReadRetreatSerializer serializer
-    let serializer: ReadGalleryCategorySerializer = active_model
+    let mut serializer: ReadGalleryCategorySerializer = active_model
         .try_into_model()
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
         .into();
+    load_actor_names(&state.database, [&mut serializer.audit])
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
     Ok(CustomResponse::builder(serializer)
         .message("Gallery category created successfully.")
         .status_code(StatusCode::CREATED)
@@ -68,15 +70,18 @@ async fn list_gallery_category(
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
     // Convert model to serializer
-    let serializers: Vec<ReadGalleryCategorySerializer> =
+    let mut serializers: Vec<ReadGalleryCategorySerializer> =
         instances.into_iter().map(|model| model.into()).collect();
+    load_actor_names(&state.database, serializers.iter_mut().map(|s| &mut s.audit))
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
     Ok(CustomResponse::builder(serializers).build())
 }
 
 async fn update_gallery_category(
     State(state): State<AppState>,
-    AuthAdmin(user): AuthAdmin,
+    AuthAdmin(_): AuthAdmin,
     Path(gallery_category_id): Path<i64>,
     Json(payload): Json<UpdateGalleryCategorySerializer>,
 ) -> Result<Response<Body>, Response<Body>> {
@@ -97,8 +102,6 @@ async fn update_gallery_category(
 
     set_fields!(active_model, payload, name);
 
-    active_model.updated_by = Set(Some(user.user_id));
-
     // Save the updated Retreat
     let instance: GalleryCategoriesModel = active_model
         .update(&state.database)
@@ -106,7 +109,10 @@ async fn update_gallery_category(
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
     // Convert to serializer
-    let serializer: ReadGalleryCategorySerializer = instance.into();
+    let mut serializer: ReadGalleryCategorySerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
     Ok(CustomResponse::builder(serializer).build())
 }
@@ -0,0 +1,67 @@
+use async_graphql::http::{GraphQLPlaygroundConfig, playground_source};
+use axum::{
+    Extension, Json, Router,
+    body::Body,
+    extract::State,
+    http::{HeaderMap, Response, StatusCode, header},
+    response::{Html, IntoResponse},
+    routing::{get, post},
+};
+
+use crate::{
+    config::Config,
+    graphql::{
+        AppSchema, Viewer, build_schema,
+        loaders::{DataLoader, DatabaseLoader},
+    },
+    state::AppState,
+    utils::{extractors::auth::AuthUser, response::to_error_response_with_message},
+};
+
+async fn graphql_handler(
+    State(state): State<AppState>,
+    Extension(schema): Extension<AppSchema>,
+    headers: HeaderMap,
+    user: Result<AuthUser, (StatusCode, String)>,
+    Json(request): Json<async_graphql::Request>,
+) -> Result<Response<Body>, Response<Body>> {
+    // Anonymous requests are fine, but a bad token should not silently become one
+    let viewer: Viewer = match user {
+        Ok(AuthUser(user)) => Viewer(Some(user)),
+        Err((status_code, message)) if headers.contains_key(header::AUTHORIZATION) => {
+            return Err(to_error_response_with_message(&message, status_code));
+        }
+        Err(_) => Viewer(None),
+    };
+
+    // Loaders live for one request so batching never serves another request's data
+    let loader: DataLoader =
+        DataLoader::new(DatabaseLoader::new(state.database.clone()), tokio::spawn);
+    let request = request.data(state).data(viewer).data(loader);
+    let response = schema.execute(request).await;
+    Ok(Json(response).into_response())
+}
+
+/// The playground loads its scripts, styles and fonts from CDNs and runs inline scripts.
+const PLAYGROUND_CONTENT_SECURITY_POLICY: &str = "default-src 'self'; \
+    script-src 'self' 'unsafe-inline' https://cdn.jsdelivr.net; \
+    style-src 'self' 'unsafe-inline' https://cdn.jsdelivr.net https://fonts.googleapis.com; \
+    font-src 'self' https://fonts.gstatic.com; \
+    img-src 'self' data: https://cdn.jsdelivr.net; \
+    worker-src 'self' blob:; \
+    frame-ancestors 'none'";
+
+async fn graphql_playground() -> impl IntoResponse {
+    (
+        [(header::CONTENT_SECURITY_POLICY, PLAYGROUND_CONTENT_SECURITY_POLICY)],
+        Html(playground_source(GraphQLPlaygroundConfig::new("/graphql"))),
+    )
+}
+
+pub fn graphql_router(config: &Config) -> Router<AppState> {
+    let mut router = Router::new().route("/graphql", post(graphql_handler));
+    if config.graphql_playground {
+        router = router.route("/graphql", get(graphql_playground));
+    }
+    router.layer(Extension(build_schema(config)))
+}
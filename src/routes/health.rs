@@ -1,11 +1,164 @@
-use axum::{response::IntoResponse, routing::get, Router};
-use crate::{state::AppState, utils::response::CustomResponse};
+use std::{path::Path, time::Instant};
+
+use axum::{
+    Router,
+    body::Body,
+    extract::State,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+    routing::get,
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::DatabaseConnection;
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    serializers::health::{ComponentHealthSerializer, HealthStatus, ReadinessSerializer},
+    state::AppState,
+    utils::response::CustomResponse,
+};
+
+const BYTES_IN_MB: u64 = 1024 * 1024;
 
 async fn health_check() -> impl IntoResponse  {
     return CustomResponse::builder({}).message("Server is running!!!").build();
 }
 
+async fn liveness() -> Response<Body> {
+    CustomResponse::builder(()).message("Server is alive.").build()
+}
+
+async fn readiness(State(state): State<AppState>) -> Response<Body> {
+    if state.shutdown.is_triggered() {
+        return CustomResponse::builder(())
+            .message("Server is shutting down.")
+            .status_code(StatusCode::SERVICE_UNAVAILABLE)
+            .build();
+    }
+
+    let (database, replica, migrations, upload_dir) = tokio::join!(
+        check_database(state.database.primary()),
+        check_replica(state.database.replica()),
+        check_migrations(state.database.primary()),
+        check_upload_dir(state.storage.upload_dir(), &state.config),
+    );
+
+    let is_ready: bool = [&database, &migrations, &upload_dir]
+        .into_iter()
+        .chain(replica.as_ref())
+        .all(|component| component.status == HealthStatus::Up);
+
+    let serializer: ReadinessSerializer = ReadinessSerializer {
+        status: if is_ready { HealthStatus::Up } else { HealthStatus::Down },
+        database,
+        replica,
+        migrations,
+        upload_dir,
+    };
+
+    if is_ready {
+        CustomResponse::builder(serializer)
+            .message("Server is ready.")
+            .build()
+    } else {
+        CustomResponse::builder(serializer)
+            .message("Server is not ready.")
+            .status_code(StatusCode::SERVICE_UNAVAILABLE)
+            .build()
+    }
+}
+
+fn component_health(
+    started_at: Instant,
+    result: Result<Option<String>, String>,
+) -> ComponentHealthSerializer {
+    let latency_ms: u128 = started_at.elapsed().as_millis();
+    match result {
+        Ok(message) => ComponentHealthSerializer {
+            status: HealthStatus::Up,
+            latency_ms,
+            message,
+        },
+        Err(message) => ComponentHealthSerializer {
+            status: HealthStatus::Down,
+            latency_ms,
+            message: Some(message),
+        },
+    }
+}
+
+pub(crate) async fn check_database(database: &DatabaseConnection) -> ComponentHealthSerializer {
+    let started_at: Instant = Instant::now();
+    let result = database.ping().await.map(|_| None).map_err(|e| e.to_string());
+    component_health(started_at, result)
+}
+
+pub(crate) async fn check_replica(
+    replica: Option<&DatabaseConnection>,
+) -> Option<ComponentHealthSerializer> {
+    match replica {
+        Some(replica) => Some(check_database(replica).await),
+        None => None,
+    }
+}
+
+pub(crate) async fn check_migrations(database: &DatabaseConnection) -> ComponentHealthSerializer {
+    let started_at: Instant = Instant::now();
+    // Fails while migrations known to this binary are not yet applied to the database.
+    let result = match Migrator::get_pending_migrations(database).await {
+        Ok(pending) if pending.is_empty() => Ok(Migrator::migrations()
+            .last()
+            .map(|migration| migration.name().to_string())),
+        Ok(pending) => Err(format!(
+            "{} pending migration(s), next is {}.",
+            pending.len(),
+            pending[0].name()
+        )),
+        Err(e) => Err(e.to_string()),
+    };
+    component_health(started_at, result)
+}
+
+pub(crate) async fn check_upload_dir(
+    upload_dir: &Path,
+    config: &Config,
+) -> ComponentHealthSerializer {
+    let started_at: Instant = Instant::now();
+    let upload_dir = upload_dir.to_path_buf();
+
+    let result = async {
+        // Probe writability with a throwaway file rather than trusting permission bits.
+        fs::create_dir_all(&upload_dir)
+            .await
+            .map_err(|e| format!("Upload directory is not accessible: {e}"))?;
+        let probe_path = upload_dir.join(format!(".health-{}", Uuid::new_v4()));
+        fs::write(&probe_path, b"ok")
+            .await
+            .map_err(|e| format!("Upload directory is not writable: {e}"))?;
+        fs::remove_file(&probe_path).await.ok();
+
+        let free_space_in_mb: u64 = fs4::available_space(&upload_dir)
+            .map_err(|e| format!("Failed to read free space: {e}"))?
+            / BYTES_IN_MB;
+        if free_space_in_mb < config.upload_min_free_space_in_mb {
+            return Err(format!(
+                "Only {free_space_in_mb} MB free, {} MB required.",
+                config.upload_min_free_space_in_mb
+            ));
+        }
+        Ok(Some(format!("{free_space_in_mb} MB free.")))
+    }
+    .await;
+
+    component_health(started_at, result)
+}
+
 pub fn health_check_router() -> Router<AppState> {
-    let router = Router::new().route("/", get(health_check));
+    let router = Router::new()
+        .route("/", get(health_check))
+        .route("/health/live", get(liveness))
+        .route("/health/ready", get(readiness));
     return router;
-}
\ No newline at end of file
+}
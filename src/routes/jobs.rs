@@ -0,0 +1,153 @@
+use axum::{
+    Router,
+    body::Body,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Response,
+    routing::{get, post},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use serde::Deserialize;
+
+use crate::{
+    entities_helper::{JobActiveModel, JobColumn, JobEntity, JobModel},
+    jobs::{STATUS_CANCELLED, STATUS_DEAD, STATUS_QUEUED},
+    serializers::jobs::ReadJobSerializer,
+    state::AppState,
+    utils::{
+        extractors::auth::AuthAdmin,
+        response::{CustomResponse, to_error_response, to_error_response_with_message},
+    },
+};
+
+/// The job list returns at most this many of the most recent jobs.
+const JOB_LIST_LIMIT: u64 = 100;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct JobListQuery {
+    pub queue: Option<String>,
+    pub status: Option<String>,
+    pub kind: Option<String>,
+}
+
+async fn find_job(state: &AppState, job_id: i64) -> Result<JobModel, Response<Body>> {
+    JobEntity::find()
+        .filter(JobColumn::JobId.eq(job_id))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| to_error_response_with_message("Job not found.", StatusCode::NOT_FOUND))
+}
+
+async fn list_jobs(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Query(query): Query<JobListQuery>,
+) -> Result<Response<Body>, Response<Body>> {
+    let mut select = JobEntity::find();
+    if let Some(queue) = query.queue {
+        select = select.filter(JobColumn::Queue.eq(queue));
+    }
+    if let Some(status) = query.status {
+        select = select.filter(JobColumn::Status.eq(status));
+    }
+    if let Some(kind) = query.kind {
+        select = select.filter(JobColumn::Kind.eq(kind));
+    }
+    let instances: Vec<JobModel> = select
+        .order_by_desc(JobColumn::JobId)
+        .limit(JOB_LIST_LIMIT)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadJobSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn get_job(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(job_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: JobModel = find_job(&state, job_id).await?;
+    let serializer: ReadJobSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer).build())
+}
+
+async fn retry_job(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(job_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: JobModel = find_job(&state, job_id).await?;
+    if instance.status != STATUS_DEAD && instance.status != STATUS_CANCELLED {
+        return Err(to_error_response_with_message(
+            "Only dead or cancelled jobs can be retried.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    // Back into the queue with a fresh set of attempts
+    let mut active_model: JobActiveModel = instance.into_active_model();
+    active_model.status = Set(STATUS_QUEUED.to_string());
+    active_model.attempts = Set(0);
+    active_model.run_at = Set(state.clock.now_fixed());
+    active_model.finished_at = Set(None);
+
+    let instance: JobModel = active_model
+        .update(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let serializer: ReadJobSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Job queued for retry.")
+        .status_code(StatusCode::ACCEPTED)
+        .build())
+}
+
+async fn cancel_job(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(job_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: JobModel = find_job(&state, job_id).await?;
+    if instance.status != STATUS_QUEUED {
+        return Err(to_error_response_with_message(
+            "Only queued jobs can be cancelled.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    // Guarded on the status, a worker may claim the job in the meantime
+    let mut active_model: JobActiveModel = instance.into_active_model();
+    active_model.status = Set(STATUS_CANCELLED.to_string());
+    active_model.finished_at = Set(Some(state.clock.now_fixed()));
+    let instance: JobModel = JobEntity::update(active_model)
+        .filter(JobColumn::Status.eq(STATUS_QUEUED))
+        .exec(&state.database)
+        .await
+        .map_err(|_| {
+            to_error_response_with_message(
+                "The job was picked up before it could be cancelled.",
+                StatusCode::CONFLICT,
+            )
+        })?;
+    let serializer: ReadJobSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Job cancelled successfully.")
+        .build())
+}
+
+pub fn job_router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/jobs/", get(list_jobs))
+        .route("/admin/jobs/{job_id}/", get(get_job))
+        .route("/admin/jobs/{job_id}/retry/", post(retry_job))
+        .route("/admin/jobs/{job_id}/cancel/", post(cancel_job))
+}
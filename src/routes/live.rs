@@ -0,0 +1,272 @@
+use std::{collections::HashSet, convert::Infallible, sync::Arc, time::Duration};
+
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    extract::{
+        Query, State, WebSocketUpgrade,
+        ws::{Message, WebSocket},
+    },
+    http::{HeaderMap, StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use futures_util::stream;
+use sea_orm::{ColumnTrait, QueryFilter};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+use crate::{
+    entities_helper::{UserColumn, UserEntity, UserModel},
+    live::{LiveMessage, Topic},
+    serializers::auth::TokenClaim,
+    state::AppState,
+    utils::{
+        jwt::get_access_token_claim,
+        permissions::is_retreat_staff,
+        response::{to_error_response, to_error_response_with_message},
+        soft_delete::SoftDelete,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LiveQuery {
+    /// Comma separated, e.g. `retreat:12,user:3`.
+    pub topics: Option<String>,
+    /// For clients that cannot set headers, like `EventSource` and browser WebSockets.
+    pub access_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClientCommand {
+    Subscribe { topic: String },
+    Unsubscribe { topic: String },
+}
+
+enum Delivery {
+    Message(Arc<LiveMessage>),
+    /// The stream fell this many messages behind and skipped them.
+    Lagged(u64),
+}
+
+async fn authenticate(
+    state: &AppState,
+    headers: &HeaderMap,
+    access_token: Option<&str>,
+) -> Result<UserModel, Response<Body>> {
+    let bearer: Option<&str> = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let access_token: &str = bearer.or(access_token).ok_or_else(|| {
+        to_error_response_with_message("Authentication required.", StatusCode::UNAUTHORIZED)
+    })?;
+
+    let token_claim: TokenClaim = get_access_token_claim(&state.config, access_token)
+        .await
+        .map_err(|_| to_error_response_with_message("Invalid Token", StatusCode::UNAUTHORIZED))?;
+    UserEntity::find_active()
+        .filter(UserColumn::UserId.eq(token_claim.user_id))
+        .filter(UserColumn::Email.eq(token_claim.email))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| to_error_response_with_message("User not found", StatusCode::NOT_FOUND))
+}
+
+/// Users may follow themselves and the retreats they are staff of.
+async fn authorize_topic(
+    state: &AppState,
+    user: &UserModel,
+    topic: &str,
+) -> Result<Topic, Response<Body>> {
+    let topic: Topic = topic
+        .parse()
+        .map_err(|e: String| to_error_response_with_message(&e, StatusCode::BAD_REQUEST))?;
+    let allowed: bool = match topic {
+        Topic::User(user_id) => user_id == user.user_id,
+        Topic::Retreat(retreat_id) => is_retreat_staff(&state.database, retreat_id, user.user_id)
+            .await
+            .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?,
+    };
+    if !allowed {
+        return Err(to_error_response_with_message(
+            &format!("Not allowed to follow `{topic}`."),
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    Ok(topic)
+}
+
+async fn authorize_topics(
+    state: &AppState,
+    user: &UserModel,
+    topics: Option<&str>,
+) -> Result<HashSet<Topic>, Response<Body>> {
+    let mut authorized: HashSet<Topic> = HashSet::new();
+    for topic in topics.unwrap_or_default().split(',').map(str::trim) {
+        if topic.is_empty() {
+            continue;
+        }
+        authorized.insert(authorize_topic(state, user, topic).await?);
+    }
+    if authorized.len() > state.config.live_max_topics {
+        return Err(to_error_response_with_message(
+            &format!(
+                "At most {} topics per stream.",
+                state.config.live_max_topics
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(authorized)
+}
+
+/// Waits for the next message on one of `topics`, `None` once the server shuts down.
+async fn next_delivery(
+    state: &AppState,
+    receiver: &mut Receiver<Arc<LiveMessage>>,
+    topics: &HashSet<Topic>,
+) -> Option<Delivery> {
+    loop {
+        let received = tokio::select! {
+            _ = state.shutdown.triggered() => return None,
+            received = receiver.recv() => received,
+        };
+        match received {
+            Ok(message) if topics.contains(&message.topic) => {
+                return Some(Delivery::Message(message));
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => return Some(Delivery::Lagged(skipped)),
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+async fn stream_events(
+    State(state): State<AppState>,
+    Query(query): Query<LiveQuery>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, Response<Body>> {
+    let user: UserModel = authenticate(&state, &headers, query.access_token.as_deref()).await?;
+    let topics: HashSet<Topic> = authorize_topics(&state, &user, query.topics.as_deref()).await?;
+
+    let keep_alive = Duration::from_secs(state.config.live_keep_alive_in_sec);
+    let receiver: Receiver<Arc<LiveMessage>> = state.live.subscribe();
+    let events = stream::unfold(
+        (state, receiver, topics),
+        |(state, mut receiver, topics)| async move {
+            let event: Event = match next_delivery(&state, &mut receiver, &topics).await? {
+                Delivery::Message(message) => Event::default()
+                    .event(message.event)
+                    .json_data(&*message)
+                    .unwrap_or_default(),
+                Delivery::Lagged(skipped) => Event::default()
+                    .event("lagged")
+                    .data(json!({ "skipped": skipped }).to_string()),
+            };
+            Some((Ok::<Event, Infallible>(event), (state, receiver, topics)))
+        },
+    );
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::new().interval(keep_alive))
+        .into_response())
+}
+
+async fn websocket_events(
+    State(state): State<AppState>,
+    Query(query): Query<LiveQuery>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response<Body>, Response<Body>> {
+    let user: UserModel = authenticate(&state, &headers, query.access_token.as_deref()).await?;
+    let topics: HashSet<Topic> = authorize_topics(&state, &user, query.topics.as_deref()).await?;
+    Ok(upgrade.on_upgrade(move |socket| run_websocket(socket, state, user, topics)))
+}
+
+/// Besides the topics given on connect, clients send
+/// `{"action": "subscribe" | "unsubscribe", "topic": "retreat:12"}` to change them.
+async fn run_websocket(
+    mut socket: WebSocket,
+    state: AppState,
+    user: UserModel,
+    mut topics: HashSet<Topic>,
+) {
+    let mut receiver: Receiver<Arc<LiveMessage>> = state.live.subscribe();
+    let mut keep_alive =
+        tokio::time::interval(Duration::from_secs(state.config.live_keep_alive_in_sec));
+    loop {
+        let reply: Message = tokio::select! {
+            delivery = next_delivery(&state, &mut receiver, &topics) => match delivery {
+                Some(Delivery::Message(message)) => Message::Text(json!(*message).to_string().into()),
+                Some(Delivery::Lagged(skipped)) => {
+                    Message::Text(json!({ "event": "lagged", "skipped": skipped }).to_string().into())
+                }
+                None => {
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+            },
+            _ = keep_alive.tick() => Message::Ping(Bytes::new()),
+            received = socket.recv() => match received {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = handle_command(&state, &user, &mut topics, text.as_str()).await;
+                    Message::Text(reply.to_string().into())
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+        if socket.send(reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn handle_command(
+    state: &AppState,
+    user: &UserModel,
+    topics: &mut HashSet<Topic>,
+    text: &str,
+) -> serde_json::Value {
+    let command: ClientCommand = match serde_json::from_str(text) {
+        Ok(command) => command,
+        Err(e) => return json!({ "event": "error", "message": e.to_string() }),
+    };
+    match command {
+        ClientCommand::Subscribe { topic } => {
+            if topics.len() >= state.config.live_max_topics {
+                return json!({ "event": "error", "message": "Too many topics." });
+            }
+            match authorize_topic(state, user, &topic).await {
+                Ok(topic) => {
+                    topics.insert(topic);
+                    json!({ "event": "subscribed", "topic": topic.to_string() })
+                }
+                Err(_) => json!({
+                    "event": "error",
+                    "message": format!("Cannot subscribe to `{topic}`."),
+                }),
+            }
+        }
+        ClientCommand::Unsubscribe { topic } => match topic.parse::<Topic>() {
+            Ok(topic) => {
+                topics.remove(&topic);
+                json!({ "event": "unsubscribed", "topic": topic.to_string() })
+            }
+            Err(e) => json!({ "event": "error", "message": e }),
+        },
+    }
+}
+
+pub fn live_router() -> Router<AppState> {
+    Router::new()
+        .route("/events/stream", get(stream_events))
+        .route("/events/ws", get(websocket_events))
+}
@@ -1,9 +1,18 @@
+pub mod audit;
 pub mod auth;
+pub mod cache;
 pub mod categories;
+pub mod exchange_rates;
 pub mod gallery_categories;
+pub mod graphql;
 pub mod health;
+pub mod jobs;
+pub mod live;
+pub mod organizations;
 pub mod retreat_galleries;
 pub mod retreat_reviews;
 pub mod retreats;
+pub mod translations;
 pub mod users;
+pub mod webhooks;
 pub mod wishlists;
@@ -0,0 +1,445 @@
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, State},
+    http::{Response, StatusCode},
+    routing::{delete, get, patch, post},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, PaginatorTrait,
+    QueryFilter, QueryOrder, TransactionTrait,
+};
+use validator::Validate;
+
+use crate::{
+    entities_helper::{
+        OrganizationActiveModel, OrganizationColumn, OrganizationEntity, OrganizationModel,
+        OrganizationUserActiveModel, OrganizationUserColumn, OrganizationUserEntity,
+        OrganizationUserModel, RetreatColumn, RetreatEntity, RetreatModel, UserColumn,
+        UserEntity, UserModel,
+    },
+    serializers::{
+        organizations::{
+            CreateOrganizationSerializer, CreateOrganizationUserSerializer,
+            ReadOrganizationSerializer, ReadOrganizationUserSerializer,
+            UpdateOrganizationSerializer, UpdateOrganizationUserSerializer,
+        },
+        retreats::ReadRetreatSerializer,
+    },
+    services::users,
+    set_active_model_fields, set_fields,
+    state::AppState,
+    utils::{
+        actor::load_actor_names,
+        cache::CacheTag,
+        extractors::auth::AuthUser,
+        permissions::organization_membership,
+        response::{CustomResponse, to_error_response, to_error_response_with_message},
+        soft_delete::SoftDelete,
+    },
+};
+
+/// Finds the organization and the user's membership of it, members may read, owners may manage.
+async fn find_organization(
+    state: &AppState,
+    organization_id: i64,
+    user_id: i64,
+    owner_only: bool,
+) -> Result<OrganizationModel, Response<Body>> {
+    let instance: OrganizationModel = OrganizationEntity::find_by_id(organization_id)
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| {
+            to_error_response_with_message("Organization not found.", StatusCode::NOT_FOUND)
+        })?;
+
+    let membership: Option<OrganizationUserModel> =
+        organization_membership(&state.database, organization_id, user_id)
+            .await
+            .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    match membership {
+        Some(member) if member.is_owner || !owner_only => Ok(instance),
+        Some(_) => Err(to_error_response_with_message(
+            "Only the organization owners can manage it.",
+            StatusCode::FORBIDDEN,
+        )),
+        None => Err(to_error_response_with_message(
+            "Organization not found.",
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+async fn find_organization_user(
+    state: &AppState,
+    organization_id: i64,
+    organization_user_id: i64,
+) -> Result<OrganizationUserModel, Response<Body>> {
+    OrganizationUserEntity::find()
+        .filter(OrganizationUserColumn::OrganizationId.eq(organization_id))
+        .filter(OrganizationUserColumn::OrganizationUserId.eq(organization_user_id))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| to_error_response_with_message("Member not found.", StatusCode::NOT_FOUND))
+}
+
+/// An organization without owners could no longer be managed by anyone.
+async fn ensure_other_owner(
+    state: &AppState,
+    member: &OrganizationUserModel,
+) -> Result<(), Response<Body>> {
+    if !member.is_owner {
+        return Ok(());
+    }
+    let other_owners: u64 = OrganizationUserEntity::find()
+        .filter(OrganizationUserColumn::OrganizationId.eq(member.organization_id))
+        .filter(OrganizationUserColumn::IsOwner.eq(true))
+        .filter(OrganizationUserColumn::OrganizationUserId.ne(member.organization_user_id))
+        .count(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    if other_owners == 0 {
+        return Err(to_error_response_with_message(
+            "An organization needs at least one owner.",
+            StatusCode::CONFLICT,
+        ));
+    }
+    Ok(())
+}
+
+async fn create_organization(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Json(payload): Json<CreateOrganizationSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+
+    let active_model: OrganizationActiveModel = set_active_model_fields!(payload, OrganizationActiveModel, {
+        name,
+        slug,
+        description,
+        email,
+        phone
+    });
+
+    // The creator becomes its first owner
+    let transaction = state
+        .database
+        .begin()
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let instance: OrganizationModel = active_model
+        .insert(&transaction)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let member: OrganizationUserActiveModel = OrganizationUserActiveModel {
+        organization_id: Set(instance.organization_id),
+        user_id: Set(user.user_id),
+        is_owner: Set(true),
+        ..Default::default()
+    };
+    member
+        .insert(&transaction)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    transaction
+        .commit()
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    let serializer: ReadOrganizationSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Organization created successfully.")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+async fn list_organizations(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<Response<Body>, Response<Body>> {
+    // Only the organizations the user is a member of
+    let instances: Vec<OrganizationModel> = OrganizationEntity::find()
+        .inner_join(OrganizationUserEntity)
+        .filter(OrganizationUserColumn::UserId.eq(user.user_id))
+        .order_by_asc(OrganizationColumn::OrganizationId)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadOrganizationSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn get_organization(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(organization_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: OrganizationModel =
+        find_organization(&state, organization_id, user.user_id, false).await?;
+    let serializer: ReadOrganizationSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer).build())
+}
+
+async fn update_organization(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(organization_id): Path<i64>,
+    Json(payload): Json<UpdateOrganizationSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    let instance: OrganizationModel =
+        find_organization(&state, organization_id, user.user_id, true).await?;
+
+    // Convert to ActiveModel for editing
+    let mut active_model: OrganizationActiveModel = instance.into_active_model();
+    set_fields!(active_model, payload, name, slug, description, email, phone);
+
+    let instance: OrganizationModel = active_model
+        .update(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let serializer: ReadOrganizationSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Organization updated successfully.")
+        .build())
+}
+
+async fn delete_organization(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(organization_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: OrganizationModel =
+        find_organization(&state, organization_id, user.user_id, true).await?;
+
+    // Members go with it, its retreats stay without an organization
+    instance
+        .into_active_model()
+        .delete(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    state.cache.invalidate(CacheTag::Retreats);
+
+    Ok(CustomResponse::builder({})
+        .message("Organization deleted successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
+
+async fn list_organization_retreats(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(organization_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    find_organization(&state, organization_id, user.user_id, false).await?;
+
+    // Unpublished retreats included, members work on them too
+    let instances: Vec<RetreatModel> = RetreatEntity::find_active()
+        .filter(RetreatColumn::OrganizationId.eq(organization_id))
+        .order_by_asc(RetreatColumn::RetreatId)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let mut serializers: Vec<ReadRetreatSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    load_actor_names(&state.database, serializers.iter_mut().map(|s| &mut s.audit))
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn list_organization_users(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(organization_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    find_organization(&state, organization_id, user.user_id, false).await?;
+
+    let instances: Vec<OrganizationUserModel> = OrganizationUserEntity::find()
+        .filter(OrganizationUserColumn::OrganizationId.eq(organization_id))
+        .order_by_asc(OrganizationUserColumn::OrganizationUserId)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadOrganizationUserSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn create_organization_user(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(organization_id): Path<i64>,
+    Json(payload): Json<CreateOrganizationUserSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    find_organization(&state, organization_id, user.user_id, true).await?;
+
+    // The user, their password job and the membership are saved all or nothing
+    let transaction = state
+        .database
+        .begin()
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Check if user exists
+    let existing: Option<UserModel> = UserEntity::find_active()
+        .filter(UserColumn::Email.eq(&payload.email))
+        .one(&transaction)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    let user_id: i64 = if let Some(existing) = existing {
+        if existing.name != payload.name {
+            // Early return: user exists with different name
+            return Ok(CustomResponse::builder({})
+                .message(&format!(
+                    "User exists with a different name <strong>{}</strong>.",
+                    existing.name
+                ))
+                .status_code(StatusCode::ACCEPTED)
+                .build());
+        }
+        let membership: Option<OrganizationUserModel> =
+            organization_membership(&transaction, organization_id, existing.user_id)
+                .await
+                .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        if membership.is_some() {
+            return Err(to_error_response_with_message(
+                "User is already a member of this organization.",
+                StatusCode::CONFLICT,
+            ));
+        }
+        existing.user_id
+    } else {
+        users::invite_user(&transaction, &state.clock, payload.name, payload.email)
+            .await?
+            .user_id
+    };
+
+    let active_model: OrganizationUserActiveModel = OrganizationUserActiveModel {
+        organization_id: Set(organization_id),
+        user_id: Set(user_id),
+        is_owner: Set(payload.is_owner),
+        role: Set(payload.role),
+        ..Default::default()
+    };
+    let instance: OrganizationUserModel = active_model
+        .insert(&transaction)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    transaction
+        .commit()
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    let serializer: ReadOrganizationUserSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Member added successfully.")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+async fn update_organization_user(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((organization_id, organization_user_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateOrganizationUserSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    find_organization(&state, organization_id, user.user_id, true).await?;
+    let instance: OrganizationUserModel =
+        find_organization_user(&state, organization_id, organization_user_id).await?;
+    if payload.is_owner == Some(false) {
+        ensure_other_owner(&state, &instance).await?;
+    }
+
+    // Convert to ActiveModel for editing
+    let mut active_model: OrganizationUserActiveModel = instance.into_active_model();
+    set_fields!(active_model, payload, role, is_owner);
+
+    let instance: OrganizationUserModel = active_model
+        .update(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let serializer: ReadOrganizationUserSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Member updated successfully.")
+        .build())
+}
+
+async fn delete_organization_user(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((organization_id, organization_user_id)): Path<(i64, i64)>,
+) -> Result<Response<Body>, Response<Body>> {
+    find_organization(&state, organization_id, user.user_id, true).await?;
+    let instance: OrganizationUserModel =
+        find_organization_user(&state, organization_id, organization_user_id).await?;
+    ensure_other_owner(&state, &instance).await?;
+
+    instance
+        .into_active_model()
+        .delete(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    Ok(CustomResponse::builder({})
+        .message("Member removed successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
+
+pub fn organization_router() -> Router<AppState> {
+    let router = Router::new()
+        .route("/organizations/", post(create_organization))
+        .route("/organizations/", get(list_organizations))
+        .route("/organizations/{organization_id}/", get(get_organization))
+        .route(
+            "/organizations/{organization_id}/",
+            patch(update_organization),
+        )
+        .route(
+            "/organizations/{organization_id}/",
+            delete(delete_organization),
+        )
+        .route(
+            "/organizations/{organization_id}/retreats/",
+            get(list_organization_retreats),
+        )
+        .route(
+            "/organizations/{organization_id}/users/",
+            get(list_organization_users),
+        )
+        .route(
+            "/organizations/{organization_id}/users/",
+            post(create_organization_user),
+        )
+        .route(
+            "/organizations/{organization_id}/users/{organization_user_id}/",
+            patch(update_organization_user),
+        )
+        .route(
+            "/organizations/{organization_id}/users/{organization_user_id}/",
+            delete(delete_organization_user),
+        );
+    return router;
+}
@@ -6,107 +6,100 @@ use axum::{
     extract::{Multipart, Path, State},
     http::{HeaderMap, StatusCode},
     response::Response,
+    middleware::from_fn_with_state,
     routing::{delete, get, patch, post},
 };
-use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
-    TryIntoModel,
-};
+use sea_orm::{ColumnTrait, QueryFilter};
 
 use crate::{
-    entities_helper::{
-        GalleryCategoriesColumn, GalleryCategoriesEntity, RetreatColumn, RetreatEntity,
-        RetreatGalleriesActiveModel, RetreatGalleriesColumn, RetreatGalleriesEntity,
-        RetreatGalleriesModel,
-    },
+    entities_helper::{RetreatGalleriesColumn, RetreatGalleriesEntity, RetreatGalleriesModel},
+    jobs::{enqueue, handlers::RemoveGalleryImage},
     serializers::retreat_galleries::ReadRetreatGallerySerializer,
+    services::{
+        gallery::{self, GalleryChanges},
+        retreats::find_retreat,
+    },
     state::AppState,
     utils::{
-        extractors::auth::AuthUser,
-        response::{CustomResponse, to_error_response, to_error_response_with_message},
-        storage::{
-            read_retreat_gallery_with_headers, remove_retreat_gallery, store_retreat_gallery,
+        actor::load_actor_names,
+        cache::CacheTag,
+        etag::{
+            ETag, check_if_match, collection_etag, content_etag, is_not_modified, not_modified,
+            with_etag,
         },
+        extractors::auth::{AuthAdmin, AuthUser},
+        i18n::{Language, translate_galleries, with_language},
+        middlewares::idempotency::idempotency,
+        response::{CustomResponse, to_error_response, to_error_response_with_message},
+        soft_delete::SoftDelete,
+        storage::{read_retreat_gallery_with_headers, store_retreat_gallery},
     },
 };
 
-async fn create_retreat_gallery(
-    State(state): State<AppState>,
-    AuthUser(user): AuthUser,
-    Path(retreat_id): Path<i64>,
-    mut multipart: Multipart,
-) -> Result<Response<Body>, Response<Body>> {
-    // Find existing Retreat
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
-
-    let mut caption: Option<String> = None;
-    let mut image_path: String = "".to_string();
-    let mut gallery_category_id: Option<i64> = None;
+/// The row change is already saved, so a failure to queue the cleanup is only logged.
+async fn queue_image_removal(state: &AppState, image_path: String) {
+    let job = RemoveGalleryImage { image_path };
+    if let Err(e) = enqueue(&state.database, &state.clock, &job).await {
+        eprintln!("Warning: failed to queue gallery image removal: {e}");
+    }
+}
 
+/// The form fields of a gallery item, storing the image if one was sent.
+async fn read_gallery_form(
+    state: &AppState,
+    multipart: &mut Multipart,
+) -> GalleryChanges {
+    let mut changes: GalleryChanges = GalleryChanges::default();
     while let Some(field) = multipart.next_field().await.unwrap_or(None) {
         let name = field.name().unwrap_or("").to_string();
         match name.as_str() {
             "caption" => {
                 if let Ok(value) = field.text().await {
-                    caption = Some(value);
+                    changes.caption = Some(value);
                 }
             }
             "image" => {
                 let file_name: String = field.file_name().unwrap().to_string();
                 let file_content: Bytes = field.bytes().await.unwrap();
-                image_path = store_retreat_gallery(file_content, file_name, None).await;
+                changes.image_path = Some(
+                    store_retreat_gallery(state.storage.upload_dir(), file_content, file_name)
+                        .await,
+                );
             }
             "gallery_category_id" => {
                 if let Ok(value) = field.text().await {
-                    let gallery_category_id_i64 = value.parse::<i64>().unwrap();
-                    GalleryCategoriesEntity::find()
-                        .filter(
-                            GalleryCategoriesColumn::GalleryCategoryId.eq(gallery_category_id_i64),
-                        )
-                        .one(&state.database)
-                        .await
-                        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-                        .ok_or_else(|| {
-                            to_error_response_with_message(
-                                "Gallery Category not found.",
-                                StatusCode::NOT_FOUND,
-                            )
-                        })?;
-                    gallery_category_id = Some(gallery_category_id_i64)
+                    changes.gallery_category_id = Some(value.parse::<i64>().unwrap());
                 }
             }
             _ => {}
         }
     }
-    let active_model: RetreatGalleriesActiveModel = RetreatGalleriesActiveModel {
-        caption: Set(caption),
-        image_path: Set(image_path),
-        retreat_id: Set(retreat_id),
-        gallery_category_id: Set(gallery_category_id),
-        created_by: Set(Some(user.user_id)),
-        updated_by: Set(Some(user.user_id)),
-        ..Default::default()
-    };
+    changes
+}
 
-    // save user
-    let active_model: RetreatGalleriesActiveModel = active_model
-        .save(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+async fn create_retreat_gallery(
+    State(state): State<AppState>,
+    AuthUser(_): AuthUser,
+    Path(retreat_id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<Response<Body>, Response<Body>> {
+    // Find existing Retreat
+    find_retreat(&state.database, retreat_id).await?;
 
-    // convert to ReadUserSerializer serializer
-    let serializer: ReadRetreatGallerySerializer = active_model
-        .try_into_model()
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .into();
+    let changes: GalleryChanges = read_gallery_form(&state, &mut multipart).await;
+    let image_path: Option<String> = changes.image_path.clone();
+    let result = gallery::create_gallery(&state.database, retreat_id, changes).await;
+    // Nothing points at the stored image if the item was not saved
+    if let (Err(_), Some(image_path)) = (&result, image_path) {
+        queue_image_removal(&state, image_path).await;
+    }
+    let instance: RetreatGalleriesModel = result?;
+    state.cache.invalidate(CacheTag::Galleries);
 
+    let mut serializer: ReadRetreatGallerySerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
     Ok(CustomResponse::builder(serializer)
         .status_code(StatusCode::CREATED)
         .build())
@@ -115,162 +108,145 @@ async fn create_retreat_gallery(
 async fn list_retreat_gallery(
     State(state): State<AppState>,
     Path(retreat_id): Path<i64>,
+    language: Language,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
     // Find existing Retreat
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
+    find_retreat(&state.database, retreat_id).await?;
 
-    let instances: Vec<RetreatGalleriesModel> = RetreatGalleriesEntity::find()
+    let mut instances: Vec<RetreatGalleriesModel> = RetreatGalleriesEntity::find_active()
         .filter(RetreatGalleriesColumn::RetreatId.eq(retreat_id))
         .all(&state.database)
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    translate_galleries(&state.database, &mut instances, &language)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Translations change independently of the galleries, so hash the body instead
+    let etag: String = if language.is_default() {
+        collection_etag(&instances)
+    } else {
+        let serializers: Vec<ReadRetreatGallerySerializer> =
+            instances.iter().cloned().map(|model| model.into()).collect();
+        content_etag(&serializers)
+    };
+    if is_not_modified(&headers, &etag) {
+        return Ok(with_language(not_modified(&etag), &language, &state.config));
+    }
 
     // Convert model to serializer
-    let serializers: Vec<ReadRetreatGallerySerializer> =
+    let mut serializers: Vec<ReadRetreatGallerySerializer> =
         instances.into_iter().map(|model| model.into()).collect();
+    load_actor_names(&state.database, serializers.iter_mut().map(|s| &mut s.audit))
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    Ok(with_language(
+
+        with_etag(CustomResponse::builder(serializers).build(), &etag),
 
-    Ok(CustomResponse::builder(serializers).build())
+        &language,
+
+        &state.config,
+
+    ))
 }
 
 async fn update_retreat_gallery(
     State(state): State<AppState>,
     AuthUser(_): AuthUser,
     Path((retreat_id, gallery_id)): Path<(i64, i64)>,
+    headers: HeaderMap,
     mut multipart: Multipart,
 ) -> Result<Response<Body>, Response<Body>> {
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
-
-    let instance: RetreatGalleriesModel = RetreatGalleriesEntity::find()
-        .filter(RetreatGalleriesColumn::GalleryId.eq(gallery_id))
-        .filter(RetreatGalleriesColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat gallery not found.", StatusCode::NOT_FOUND)
-        })?;
+    find_retreat(&state.database, retreat_id).await?;
+    let instance: RetreatGalleriesModel =
+        gallery::find_gallery(&state.database, retreat_id, gallery_id).await?;
+    check_if_match(&headers, &instance.etag())?;
     let image_path: String = instance.image_path.clone();
-    // Convert to ActiveModel for editing
-    let mut active_model: RetreatGalleriesActiveModel = instance.into_active_model();
 
-    while let Some(field) = multipart.next_field().await.unwrap_or(None) {
-        let name = field.name().unwrap_or("").to_string();
-        match name.as_str() {
-            "caption" => {
-                if let Ok(value) = field.text().await {
-                    let caption: Option<String> = Some(value);
-                    active_model.caption = Set(caption);
-                }
-            }
-            "image" => {
-                let file_name: String = field.file_name().unwrap().to_string();
-                let file_content: Bytes = field.bytes().await.unwrap();
-                let image_path: String =
-                    store_retreat_gallery(file_content, file_name, Some(image_path.clone())).await;
-                active_model.image_path = Set(image_path);
-            }
-            "gallery_category_id" => {
-                if let Ok(value) = field.text().await {
-                    let gallery_category_id_i64 = value.parse::<i64>().unwrap();
-                    GalleryCategoriesEntity::find()
-                        .filter(
-                            GalleryCategoriesColumn::GalleryCategoryId.eq(gallery_category_id_i64),
-                        )
-                        .one(&state.database)
-                        .await
-                        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-                        .ok_or_else(|| {
-                            to_error_response_with_message(
-                                "Gallery Category not found.",
-                                StatusCode::NOT_FOUND,
-                            )
-                        })?;
-                    let gallery_category_id: Option<i64> = Some(gallery_category_id_i64);
-                    active_model.gallery_category_id = Set(gallery_category_id);
-                }
-            }
-            _ => {}
-        }
+    // Save the updated gallery, unless someone else changed it meanwhile
+    let changes: GalleryChanges = read_gallery_form(&state, &mut multipart).await;
+    let new_image_path: Option<String> = changes.image_path.clone();
+    let result = gallery::update_gallery(&state.database, instance, changes).await;
+    // Drop whichever image the row no longer points at
+    if let Some(new_image_path) = new_image_path {
+        let unreferenced: String = if result.is_ok() {
+            image_path
+        } else {
+            new_image_path
+        };
+        queue_image_removal(&state, unreferenced).await;
     }
+    let instance: RetreatGalleriesModel = result?;
+    state.cache.invalidate(CacheTag::Galleries);
+    let etag: String = instance.etag();
 
-    // Save the updated Retreat
-    let instance: RetreatGalleriesModel = active_model
-        .update(&state.database)
+    // Convert to serializer
+    let mut serializer: ReadRetreatGallerySerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-    // Convert to serializer
-    let serializer: ReadRetreatGallerySerializer = instance.into();
-
-    Ok(CustomResponse::builder(serializer).build())
+    Ok(with_etag(CustomResponse::builder(serializer).build(), &etag))
 }
 
 async fn delete_retreat_gallery(
     State(state): State<AppState>,
     AuthUser(_): AuthUser,
     Path((retreat_id, gallery_id)): Path<(i64, i64)>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
-    let instance: RetreatGalleriesModel = RetreatGalleriesEntity::find()
-        .filter(RetreatGalleriesColumn::GalleryId.eq(gallery_id))
-        .filter(RetreatGalleriesColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat gallery not found.", StatusCode::NOT_FOUND)
-        })?;
+    let instance: RetreatGalleriesModel =
+        gallery::find_gallery(&state.database, retreat_id, gallery_id).await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    let image_relative_path: String = instance.image_path.clone();
+    gallery::delete_gallery(&state.database, instance, state.clock.now_fixed()).await?;
+    state.cache.invalidate(CacheTag::Galleries);
 
-    // Convert to ActiveModel for editing
-    let active_model: RetreatGalleriesActiveModel = instance.into_active_model();
+    Ok(CustomResponse::builder({})
+        .message("Retreat gallery deleted successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
 
-    remove_retreat_gallery(image_relative_path).await;
+async fn restore_retreat_gallery(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path((retreat_id, gallery_id)): Path<(i64, i64)>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: RetreatGalleriesModel =
+        gallery::restore_gallery(&state.database, retreat_id, gallery_id).await?;
+    state.cache.invalidate(CacheTag::Galleries);
 
-    active_model
-        .delete(&state.database)
+    let mut serializer: ReadRetreatGallerySerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-    // Convert model to serializer
-    Ok(CustomResponse::builder({})
-        .message("Retreat gallery deleted successfully.")
-        .status_code(StatusCode::NO_CONTENT)
+    Ok(CustomResponse::builder(serializer)
+        .message("Retreat gallery restored successfully.")
         .build())
 }
 
 async fn get_gallery_image(
     State(state): State<AppState>,
     Path((retreat_id, gallery_id)): Path<(i64, i64)>,
+    request_headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
-    let instance: RetreatGalleriesModel = RetreatGalleriesEntity::find()
-        .filter(RetreatGalleriesColumn::GalleryId.eq(gallery_id))
-        .filter(RetreatGalleriesColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat gallery not found.", StatusCode::NOT_FOUND)
-        })?;
+    let instance: RetreatGalleriesModel =
+        gallery::find_gallery(&state.database, retreat_id, gallery_id).await?;
+
+    let etag: String = instance.etag();
+    if is_not_modified(&request_headers, &etag) {
+        return Ok(not_modified(&etag));
+    }
 
     let image_relative_path: String = instance.image_path.clone();
 
     let result: Result<(Vec<u8>, HeaderMap), Box<dyn Error>> =
-        read_retreat_gallery_with_headers(image_relative_path).await;
+        read_retreat_gallery_with_headers(state.storage.upload_dir(), image_relative_path)
+            .await;
     let (bytes, headers) = match result {
         Ok(v) => v,
         Err(_) => {
@@ -291,14 +267,15 @@ async fn get_gallery_image(
     }
 
     let response = builder.body(Body::from(Bytes::from(bytes))).unwrap();
-    Ok(response)
+    Ok(with_etag(response, &etag))
 }
 
-pub fn retreat_gallery_router() -> Router<AppState> {
+pub fn retreat_gallery_router(state: &AppState) -> Router<AppState> {
     let router = Router::new()
         .route(
             "/retreats/{retreat_id}/galleries/",
-            post(create_retreat_gallery),
+            post(create_retreat_gallery)
+                .route_layer(from_fn_with_state(state.clone(), idempotency)),
         )
         .route(
             "/retreats/{retreat_id}/galleries/",
@@ -312,6 +289,10 @@ pub fn retreat_gallery_router() -> Router<AppState> {
             "/retreats/{retreat_id}/galleries/{gallery_id}/",
             delete(delete_retreat_gallery),
         )
+        .route(
+            "/admin/retreats/{retreat_id}/galleries/{gallery_id}/restore/",
+            post(restore_retreat_gallery),
+        )
         .route(
             "/retreats/{retreat_id}/galleries/{gallery_id}/image/",
             get(get_gallery_image),
@@ -1,30 +1,36 @@
 use axum::{
     Json, Router,
     body::Body,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::Response,
+    middleware::from_fn_with_state,
     routing::{delete, get, patch, post},
 };
-use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
-    TryIntoModel,
-};
+use sea_orm::{ColumnTrait, QueryFilter};
+use serde_json::Value as JsonValue;
 use validator::Validate;
 
 use crate::{
-    entities_helper::{
-        RetreatColumn, RetreatEntity, RetreatReviewActiveModel, RetreatReviewColumn,
-        RetreatReviewEntity, RetreatReviewModel,
-    },
+    entities_helper::{RetreatReviewColumn, RetreatReviewEntity, RetreatReviewModel},
     serializers::retreat_reviews::{
         CreateRetreatReviewSerializer, ReadRetreatReviewSerializer, UpdateRetreatReviewSerializer,
     },
-    set_fields,
+    services::{retreats::find_retreat, reviews},
     state::AppState,
     utils::{
-        extractors::auth::AuthUser,
-        response::{CustomResponse, to_error_response, to_error_response_with_message},
+        cache::CacheTag,
+        currency::Currency,
+        embed::{EmbedQuery, embed_reviews},
+        etag::{
+            ETag, check_if_match, collection_etag, content_etag, is_not_modified, not_modified,
+            with_etag,
+        },
+        extractors::auth::{AuthAdmin, AuthUser},
+        i18n::Language,
+        middlewares::idempotency::idempotency,
+        response::{CustomResponse, to_error_response},
+        soft_delete::SoftDelete,
     },
 };
 
@@ -37,149 +43,124 @@ async fn create_retreat_review(
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
-    // Find existing Retreat
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
-
-    let active_model: RetreatReviewActiveModel = RetreatReviewActiveModel {
-        rating: Set(payload.rating),
-        review: Set(payload.review),
-        user_id: Set(user.user_id),
-        retreat_id: Set(retreat_id),
-        ..Default::default()
-    };
-
-    // save user
-    let active_model: RetreatReviewActiveModel = active_model
-        .save(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-    // convert to ReadUserSerializer serializer
-    let serializer: ReadRetreatReviewSerializer = active_model
-        .try_into_model()
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .into();
+    let instance: RetreatReviewModel =
+        reviews::post_review(&state.database, retreat_id, user.user_id, payload).await?;
+    state.cache.invalidate(CacheTag::Reviews);
 
+    let serializer: ReadRetreatReviewSerializer = instance.into();
     Ok(CustomResponse::builder(serializer).build())
 }
 
 async fn list_retreat_review(
     State(state): State<AppState>,
     Path(retreat_id): Path<i64>,
+    Query(query): Query<EmbedQuery>,
+    language: Language,
+    currency: Currency,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
     // Find existing Retreat
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
+    find_retreat(&state.database, retreat_id).await?;
 
-    let instances: Vec<RetreatReviewModel> = RetreatReviewEntity::find()
+    let instances: Vec<RetreatReviewModel> = RetreatReviewEntity::find_active()
         .filter(RetreatReviewColumn::RetreatId.eq(retreat_id))
         .all(&state.database)
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
+    // Embedded relations change independently of the reviews, so hash the body instead
+    if !query.is_default() {
+        let serializers: Vec<JsonValue> =
+            embed_reviews(&state.database, instances, &query, &language, &currency).await?;
+        let etag: String = content_etag(&serializers);
+        if is_not_modified(&headers, &etag) {
+            return Ok(not_modified(&etag));
+        }
+        return Ok(with_etag(CustomResponse::builder(serializers).build(), &etag));
+    }
+
+    let etag: String = collection_etag(&instances);
+    if is_not_modified(&headers, &etag) {
+        return Ok(not_modified(&etag));
+    }
+
     // Convert model to serializer
     let serializers: Vec<ReadRetreatReviewSerializer> =
         instances.into_iter().map(|model| model.into()).collect();
 
-    Ok(CustomResponse::builder(serializers).build())
+    Ok(with_etag(CustomResponse::builder(serializers).build(), &etag))
 }
 
 async fn update_retreat_review(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
     Path((retreat_id, review_id)): Path<(i64, i64)>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateRetreatReviewSerializer>,
 ) -> Result<Response<Body>, Response<Body>> {
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
 
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
-
-    let instance: RetreatReviewModel = RetreatReviewEntity::find()
-        .filter(RetreatReviewColumn::ReviewId.eq(review_id))
-        .filter(RetreatReviewColumn::RetreatId.eq(retreat_id))
-        .filter(RetreatReviewColumn::UserId.eq(user.user_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat review not found.", StatusCode::NOT_FOUND)
-        })?;
+    find_retreat(&state.database, retreat_id).await?;
+    let instance: RetreatReviewModel =
+        reviews::find_own_review(&state.database, Some(retreat_id), review_id, user.user_id)
+            .await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    // Convert to ActiveModel for editing
-    let mut active_model: RetreatReviewActiveModel = instance.into_active_model();
-
-    set_fields!(active_model, payload, rating, review);
-
-    // Save the updated Retreat
-    let instance = active_model
-        .update(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    // Save the updated review, unless someone else changed it meanwhile
+    let instance: RetreatReviewModel =
+        reviews::update_review(&state.database, instance, payload).await?;
+    state.cache.invalidate(CacheTag::Reviews);
+    let etag: String = instance.etag();
 
     // Convert to serializer
     let serializer: ReadRetreatReviewSerializer = instance.into();
 
-    Ok(CustomResponse::builder(serializer).build())
+    Ok(with_etag(CustomResponse::builder(serializer).build(), &etag))
 }
 
 async fn delete_retreat_review(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
     Path((retreat_id, review_id)): Path<(i64, i64)>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
-    let instance: RetreatReviewModel = RetreatReviewEntity::find()
-        .filter(RetreatReviewColumn::ReviewId.eq(review_id))
-        .filter(RetreatReviewColumn::RetreatId.eq(retreat_id))
-        .filter(RetreatReviewColumn::UserId.eq(user.user_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat review not found.", StatusCode::NOT_FOUND)
-        })?;
+    let instance: RetreatReviewModel =
+        reviews::find_own_review(&state.database, Some(retreat_id), review_id, user.user_id)
+            .await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    // Convert to ActiveModel for editing
-    let active_model: RetreatReviewActiveModel = instance.into_active_model();
+    reviews::delete_review(&state.database, instance, state.clock.now_fixed()).await?;
+    state.cache.invalidate(CacheTag::Reviews);
 
-    active_model
-        .delete(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-    // Convert model to serializer
     Ok(CustomResponse::builder({})
         .message("Retreat review deleted successfully.")
         .status_code(StatusCode::NO_CONTENT)
         .build())
 }
 
-pub fn retreat_review_router() -> Router<AppState> {
+async fn restore_retreat_review(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path((retreat_id, review_id)): Path<(i64, i64)>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: RetreatReviewModel =
+        reviews::restore_review(&state.database, retreat_id, review_id).await?;
+    state.cache.invalidate(CacheTag::Reviews);
+
+    let serializer: ReadRetreatReviewSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Retreat review restored successfully.")
+        .build())
+}
+
+pub fn retreat_review_router(state: &AppState) -> Router<AppState> {
     let router = Router::new()
         .route(
             "/retreats/{retreat_id}/reviews/",
-            post(create_retreat_review),
+            post(create_retreat_review)
+                .route_layer(from_fn_with_state(state.clone(), idempotency)),
         )
         .route("/retreats/{retreat_id}/reviews/", get(list_retreat_review))
         .route(
@@ -189,6 +170,10 @@ pub fn retreat_review_router() -> Router<AppState> {
         .route(
             "/retreats/{retreat_id}/reviews/{review_id}/",
             delete(delete_retreat_review),
+        )
+        .route(
+            "/admin/retreats/{retreat_id}/reviews/{review_id}/restore/",
+            post(restore_retreat_review),
         );
     return router;
 }
@@ -1,32 +1,42 @@
 use axum::{
     Json, Router,
     body::Body,
-    extract::{Path, State},
-    http::{Response, StatusCode},
+    extract::{Path, Query, State},
+    http::{HeaderMap, Response, StatusCode},
+    middleware::from_fn_with_state,
     routing::{delete, get, patch, post},
 };
-use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
-    TryIntoModel,
-};
+use chrono::NaiveDate;
+use sea_orm::QueryFilter;
 
+use serde_json::Value as JsonValue;
 use validator::Validate;
 
 use crate::{
-    entities_helper::{
-        RetreatActiveModel, RetreatColumn, RetreatEntity, RetreatModel, RetreatUserActiveModel,
-        RetreatUserColumn, RetreatUserEntity, RetreatUserModel, UserActiveModel, UserColumn,
-        UserEntity, UserModel,
-    },
+    entities_helper::{RetreatEntity, RetreatModel},
     serializers::retreats::{
         CreateRetreatSerializer, CreateRetreatUserSerializer, ReadRetreatSerializer,
         UpdateRetreatSerializer, UpdateRetreatUserSerializer,
     },
-    set_active_model_fields, set_fields,
+    services::{
+        retreats,
+        staff::{self, AddedStaff},
+    },
     state::AppState,
     utils::{
-        password::create_password,
-        response::{CustomResponse, to_error_response, to_error_response_with_message},
+        actor::{current_actor, load_actor_names},
+        cache::CacheTag,
+        currency::{BudgetQuery, Currency, budget_condition},
+        embed::{EmbedQuery, embed_retreats},
+        etag::{
+            ETag, check_if_match, collection_etag, content_etag, is_not_modified, not_modified,
+            with_etag,
+        },
+        extractors::auth::AuthAdmin,
+        i18n::{Language, translate_retreats, with_language},
+        middlewares::idempotency::idempotency,
+        response::{CustomResponse, to_error_response},
+        soft_delete::SoftDelete,
     },
 };
 
@@ -37,310 +47,271 @@ async fn create_retreat(
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    let instance: RetreatModel = retreats::create_retreat(
+        &state.database,
+        payload,
+        &state.config.base_currency,
+        current_actor(),
+    )
+    .await?;
+    state.cache.invalidate(CacheTag::Retreats);
 
-    let active_model: RetreatActiveModel = set_active_model_fields!(payload, RetreatActiveModel, {
-        name,
-        description,
-        category_id,
-        slug,
-        social_links,
-        email,
-        phone,
-        latitude,
-        longitude,
-        address
-    });
-
-    // save Retreat
-    let active_model: RetreatActiveModel = active_model
-        .save(&state.database)
+    // convert to ReadRetreatSerializer serializer
+    let mut serializer: ReadRetreatSerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-    // convert to ReadRetreatSerializer serializer
-    let serializer: ReadRetreatSerializer = active_model
-        .try_into_model()
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .into();
     Ok(CustomResponse::builder(serializer)
         .message("Retreat created successfully.")
         .status_code(StatusCode::CREATED)
         .build())
 }
 
-async fn list_retreats(State(state): State<AppState>) -> Result<Response<Body>, Response<Body>> {
-    // Query a single record
-    let instances: Vec<RetreatModel> = RetreatEntity::find()
+async fn list_retreats(
+    State(state): State<AppState>,
+    Query(query): Query<EmbedQuery>,
+    Query(budget): Query<BudgetQuery>,
+    language: Language,
+    currency: Currency,
+    headers: HeaderMap,
+) -> Result<Response<Body>, Response<Body>> {
+    // Budgets are compared across currencies at today's rates
+    let mut select = RetreatEntity::find_active();
+    let today: NaiveDate = state.clock.now().date_naive();
+    if let Some(condition) =
+        budget_condition(&state.database, &state.config, &currency, &budget, today)
+            .await
+            .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+    {
+        select = select.filter(condition);
+    }
+    let mut instances: Vec<RetreatModel> = select
         .all(&state.database)
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    translate_retreats(&state.database, &mut instances, &language)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    currency.convert_retreats(&mut instances);
+
+    // Embedded relations, translations and exchange rates change independently of the
+    // retreats, so hash the body instead
+    if !query.is_default() || !language.is_default() || !currency.is_default() {
+        let serializers: Vec<JsonValue> =
+            embed_retreats(&state.database, instances, &query, &language).await?;
+        let etag: String = content_etag(&serializers);
+        if is_not_modified(&headers, &etag) {
+            return Ok(with_language(not_modified(&etag), &language, &state.config));
+        }
+        return Ok(with_language(
+            with_etag(CustomResponse::builder(serializers).build(), &etag),
+            &language,
+            &state.config,
+        ));
+    }
+
+    let etag: String = collection_etag(&instances);
+    if is_not_modified(&headers, &etag) {
+        return Ok(with_language(not_modified(&etag), &language, &state.config));
+    }
+
     // Convert model to serializer
-    let serializers: Vec<ReadRetreatSerializer> =
+    let mut serializers: Vec<ReadRetreatSerializer> =
         instances.into_iter().map(|model| model.into()).collect();
-    Ok(CustomResponse::builder(serializers).build())
+    load_actor_names(&state.database, serializers.iter_mut().map(|s| &mut s.audit))
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    Ok(with_language(
+        with_etag(CustomResponse::builder(serializers).build(), &etag),
+        &language,
+        &state.config,
+    ))
 }
 
 async fn get_retreat(
     State(state): State<AppState>,
     Path(retreat_id): Path<i64>,
+    Query(query): Query<EmbedQuery>,
+    language: Language,
+    currency: Currency,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
     // Query a single record
-    let instance = RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
+    let mut instance: RetreatModel = retreats::find_retreat(&state.database, retreat_id).await?;
+    translate_retreats(&state.database, [&mut instance], &language)
         .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    currency.convert_retreats([&mut instance]);
+
+    // Embedded relations, translations and exchange rates change independently of the
+    // retreat, so hash the body instead
+    if !query.is_default() || !language.is_default() || !currency.is_default() {
+        let serializer: JsonValue =
+            embed_retreats(&state.database, vec![instance], &query, &language)
+                .await?
+                .pop()
+                .unwrap_or_default();
+        let etag: String = content_etag(&serializer);
+        if is_not_modified(&headers, &etag) {
+            return Ok(with_language(not_modified(&etag), &language, &state.config));
+        }
+        return Ok(with_language(
+            with_etag(CustomResponse::builder(serializer).build(), &etag),
+            &language,
+            &state.config,
+        ));
+    }
+
+    let etag: String = instance.etag();
+    if is_not_modified(&headers, &etag) {
+        return Ok(with_language(not_modified(&etag), &language, &state.config));
+    }
 
     // Convert model to serializer
-    let serializer: ReadRetreatSerializer = instance.into();
-    Ok(CustomResponse::builder(serializer).build())
+    let mut serializer: ReadRetreatSerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    Ok(with_language(
+        with_etag(CustomResponse::builder(serializer).build(), &etag),
+        &language,
+        &state.config,
+    ))
 }
 
 async fn update_retreat(
     State(state): State<AppState>,
     Path(retreat_id): Path<i64>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateRetreatSerializer>,
 ) -> Result<Response<Body>, Response<Body>> {
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
     // Find existing Retreat
-    let instance = RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
+    let instance: RetreatModel = retreats::find_retreat(&state.database, retreat_id).await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    // Convert to ActiveModel for editing
-    let mut active_model: RetreatActiveModel = instance.into_active_model();
+    // Save it unless someone else changed it meanwhile
+    let instance: RetreatModel =
+        retreats::update_retreat(&state.database, instance, payload, current_actor()).await?;
+    state.cache.invalidate(CacheTag::Retreats);
+    let etag: String = instance.etag();
 
-    set_fields!(
-        active_model,
-        payload,
-        name,
-        description,
-        category_id,
-        slug,
-        social_links,
-        email,
-        phone,
-        longitude,
-        latitude,
-        address,
-        budget_min,
-        budget_max,
-        is_published
-    );
-
-    // Save the updated Retreat
-    let instance = active_model
-        .update(&state.database)
+    // Convert to serializer
+    let mut serializer: ReadRetreatSerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-    // Convert to serializer
-    let serializer: ReadRetreatSerializer = instance.into();
-
-    // Return success
-    Ok(CustomResponse::builder(serializer)
-        .message("Retreat updated successfully.")
-        .status_code(StatusCode::OK)
-        .build())
+    Ok(with_etag(
+        CustomResponse::builder(serializer)
+            .message("Retreat updated successfully.")
+            .status_code(StatusCode::OK)
+            .build(),
+        &etag,
+    ))
 }
 
 async fn delete_retreat(
     State(state): State<AppState>,
     Path(retreat_id): Path<i64>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
     // Query a single record
-    let instance = RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
+    let instance: RetreatModel = retreats::find_retreat(&state.database, retreat_id).await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    // Convert to ActiveModel for editing
-    let active_model: RetreatActiveModel = instance.into_active_model();
+    retreats::delete_retreat(&state.database, instance, state.clock.now_fixed()).await?;
+    state.cache.invalidate(CacheTag::Retreats);
 
-    active_model
-        .delete(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-    // Convert model to serializer
     Ok(CustomResponse::builder({})
         .message("Retreat deleted successfully.")
         .status_code(StatusCode::NO_CONTENT)
         .build())
 }
 
-async fn create_retreat_user(
+async fn restore_retreat(
     State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
     Path(retreat_id): Path<i64>,
-    Json(payload): Json<CreateRetreatUserSerializer>,
 ) -> Result<Response<Body>, Response<Body>> {
-    // Ensure retreat exists
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
+    let instance: RetreatModel = retreats::restore_retreat(&state.database, retreat_id).await?;
+    state.cache.invalidate(CacheTag::Retreats);
 
-    // Check if user exists
-    let user = UserEntity::find()
-        .filter(UserColumn::Email.eq(&payload.email))
-        .one(&state.database)
+    let mut serializer: ReadRetreatSerializer = instance.into();
+    load_actor_names(&state.database, [&mut serializer.audit])
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    Ok(CustomResponse::builder(serializer)
+        .message("Retreat restored successfully.")
+        .build())
+}
 
-    let user_id: i64 = if let Some(user) = user {
-        if user.name != payload.name {
-            // Early return: user exists with different name
-            return Ok(CustomResponse::builder({})
-                .message(&format!(
-                    "User exists with a different name <strong>{}</strong>.",
-                    user.name
-                ))
-                .status_code(StatusCode::ACCEPTED)
-                .build());
+async fn create_retreat_user(
+    State(state): State<AppState>,
+    Path(retreat_id): Path<i64>,
+    Json(payload): Json<CreateRetreatUserSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    match staff::add_staff(&state.database, &state.clock, retreat_id, payload).await? {
+        AddedStaff::NameMismatch(name) => Ok(CustomResponse::builder({})
+            .message(&format!(
+                "User exists with a different name <strong>{name}</strong>."
+            ))
+            .status_code(StatusCode::ACCEPTED)
+            .build()),
+        AddedStaff::Added(_) => {
+            state.cache.invalidate(CacheTag::Staff);
+            Ok(CustomResponse::builder({})
+                .message("Staff added successfully.")
+                .status_code(StatusCode::CREATED)
+                .build())
         }
-        user.user_id
-    } else {
-        // Create new user
-        let hashed_password = create_password("tempPassword")
-            .await
-            .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-        let user_active_model = UserActiveModel {
-            name: Set(payload.name),
-            email: Set(payload.email),
-            password: Set(hashed_password),
-            ..Default::default()
-        };
-
-        let saved_user: UserModel = user_active_model
-            .save(&state.database)
-            .await
-            .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-            .try_into_model()
-            .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-        saved_user.user_id
-    };
-
-    // Associate user with retreat
-    let active_model: RetreatUserActiveModel = RetreatUserActiveModel {
-        retreat_id: Set(retreat_id),
-        user_id: Set(user_id),
-        role: Set(Some(payload.role)),
-        ..Default::default()
-    };
-
-    active_model
-        .save(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-    Ok(CustomResponse::builder({})
-        .message("Staff added successfully.")
-        .status_code(StatusCode::CREATED)
-        .build())
+    }
 }
 
 async fn update_retreat_user(
     State(state): State<AppState>,
-    Path(retreat_id): Path<i64>,
-    Path(retreat_user_id): Path<i64>,
+    Path((retreat_id, retreat_user_id)): Path<(i64, i64)>,
     Json(payload): Json<UpdateRetreatUserSerializer>,
 ) -> Result<Response<Body>, Response<Body>> {
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
-    // Ensure retreat exists
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
-
-    // Ensure retreat exists
-    let instance: RetreatUserModel = RetreatUserEntity::find()
-        .filter(RetreatUserColumn::RetreatUserId.eq(retreat_user_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| to_error_response_with_message("Staff not found.", StatusCode::NOT_FOUND))?;
-
-    // Convert to ActiveModel for editing
-    let mut active_model: RetreatUserActiveModel = instance.into_active_model();
-
-    set_fields!(active_model, payload, role);
+    staff::update_staff(&state.database, retreat_id, retreat_user_id, payload).await?;
+    state.cache.invalidate(CacheTag::Staff);
 
     Ok(CustomResponse::builder({})
-        .message("Staff added successfully.")
-        .status_code(StatusCode::CREATED)
+        .message("Staff updated successfully.")
         .build())
 }
 
 async fn delete_retreat_user(
     State(state): State<AppState>,
-    Path(retreat_id): Path<i64>,
-    Path(retreat_user_id): Path<i64>,
+    Path((retreat_id, retreat_user_id)): Path<(i64, i64)>,
 ) -> Result<Response<Body>, Response<Body>> {
-    // Ensure retreat exists
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
-
-    // Ensure retreat exists
-    let instance: RetreatUserModel = RetreatUserEntity::find()
-        .filter(RetreatUserColumn::RetreatUserId.eq(retreat_user_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| to_error_response_with_message("Staff not found.", StatusCode::NOT_FOUND))?;
-
-    // Convert to ActiveModel for editing
-    let active_model: RetreatUserActiveModel = instance.into_active_model();
-
-    active_model
-        .delete(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    staff::remove_staff(&state.database, retreat_id, retreat_user_id).await?;
+    state.cache.invalidate(CacheTag::Staff);
 
-    // Convert model to serializer
     Ok(CustomResponse::builder({})
         .message("Staff deleted successfully.")
         .status_code(StatusCode::NO_CONTENT)
         .build())
 }
 
-pub fn retreat_router() -> Router<AppState> {
+pub fn retreat_router(state: &AppState) -> Router<AppState> {
     let router = Router::new()
-        .route("/retreats/", post(create_retreat))
+        .route(
+            "/retreats/",
+            post(create_retreat).route_layer(from_fn_with_state(state.clone(), idempotency)),
+        )
         .route("/retreats/", get(list_retreats))
         .route("/retreats/{retreat_id}/", get(get_retreat))
         .route("/retreats/{retreat_id}/", patch(update_retreat))
         .route("/retreats/{retreat_id}/", delete(delete_retreat))
+        .route(
+            "/admin/retreats/{retreat_id}/restore/",
+            post(restore_retreat),
+        )
         .route("/retreats/{retreat_id}/users/", post(create_retreat_user))
         .route(
             "/retreats/{retreat_id}/users/{retreat_user_id}/",
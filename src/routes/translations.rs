@@ -0,0 +1,483 @@
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, State},
+    http::{Response, StatusCode},
+    routing::{delete, get, put},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder,
+};
+use validator::Validate;
+
+use crate::{
+    entities_helper::{
+        CategoryEntity, CategoryTranslationActiveModel, CategoryTranslationColumn,
+        CategoryTranslationEntity, CategoryTranslationModel, GalleryTranslationActiveModel,
+        GalleryTranslationColumn, GalleryTranslationEntity, GalleryTranslationModel, RetreatColumn,
+        RetreatEntity, RetreatGalleriesColumn, RetreatGalleriesEntity, RetreatGalleriesModel,
+        RetreatModel, RetreatTranslationActiveModel, RetreatTranslationColumn,
+        RetreatTranslationEntity, RetreatTranslationModel,
+    },
+    serializers::translations::{
+        ReadCategoryTranslationSerializer, ReadGalleryTranslationSerializer,
+        ReadRetreatTranslationSerializer, TranslationStatusSerializer,
+        UpsertGalleryTranslationSerializer, UpsertTranslationSerializer,
+    },
+    state::AppState,
+    utils::{
+        cache::CacheTag,
+        extractors::auth::{AuthAdmin, AuthUser},
+        i18n::{translatable_locale, translatable_locales},
+        permissions::is_retreat_staff,
+        response::{CustomResponse, to_error_response, to_error_response_with_message},
+        soft_delete::SoftDelete,
+    },
+};
+
+// Same error type as the handlers, so it can be used with `?`.
+#[allow(clippy::result_large_err)]
+fn check_locale(state: &AppState, locale: &str) -> Result<String, Response<Body>> {
+    translatable_locale(&state.config, locale)
+        .map_err(|e| to_error_response_with_message(&e, StatusCode::BAD_REQUEST))
+}
+
+/// Retreat content is translated by its staff, members of its organization included.
+async fn find_staff_retreat(
+    state: &AppState,
+    retreat_id: i64,
+    user_id: i64,
+) -> Result<RetreatModel, Response<Body>> {
+    let instance: RetreatModel = RetreatEntity::find_active()
+        .filter(RetreatColumn::RetreatId.eq(retreat_id))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| {
+            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
+        })?;
+
+    let is_staff: bool = is_retreat_staff(&state.database, retreat_id, user_id)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    if !is_staff {
+        return Err(to_error_response_with_message(
+            "Only the retreat staff can manage its translations.",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    Ok(instance)
+}
+
+async fn find_gallery(
+    state: &AppState,
+    retreat_id: i64,
+    gallery_id: i64,
+) -> Result<RetreatGalleriesModel, Response<Body>> {
+    RetreatGalleriesEntity::find_active()
+        .filter(RetreatGalleriesColumn::RetreatId.eq(retreat_id))
+        .filter(RetreatGalleriesColumn::GalleryId.eq(gallery_id))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| to_error_response_with_message("Gallery not found.", StatusCode::NOT_FOUND))
+}
+
+async fn ensure_category_exists(state: &AppState, category_id: i64) -> Result<(), Response<Body>> {
+    CategoryEntity::find_by_id(category_id)
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| {
+            to_error_response_with_message("Category not found.", StatusCode::NOT_FOUND)
+        })?;
+    Ok(())
+}
+
+fn translation_not_found() -> Response<Body> {
+    to_error_response_with_message("Translation not found.", StatusCode::NOT_FOUND)
+}
+
+async fn list_retreat_translations(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(retreat_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    find_staff_retreat(&state, retreat_id, user.user_id).await?;
+
+    let instances: Vec<RetreatTranslationModel> = RetreatTranslationEntity::find()
+        .filter(RetreatTranslationColumn::RetreatId.eq(retreat_id))
+        .order_by_asc(RetreatTranslationColumn::Locale)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadRetreatTranslationSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn upsert_retreat_translation(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, locale)): Path<(i64, String)>,
+    Json(payload): Json<UpsertTranslationSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    let locale: String = check_locale(&state, &locale)?;
+    find_staff_retreat(&state, retreat_id, user.user_id).await?;
+
+    let existing: Option<RetreatTranslationModel> = RetreatTranslationEntity::find()
+        .filter(RetreatTranslationColumn::RetreatId.eq(retreat_id))
+        .filter(RetreatTranslationColumn::Locale.eq(&locale))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let (status_code, mut active_model) = match existing {
+        Some(existing) => (StatusCode::OK, existing.into_active_model()),
+        None => (
+            StatusCode::CREATED,
+            RetreatTranslationActiveModel {
+                retreat_id: Set(retreat_id),
+                locale: Set(locale),
+                ..Default::default()
+            },
+        ),
+    };
+    active_model.name = Set(payload.name);
+    active_model.description = Set(payload.description);
+
+    let instance: RetreatTranslationModel = active_model
+        .save(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .try_into()
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    state.cache.invalidate(CacheTag::Retreats);
+
+    let serializer: ReadRetreatTranslationSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Translation saved successfully.")
+        .status_code(status_code)
+        .build())
+}
+
+async fn delete_retreat_translation(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, locale)): Path<(i64, String)>,
+) -> Result<Response<Body>, Response<Body>> {
+    find_staff_retreat(&state, retreat_id, user.user_id).await?;
+
+    let result = RetreatTranslationEntity::delete_many()
+        .filter(RetreatTranslationColumn::RetreatId.eq(retreat_id))
+        .filter(RetreatTranslationColumn::Locale.eq(locale.to_ascii_lowercase()))
+        .exec(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    if result.rows_affected == 0 {
+        return Err(translation_not_found());
+    }
+    state.cache.invalidate(CacheTag::Retreats);
+
+    Ok(CustomResponse::builder({})
+        .message("Translation deleted successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
+
+async fn retreat_translation_status(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(retreat_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let retreat: RetreatModel = find_staff_retreat(&state, retreat_id, user.user_id).await?;
+
+    let galleries: Vec<RetreatGalleriesModel> = RetreatGalleriesEntity::find_active()
+        .filter(RetreatGalleriesColumn::RetreatId.eq(retreat_id))
+        .order_by_asc(RetreatGalleriesColumn::GalleryId)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let retreat_translations: Vec<RetreatTranslationModel> = RetreatTranslationEntity::find()
+        .filter(RetreatTranslationColumn::RetreatId.eq(retreat_id))
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let gallery_ids: Vec<i64> = galleries.iter().map(|gallery| gallery.gallery_id).collect();
+    let gallery_translations: Vec<GalleryTranslationModel> = GalleryTranslationEntity::find()
+        .filter(GalleryTranslationColumn::GalleryId.is_in(gallery_ids))
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Only fields with source content need a translation
+    let has_content = |value: Option<&String>| value.is_some_and(|value| !value.trim().is_empty());
+    let mut serializers: Vec<TranslationStatusSerializer> = Vec::new();
+    for locale in translatable_locales(&state.config) {
+        let translation: Option<&RetreatTranslationModel> = retreat_translations
+            .iter()
+            .find(|translation| translation.locale == locale);
+        let mut missing: Vec<String> = Vec::new();
+        if !has_content(translation.and_then(|t| t.name.as_ref())) {
+            missing.push("name".to_string());
+        }
+        if has_content(retreat.description.as_ref())
+            && !has_content(translation.and_then(|t| t.description.as_ref()))
+        {
+            missing.push("description".to_string());
+        }
+        for gallery in galleries
+            .iter()
+            .filter(|gallery| has_content(gallery.caption.as_ref()))
+        {
+            let translated: bool = gallery_translations.iter().any(|translation| {
+                translation.gallery_id == gallery.gallery_id
+                    && translation.locale == locale
+                    && has_content(translation.caption.as_ref())
+            });
+            if !translated {
+                missing.push(format!("galleries.{}.caption", gallery.gallery_id));
+            }
+        }
+        serializers.push(TranslationStatusSerializer {
+            locale,
+            complete: missing.is_empty(),
+            missing,
+        });
+    }
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn list_gallery_translations(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, gallery_id)): Path<(i64, i64)>,
+) -> Result<Response<Body>, Response<Body>> {
+    find_staff_retreat(&state, retreat_id, user.user_id).await?;
+    find_gallery(&state, retreat_id, gallery_id).await?;
+
+    let instances: Vec<GalleryTranslationModel> = GalleryTranslationEntity::find()
+        .filter(GalleryTranslationColumn::GalleryId.eq(gallery_id))
+        .order_by_asc(GalleryTranslationColumn::Locale)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadGalleryTranslationSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn upsert_gallery_translation(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, gallery_id, locale)): Path<(i64, i64, String)>,
+    Json(payload): Json<UpsertGalleryTranslationSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    let locale: String = check_locale(&state, &locale)?;
+    find_staff_retreat(&state, retreat_id, user.user_id).await?;
+    find_gallery(&state, retreat_id, gallery_id).await?;
+
+    let existing: Option<GalleryTranslationModel> = GalleryTranslationEntity::find()
+        .filter(GalleryTranslationColumn::GalleryId.eq(gallery_id))
+        .filter(GalleryTranslationColumn::Locale.eq(&locale))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let (status_code, mut active_model) = match existing {
+        Some(existing) => (StatusCode::OK, existing.into_active_model()),
+        None => (
+            StatusCode::CREATED,
+            GalleryTranslationActiveModel {
+                gallery_id: Set(gallery_id),
+                locale: Set(locale),
+                ..Default::default()
+            },
+        ),
+    };
+    active_model.caption = Set(payload.caption);
+
+    let instance: GalleryTranslationModel = active_model
+        .save(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .try_into()
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    state.cache.invalidate(CacheTag::Galleries);
+
+    let serializer: ReadGalleryTranslationSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Translation saved successfully.")
+        .status_code(status_code)
+        .build())
+}
+
+async fn delete_gallery_translation(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, gallery_id, locale)): Path<(i64, i64, String)>,
+) -> Result<Response<Body>, Response<Body>> {
+    find_staff_retreat(&state, retreat_id, user.user_id).await?;
+    find_gallery(&state, retreat_id, gallery_id).await?;
+
+    let result = GalleryTranslationEntity::delete_many()
+        .filter(GalleryTranslationColumn::GalleryId.eq(gallery_id))
+        .filter(GalleryTranslationColumn::Locale.eq(locale.to_ascii_lowercase()))
+        .exec(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    if result.rows_affected == 0 {
+        return Err(translation_not_found());
+    }
+    state.cache.invalidate(CacheTag::Galleries);
+
+    Ok(CustomResponse::builder({})
+        .message("Translation deleted successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
+
+async fn list_category_translations(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(category_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_category_exists(&state, category_id).await?;
+
+    let instances: Vec<CategoryTranslationModel> = CategoryTranslationEntity::find()
+        .filter(CategoryTranslationColumn::CategoryId.eq(category_id))
+        .order_by_asc(CategoryTranslationColumn::Locale)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadCategoryTranslationSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn upsert_category_translation(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path((category_id, locale)): Path<(i64, String)>,
+    Json(payload): Json<UpsertTranslationSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    let locale: String = check_locale(&state, &locale)?;
+    ensure_category_exists(&state, category_id).await?;
+
+    let existing: Option<CategoryTranslationModel> = CategoryTranslationEntity::find()
+        .filter(CategoryTranslationColumn::CategoryId.eq(category_id))
+        .filter(CategoryTranslationColumn::Locale.eq(&locale))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let (status_code, mut active_model) = match existing {
+        Some(existing) => (StatusCode::OK, existing.into_active_model()),
+        None => (
+            StatusCode::CREATED,
+            CategoryTranslationActiveModel {
+                category_id: Set(category_id),
+                locale: Set(locale),
+                ..Default::default()
+            },
+        ),
+    };
+    active_model.name = Set(payload.name);
+    active_model.description = Set(payload.description);
+
+    let instance: CategoryTranslationModel = active_model
+        .save(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .try_into()
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    state.cache.invalidate(CacheTag::Categories);
+
+    let serializer: ReadCategoryTranslationSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Translation saved successfully.")
+        .status_code(status_code)
+        .build())
+}
+
+async fn delete_category_translation(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path((category_id, locale)): Path<(i64, String)>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_category_exists(&state, category_id).await?;
+
+    let result = CategoryTranslationEntity::delete_many()
+        .filter(CategoryTranslationColumn::CategoryId.eq(category_id))
+        .filter(CategoryTranslationColumn::Locale.eq(locale.to_ascii_lowercase()))
+        .exec(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    if result.rows_affected == 0 {
+        return Err(translation_not_found());
+    }
+    state.cache.invalidate(CacheTag::Categories);
+
+    Ok(CustomResponse::builder({})
+        .message("Translation deleted successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
+
+pub fn translation_router() -> Router<AppState> {
+    let router = Router::new()
+        .route(
+            "/retreats/{retreat_id}/translations/",
+            get(list_retreat_translations),
+        )
+        .route(
+            "/retreats/{retreat_id}/translations/{locale}/",
+            put(upsert_retreat_translation),
+        )
+        .route(
+            "/retreats/{retreat_id}/translations/{locale}/",
+            delete(delete_retreat_translation),
+        )
+        .route(
+            "/retreats/{retreat_id}/translation-status/",
+            get(retreat_translation_status),
+        )
+        .route(
+            "/retreats/{retreat_id}/galleries/{gallery_id}/translations/",
+            get(list_gallery_translations),
+        )
+        .route(
+            "/retreats/{retreat_id}/galleries/{gallery_id}/translations/{locale}/",
+            put(upsert_gallery_translation),
+        )
+        .route(
+            "/retreats/{retreat_id}/galleries/{gallery_id}/translations/{locale}/",
+            delete(delete_gallery_translation),
+        )
+        .route(
+            "/categories/{category_id}/translations/",
+            get(list_category_translations),
+        )
+        .route(
+            "/categories/{category_id}/translations/{locale}/",
+            put(upsert_category_translation),
+        )
+        .route(
+            "/categories/{category_id}/translations/{locale}/",
+            delete(delete_category_translation),
+        );
+    return router;
+}
@@ -2,23 +2,24 @@ use axum::{
     Json, Router,
     body::Body,
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::Response,
     routing::{delete, get, patch, post},
 };
-use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
-    TryIntoModel,
-};
+use sea_orm::{ColumnTrait, QueryFilter};
 use validator::Validate;
 
 use crate::{
-    entities_helper::{UserActiveModel, UserColumn, UserEntity, UserModel},
+    entities_helper::{UserColumn, UserEntity, UserModel},
     serializers::users::{CreateUserSerializer, ReadUserSerializer, UpdateUserSerializer},
-    set_fields,
+    services::users,
     state::AppState,
     utils::{
-        extractors::auth::AuthUser, password::create_password, response::{to_error_response, to_error_response_with_message, CustomResponse}
+        etag::{
+            ETag, check_if_match, collection_etag, is_not_modified, not_modified, with_etag,
+        },
+        extractors::auth::{AuthAdmin, AuthUser}, password::create_password, response::{to_error_response, to_error_response_with_message, CustomResponse},
+        soft_delete::SoftDelete,
     },
 };
 
@@ -30,121 +31,106 @@ async fn create_users(
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
 
-    let hashed_password: String = create_password(&payload.password)
+    let hashed_password: String = create_password(&payload.password, &state.config.password_salt)
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-    let active_model: UserActiveModel = UserActiveModel {
-        name: Set(payload.name),
-        email: Set(payload.email),
-        password: Set(hashed_password),
-        phone: Set(payload.phone),
-        ..Default::default()
-    };
-
     // save user
-    let active_model: UserActiveModel = active_model
-        .save(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let instance: UserModel = users::create_user(&state.database, payload, hashed_password).await?;
 
     // convert to ReadUserSerializer serializer
-    let serializer: ReadUserSerializer = active_model
-        .try_into_model()
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .into();
+    let serializer: ReadUserSerializer = instance.into();
     Ok(CustomResponse::builder(serializer)
         .message("User created successfully.")
         .status_code(StatusCode::CREATED)
         .build())
 }
 
-async fn list_users(State(state): State<AppState>) -> Result<Response<Body>, Response<Body>> {
+async fn list_users(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, Response<Body>> {
     // Query a single record
-    let instances: Vec<UserModel> = UserEntity::find()
+    let instances: Vec<UserModel> = UserEntity::find_active()
         .all(&state.database)
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    let etag: String = collection_etag(&instances);
+    if is_not_modified(&headers, &etag) {
+        return Ok(not_modified(&etag));
+    }
+
     // Convert model to serializer
     let serializers: Vec<ReadUserSerializer> =
         instances.into_iter().map(|model| model.into()).collect();
-    Ok(CustomResponse::builder(serializers).build())
+    Ok(with_etag(CustomResponse::builder(serializers).build(), &etag))
 }
 
 async fn get_user(
     State(state): State<AppState>,
     Path(user_id): Path<i64>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
     // Query a single record
-    let instance = UserEntity::find()
+    let instance = UserEntity::find_active()
         .filter(UserColumn::UserId.eq(user_id))
         .one(&state.database)
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
         .ok_or_else(|| to_error_response_with_message("User not found.", StatusCode::NOT_FOUND))?;
 
+    let etag: String = instance.etag();
+    if is_not_modified(&headers, &etag) {
+        return Ok(not_modified(&etag));
+    }
+
     // Convert model to serializer
     let serializer: ReadUserSerializer = instance.into();
-    Ok(CustomResponse::builder(serializer).build())
+    Ok(with_etag(CustomResponse::builder(serializer).build(), &etag))
 }
 
 async fn update_user(
     State(state): State<AppState>,
     AuthUser(_): AuthUser,
     Path(user_id): Path<i64>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateUserSerializer>,
 ) -> Result<Response<Body>, Response<Body>> {
     payload
         .validate()
         .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
-    // Find existing Retreat
-    let instance: UserModel = UserEntity::find()
-        .filter(UserColumn::UserId.eq(user_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| to_error_response_with_message("User not found.", StatusCode::NOT_FOUND))?;
-
-    // Convert to ActiveModel for editing
-    let mut active_model: UserActiveModel = instance.into_active_model();
-
-    set_fields!(active_model, payload, name, email, phone);
+    // Find existing user
+    let instance: UserModel = users::find_user(&state.database, user_id).await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    // Save the updated Retreat
-    let instance = active_model
-        .update(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    // Save the updated user, unless someone else changed it meanwhile
+    let instance: UserModel = users::update_user(&state.database, instance, payload).await?;
+    let etag: String = instance.etag();
 
     // Convert to serializer
     let serializer: ReadUserSerializer = instance.into();
 
     // Return success
-    Ok(CustomResponse::builder(serializer)
-        .message("User updated successfully.")
-        .status_code(StatusCode::OK)
-        .build())
+    Ok(with_etag(
+        CustomResponse::builder(serializer)
+            .message("User updated successfully.")
+            .status_code(StatusCode::OK)
+            .build(),
+        &etag,
+    ))
 }
 
 async fn delete_user(
     State(state): State<AppState>,
     Path(user_id): Path<i64>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, Response<Body>> {
     // Query a single record
-    let instance = UserEntity::find()
-        .filter(UserColumn::UserId.eq(user_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| to_error_response_with_message("User not found.", StatusCode::NOT_FOUND))?;
-
-    // Convert to ActiveModel for editing
-    let active_model: UserActiveModel = instance.into_active_model();
+    let instance: UserModel = users::find_user(&state.database, user_id).await?;
+    check_if_match(&headers, &instance.etag())?;
 
-    active_model
-        .delete(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    users::delete_user(&state.database, instance, state.clock.now_fixed()).await?;
 
     // Convert model to serializer
     Ok(CustomResponse::builder({})
@@ -153,12 +139,26 @@ async fn delete_user(
         .build())
 }
 
+async fn restore_user(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(user_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: UserModel = users::restore_user(&state.database, user_id).await?;
+
+    let serializer: ReadUserSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("User restored successfully.")
+        .build())
+}
+
 pub fn users_router() -> Router<AppState> {
     let router = Router::new()
         .route("/users/", post(create_users))
         .route("/users/", get(list_users))
         .route("/users/{user_id}/", get(get_user))
         .route("/users/{user_id}/", patch(update_user))
-        .route("/users/{user_id}/", delete(delete_user));
+        .route("/users/{user_id}/", delete(delete_user))
+        .route("/admin/users/{user_id}/restore/", post(restore_user));
     return router;
 }
@@ -0,0 +1,467 @@
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Response,
+    routing::{delete, get, patch, post},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, QuerySelect, TryIntoModel, sea_query::SimpleExpr,
+};
+use serde::Deserialize;
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    entities_helper::{
+        RetreatColumn, RetreatEntity, WebhookDeliveryActiveModel, WebhookDeliveryColumn,
+        WebhookDeliveryEntity, WebhookDeliveryModel, WebhookSubscriptionActiveModel,
+        WebhookSubscriptionColumn, WebhookSubscriptionEntity, WebhookSubscriptionModel,
+    },
+    serializers::webhooks::{
+        CreateWebhookSubscriptionSerializer, CreatedWebhookSubscriptionSerializer,
+        ReadWebhookDeliverySerializer, ReadWebhookSubscriptionSerializer,
+        UpdateWebhookSubscriptionSerializer,
+    },
+    set_fields,
+    state::AppState,
+    utils::{
+        extractors::auth::{AuthAdmin, AuthUser},
+        permissions::is_retreat_owner,
+        response::{CustomResponse, to_error_response, to_error_response_with_message},
+        soft_delete::SoftDelete,
+    },
+    webhooks::{
+        STATUS_DEAD, STATUS_PENDING,
+        delivery::{check_target, generate_secret},
+    },
+};
+
+/// The delivery log returns at most this many of the most recent deliveries.
+const DELIVERY_LOG_LIMIT: u64 = 100;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DeliveryLogQuery {
+    pub status: Option<String>,
+}
+
+// Subscriptions are either platform wide (`retreat_id` is `None`, managed by admins) or
+// scoped to one retreat (managed by its owners). The helpers below serve both.
+
+fn subscription_scope(retreat_id: Option<i64>) -> SimpleExpr {
+    match retreat_id {
+        Some(retreat_id) => WebhookSubscriptionColumn::RetreatId.eq(retreat_id),
+        None => WebhookSubscriptionColumn::RetreatId.is_null(),
+    }
+}
+
+async fn ensure_retreat_owner(
+    state: &AppState,
+    retreat_id: i64,
+    user_id: i64,
+) -> Result<(), Response<Body>> {
+    RetreatEntity::find_active()
+        .filter(RetreatColumn::RetreatId.eq(retreat_id))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| {
+            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
+        })?;
+
+    // Owners of the retreat's organization count as owners too
+    let is_owner: bool = is_retreat_owner(&state.database, retreat_id, user_id)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    if !is_owner {
+        return Err(to_error_response_with_message(
+            "Only the retreat owner can manage its webhooks.",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    Ok(())
+}
+
+async fn find_subscription(
+    state: &AppState,
+    retreat_id: Option<i64>,
+    webhook_subscription_id: i64,
+) -> Result<WebhookSubscriptionModel, Response<Body>> {
+    WebhookSubscriptionEntity::find()
+        .filter(WebhookSubscriptionColumn::WebhookSubscriptionId.eq(webhook_subscription_id))
+        .filter(subscription_scope(retreat_id))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| {
+            to_error_response_with_message("Webhook subscription not found.", StatusCode::NOT_FOUND)
+        })
+}
+
+async fn create_subscription(
+    state: &AppState,
+    retreat_id: Option<i64>,
+    payload: CreateWebhookSubscriptionSerializer,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    check_target(&payload.url)
+        .await
+        .map_err(|e| to_error_response_with_message(&e, StatusCode::BAD_REQUEST))?;
+
+    let active_model: WebhookSubscriptionActiveModel = WebhookSubscriptionActiveModel {
+        retreat_id: Set(retreat_id),
+        url: Set(payload.url),
+        secret: Set(generate_secret()),
+        events: Set(json!(payload.events)),
+        ..Default::default()
+    };
+
+    // save subscription
+    let active_model: WebhookSubscriptionActiveModel = active_model
+        .save(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    // the secret is only shown here, so the subscriber can verify signatures
+    let serializer: CreatedWebhookSubscriptionSerializer = active_model
+        .try_into_model()
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Webhook subscription created successfully.")
+        .status_code(StatusCode::CREATED)
+        .build())
+}
+
+async fn list_subscriptions(
+    state: &AppState,
+    retreat_id: Option<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    let instances: Vec<WebhookSubscriptionModel> = WebhookSubscriptionEntity::find()
+        .filter(subscription_scope(retreat_id))
+        .order_by_asc(WebhookSubscriptionColumn::WebhookSubscriptionId)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadWebhookSubscriptionSerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn get_subscription(
+    state: &AppState,
+    retreat_id: Option<i64>,
+    webhook_subscription_id: i64,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: WebhookSubscriptionModel =
+        find_subscription(state, retreat_id, webhook_subscription_id).await?;
+    let serializer: ReadWebhookSubscriptionSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer).build())
+}
+
+async fn update_subscription(
+    state: &AppState,
+    retreat_id: Option<i64>,
+    webhook_subscription_id: i64,
+    payload: UpdateWebhookSubscriptionSerializer,
+) -> Result<Response<Body>, Response<Body>> {
+    payload
+        .validate()
+        .map_err(|e| to_error_response(e, StatusCode::BAD_REQUEST))?;
+    if let Some(url) = &payload.url {
+        check_target(url)
+            .await
+            .map_err(|e| to_error_response_with_message(&e, StatusCode::BAD_REQUEST))?;
+    }
+    let instance: WebhookSubscriptionModel =
+        find_subscription(state, retreat_id, webhook_subscription_id).await?;
+
+    // Convert to ActiveModel for editing
+    let mut active_model: WebhookSubscriptionActiveModel = instance.into_active_model();
+    set_fields!(active_model, payload, url, is_active);
+    if let Some(events) = payload.events {
+        active_model.events = Set(json!(events));
+    }
+
+    let instance: WebhookSubscriptionModel = active_model
+        .update(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let serializer: ReadWebhookSubscriptionSerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Webhook subscription updated successfully.")
+        .build())
+}
+
+async fn delete_subscription(
+    state: &AppState,
+    retreat_id: Option<i64>,
+    webhook_subscription_id: i64,
+) -> Result<Response<Body>, Response<Body>> {
+    let instance: WebhookSubscriptionModel =
+        find_subscription(state, retreat_id, webhook_subscription_id).await?;
+
+    // Pending deliveries go with it
+    instance
+        .into_active_model()
+        .delete(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    Ok(CustomResponse::builder(())
+        .message("Webhook subscription deleted successfully.")
+        .status_code(StatusCode::NO_CONTENT)
+        .build())
+}
+
+async fn list_deliveries(
+    state: &AppState,
+    retreat_id: Option<i64>,
+    webhook_subscription_id: i64,
+    query: DeliveryLogQuery,
+) -> Result<Response<Body>, Response<Body>> {
+    find_subscription(state, retreat_id, webhook_subscription_id).await?;
+
+    let mut select = WebhookDeliveryEntity::find()
+        .filter(WebhookDeliveryColumn::WebhookSubscriptionId.eq(webhook_subscription_id));
+    if let Some(status) = query.status {
+        select = select.filter(WebhookDeliveryColumn::Status.eq(status));
+    }
+    let instances: Vec<WebhookDeliveryModel> = select
+        .order_by_desc(WebhookDeliveryColumn::WebhookDeliveryId)
+        .limit(DELIVERY_LOG_LIMIT)
+        .all(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    // Convert model to serializer
+    let serializers: Vec<ReadWebhookDeliverySerializer> =
+        instances.into_iter().map(|model| model.into()).collect();
+    Ok(CustomResponse::builder(serializers).build())
+}
+
+async fn retry_delivery(
+    state: &AppState,
+    retreat_id: Option<i64>,
+    webhook_subscription_id: i64,
+    webhook_delivery_id: i64,
+) -> Result<Response<Body>, Response<Body>> {
+    find_subscription(state, retreat_id, webhook_subscription_id).await?;
+
+    let instance: WebhookDeliveryModel = WebhookDeliveryEntity::find()
+        .filter(WebhookDeliveryColumn::WebhookDeliveryId.eq(webhook_delivery_id))
+        .filter(WebhookDeliveryColumn::WebhookSubscriptionId.eq(webhook_subscription_id))
+        .one(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
+        .ok_or_else(|| {
+            to_error_response_with_message("Webhook delivery not found.", StatusCode::NOT_FOUND)
+        })?;
+    if instance.status != STATUS_DEAD {
+        return Err(to_error_response_with_message(
+            "Only dead deliveries can be retried.",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    // Back into the queue with a fresh set of attempts
+    let mut active_model: WebhookDeliveryActiveModel = instance.into_active_model();
+    active_model.status = Set(STATUS_PENDING.to_string());
+    active_model.attempts = Set(0);
+    active_model.next_attempt_at = Set(state.clock.now_fixed());
+
+    let instance: WebhookDeliveryModel = active_model
+        .update(&state.database)
+        .await
+        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    let serializer: ReadWebhookDeliverySerializer = instance.into();
+    Ok(CustomResponse::builder(serializer)
+        .message("Webhook delivery queued for retry.")
+        .status_code(StatusCode::ACCEPTED)
+        .build())
+}
+
+async fn create_platform_webhook(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Json(payload): Json<CreateWebhookSubscriptionSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    create_subscription(&state, None, payload).await
+}
+
+async fn list_platform_webhooks(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+) -> Result<Response<Body>, Response<Body>> {
+    list_subscriptions(&state, None).await
+}
+
+async fn get_platform_webhook(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(webhook_subscription_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    get_subscription(&state, None, webhook_subscription_id).await
+}
+
+async fn update_platform_webhook(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(webhook_subscription_id): Path<i64>,
+    Json(payload): Json<UpdateWebhookSubscriptionSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    update_subscription(&state, None, webhook_subscription_id, payload).await
+}
+
+async fn delete_platform_webhook(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(webhook_subscription_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    delete_subscription(&state, None, webhook_subscription_id).await
+}
+
+async fn list_platform_webhook_deliveries(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path(webhook_subscription_id): Path<i64>,
+    Query(query): Query<DeliveryLogQuery>,
+) -> Result<Response<Body>, Response<Body>> {
+    list_deliveries(&state, None, webhook_subscription_id, query).await
+}
+
+async fn retry_platform_webhook_delivery(
+    State(state): State<AppState>,
+    AuthAdmin(_): AuthAdmin,
+    Path((webhook_subscription_id, webhook_delivery_id)): Path<(i64, i64)>,
+) -> Result<Response<Body>, Response<Body>> {
+    retry_delivery(&state, None, webhook_subscription_id, webhook_delivery_id).await
+}
+
+async fn create_retreat_webhook(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(retreat_id): Path<i64>,
+    Json(payload): Json<CreateWebhookSubscriptionSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_retreat_owner(&state, retreat_id, user.user_id).await?;
+    create_subscription(&state, Some(retreat_id), payload).await
+}
+
+async fn list_retreat_webhooks(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(retreat_id): Path<i64>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_retreat_owner(&state, retreat_id, user.user_id).await?;
+    list_subscriptions(&state, Some(retreat_id)).await
+}
+
+async fn get_retreat_webhook(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, webhook_subscription_id)): Path<(i64, i64)>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_retreat_owner(&state, retreat_id, user.user_id).await?;
+    get_subscription(&state, Some(retreat_id), webhook_subscription_id).await
+}
+
+async fn update_retreat_webhook(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, webhook_subscription_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateWebhookSubscriptionSerializer>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_retreat_owner(&state, retreat_id, user.user_id).await?;
+    update_subscription(&state, Some(retreat_id), webhook_subscription_id, payload).await
+}
+
+async fn delete_retreat_webhook(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, webhook_subscription_id)): Path<(i64, i64)>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_retreat_owner(&state, retreat_id, user.user_id).await?;
+    delete_subscription(&state, Some(retreat_id), webhook_subscription_id).await
+}
+
+async fn list_retreat_webhook_deliveries(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, webhook_subscription_id)): Path<(i64, i64)>,
+    Query(query): Query<DeliveryLogQuery>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_retreat_owner(&state, retreat_id, user.user_id).await?;
+    list_deliveries(&state, Some(retreat_id), webhook_subscription_id, query).await
+}
+
+async fn retry_retreat_webhook_delivery(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((retreat_id, webhook_subscription_id, webhook_delivery_id)): Path<(i64, i64, i64)>,
+) -> Result<Response<Body>, Response<Body>> {
+    ensure_retreat_owner(&state, retreat_id, user.user_id).await?;
+    retry_delivery(
+        &state,
+        Some(retreat_id),
+        webhook_subscription_id,
+        webhook_delivery_id,
+    )
+    .await
+}
+
+pub fn webhook_router() -> Router<AppState> {
+    Router::new()
+        .route("/webhooks/", post(create_platform_webhook))
+        .route("/webhooks/", get(list_platform_webhooks))
+        .route(
+            "/webhooks/{webhook_subscription_id}/",
+            get(get_platform_webhook),
+        )
+        .route(
+            "/webhooks/{webhook_subscription_id}/",
+            patch(update_platform_webhook),
+        )
+        .route(
+            "/webhooks/{webhook_subscription_id}/",
+            delete(delete_platform_webhook),
+        )
+        .route(
+            "/webhooks/{webhook_subscription_id}/deliveries/",
+            get(list_platform_webhook_deliveries),
+        )
+        .route(
+            "/webhooks/{webhook_subscription_id}/deliveries/{webhook_delivery_id}/retry/",
+            post(retry_platform_webhook_delivery),
+        )
+        .route(
+            "/retreats/{retreat_id}/webhooks/",
+            post(create_retreat_webhook),
+        )
+        .route("/retreats/{retreat_id}/webhooks/", get(list_retreat_webhooks))
+        .route(
+            "/retreats/{retreat_id}/webhooks/{webhook_subscription_id}/",
+            get(get_retreat_webhook),
+        )
+        .route(
+            "/retreats/{retreat_id}/webhooks/{webhook_subscription_id}/",
+            patch(update_retreat_webhook),
+        )
+        .route(
+            "/retreats/{retreat_id}/webhooks/{webhook_subscription_id}/",
+            delete(delete_retreat_webhook),
+        )
+        .route(
+            "/retreats/{retreat_id}/webhooks/{webhook_subscription_id}/deliveries/",
+            get(list_retreat_webhook_deliveries),
+        )
+        .route(
+            "/retreats/{retreat_id}/webhooks/{webhook_subscription_id}/deliveries/{webhook_delivery_id}/retry/",
+            post(retry_retreat_webhook_delivery),
+        )
+}
@@ -1,24 +1,23 @@
 use axum::{
     Router,
     body::Body,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{Response, StatusCode},
     routing::{delete, get, post},
 };
-use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
-};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::Value as JsonValue;
 
 use crate::{
-    entities_helper::{
-        RetreatColumn, RetreatEntity, WishlistActiveModel, WishlistColumn, WishlistEntity,
-        WishlistModel,
-    },
-    serializers::wishlists::ReadWishlistSerializer,
+    entities_helper::{WishlistColumn, WishlistEntity, WishlistModel},
+    services::{retreats::find_retreat, wishlists},
     state::AppState,
     utils::{
+        currency::Currency,
+        embed::{EmbedQuery, embed_wishlists},
         extractors::auth::AuthUser,
-        response::{CustomResponse, to_error_response, to_error_response_with_message},
+        i18n::Language,
+        response::{CustomResponse, to_error_response},
     },
 };
 
@@ -27,43 +26,8 @@ async fn create_wishlist_item(
     AuthUser(user): AuthUser,
     Path(retreat_id): Path<i64>,
 ) -> Result<Response<Body>, Response<Body>> {
-    // Ensure retreat exists
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
+    wishlists::add_to_wishlist(&state.database, retreat_id, user.user_id).await?;
 
-    let wishlist = WishlistEntity::find()
-        .filter(WishlistColumn::RetreatId.eq(retreat_id))
-        .filter(WishlistColumn::UserId.eq(user.user_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-    if wishlist.is_some() {
-        return Ok(CustomResponse::builder({})
-            .message("Retreat added to wishlist successfully.")
-            .status_code(StatusCode::CREATED)
-            .build());
-    }
-
-    let active_model: WishlistActiveModel = WishlistActiveModel {
-        retreat_id: Set(retreat_id),
-        user_id: Set(user.user_id),
-        ..Default::default()
-    };
-
-    // save wishlist
-    active_model
-        .save(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-    // Convert model to serializer
     Ok(CustomResponse::builder({})
         .message("Retreat added to wishlist successfully.")
         .status_code(StatusCode::CREATED)
@@ -76,30 +40,9 @@ async fn delete_wishlist_item(
     Path(retreat_id): Path<i64>,
 ) -> Result<Response<Body>, Response<Body>> {
     // Ensure retreat exists
-    RetreatEntity::find()
-        .filter(RetreatColumn::RetreatId.eq(retreat_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Retreat not found.", StatusCode::NOT_FOUND)
-        })?;
-    let active_model = WishlistEntity::find()
-        .filter(WishlistColumn::RetreatId.eq(retreat_id))
-        .filter(WishlistColumn::UserId.eq(user.user_id))
-        .one(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?
-        .ok_or_else(|| {
-            to_error_response_with_message("Wishlist not found.", StatusCode::NOT_FOUND)
-        })?
-        .into_active_model();
+    find_retreat(&state.database, retreat_id).await?;
+    wishlists::remove_from_wishlist(&state.database, retreat_id, user.user_id).await?;
 
-    active_model
-        .delete(&state.database)
-        .await
-        .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-    // Convert model to serializer
     Ok(CustomResponse::builder({})
         .message("Retreat deleted from wishlist successfully.")
         .status_code(StatusCode::NO_CONTENT)
@@ -109,6 +52,9 @@ async fn delete_wishlist_item(
 async fn list_wishlist_items(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
+    Query(query): Query<EmbedQuery>,
+    language: Language,
+    currency: Currency,
 ) -> Result<Response<Body>, Response<Body>> {
     // Convert model to serializer
     let instances: Vec<WishlistModel> = WishlistEntity::find()
@@ -117,9 +63,9 @@ async fn list_wishlist_items(
         .await
         .map_err(|e| to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-    // Convert model to serializer
-    let serializers: Vec<ReadWishlistSerializer> =
-        instances.into_iter().map(|model| model.into()).collect();
+    // Convert model to serializer, embedding requested relations
+    let serializers: Vec<JsonValue> =
+        embed_wishlists(&state.database, instances, &query, &language, &currency).await?;
     Ok(CustomResponse::builder(serializers).build())
 }
 
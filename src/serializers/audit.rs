@@ -0,0 +1,57 @@
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::{entities_helper::AuditEventModel, map_fields};
+
+/// Who created and last changed a resource, flattened into its read serializer. The
+/// names are only known once loaded with `load_actor_names`.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct AuditFieldsSerializer {
+    pub created_by: Option<i64>,
+    pub created_by_name: Option<String>,
+    pub updated_by: Option<i64>,
+    pub updated_by_name: Option<String>,
+}
+
+impl AuditFieldsSerializer {
+    pub fn new(created_by: Option<i64>, updated_by: Option<i64>) -> Self {
+        AuditFieldsSerializer {
+            created_by,
+            updated_by,
+            ..Default::default()
+        }
+    }
+}
+
+/// An entry of the change history. `diff` holds `{"field": {"from": ..., "to": ...}}`
+/// for every field the change touched.
+#[derive(Serialize, Clone, Debug)]
+pub struct ReadAuditEventSerializer {
+    pub audit_event_id: i64,
+    pub actor_id: Option<i64>,
+    pub actor_name: Option<String>,
+    pub entity: String,
+    pub entity_id: i64,
+    pub retreat_id: Option<i64>,
+    pub action: String,
+    pub diff: JsonValue,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl From<AuditEventModel> for ReadAuditEventSerializer {
+    fn from(value: AuditEventModel) -> Self {
+        map_fields!(value, ReadAuditEventSerializer, {
+            audit_event_id,
+            actor_id,
+            entity,
+            entity_id,
+            retreat_id,
+            action,
+            diff,
+            created_at
+        }, {
+            actor_name: None
+        })
+    }
+}
@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use crate::{entities_helper::categories::CategoryModel, map_fields, utils::serializer::deserialize_some};
+use crate::{entities_helper::categories::CategoryModel, map_fields, serializers::audit::AuditFieldsSerializer, utils::serializer::deserialize_some};
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
 
@@ -23,6 +23,8 @@ pub struct ReadCategorySerializer {
     category_id: i64,
     name: String,
     description: Option<String>,
+    #[serde(flatten)]
+    pub audit: AuditFieldsSerializer,
 }
 
 impl From<CategoryModel> for ReadCategorySerializer {
@@ -31,6 +33,8 @@ impl From<CategoryModel> for ReadCategorySerializer {
             category_id,
             name,
             description
+        }, {
+            audit: AuditFieldsSerializer::new(value.created_by, value.updated_by)
         })
     }
 }
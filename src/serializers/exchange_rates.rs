@@ -0,0 +1,61 @@
+use std::borrow::Cow;
+
+use sea_orm::prelude::{Date, DateTimeWithTimeZone, Decimal};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{entities_helper::ExchangeRateModel, map_fields, utils::currency::is_currency_code};
+
+fn validate_currency(currency: &str) -> Result<(), ValidationError> {
+    if !is_currency_code(currency) {
+        return Err(ValidationError::new("Validation")
+            .with_message(Cow::from("Currency must be an uppercase ISO 4217 code")));
+    }
+    Ok(())
+}
+
+fn validate_rate(rate: &Decimal) -> Result<(), ValidationError> {
+    if rate.is_sign_negative() || rate.is_zero() {
+        return Err(ValidationError::new("Validation")
+            .with_message(Cow::from("Rate must be greater than zero")));
+    }
+    Ok(())
+}
+
+/// `rate` units of `currency` buy one unit of the base currency, from `effective_from` on.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateExchangeRateSerializer {
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: String,
+    #[validate(custom(function = "validate_rate"))]
+    pub rate: Decimal,
+    pub effective_from: Date,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UpdateExchangeRateSerializer {
+    #[validate(custom(function = "validate_rate"))]
+    pub rate: Option<Decimal>,
+    pub effective_from: Option<Date>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadExchangeRateSerializer {
+    exchange_rate_id: i64,
+    currency: String,
+    rate: Decimal,
+    effective_from: Date,
+    updated_at: DateTimeWithTimeZone,
+}
+
+impl From<ExchangeRateModel> for ReadExchangeRateSerializer {
+    fn from(value: ExchangeRateModel) -> Self {
+        map_fields!(value, ReadExchangeRateSerializer, {
+            exchange_rate_id,
+            currency,
+            rate,
+            effective_from,
+            updated_at
+        })
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::{entities_helper::GalleryCategoriesModel, map_fields};
+use crate::{entities_helper::GalleryCategoriesModel, map_fields, serializers::audit::AuditFieldsSerializer};
 
 #[derive(Deserialize, Clone, Debug, Validate)]
 pub struct CreateGalleryCategorySerializer{
@@ -17,7 +17,9 @@ pub struct UpdateGalleryCategorySerializer{
 #[derive(Serialize, Clone, Debug, Validate)]
 pub struct ReadGalleryCategorySerializer{
     pub gallery_category_id: i64,
-    pub name: String
+    pub name: String,
+    #[serde(flatten)]
+    pub audit: AuditFieldsSerializer
 }
 
 impl From<GalleryCategoriesModel> for ReadGalleryCategorySerializer{
@@ -25,6 +27,8 @@ impl From<GalleryCategoriesModel> for ReadGalleryCategorySerializer{
         map_fields!(value, ReadGalleryCategorySerializer, {
             gallery_category_id,
             name
+        }, {
+            audit: AuditFieldsSerializer::new(value.created_by, value.updated_by)
         })
     }
 
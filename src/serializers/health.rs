@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ComponentHealthSerializer {
+    pub status: HealthStatus,
+    pub latency_ms: u128,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadinessSerializer {
+    pub status: HealthStatus,
+    pub database: ComponentHealthSerializer,
+    /// Only reported when a read replica is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replica: Option<ComponentHealthSerializer>,
+    pub migrations: ComponentHealthSerializer,
+    pub upload_dir: ComponentHealthSerializer,
+}
@@ -0,0 +1,42 @@
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::{entities_helper::JobModel, map_fields};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReadJobSerializer {
+    pub job_id: i64,
+    pub queue: String,
+    pub kind: String,
+    pub payload: JsonValue,
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTimeWithTimeZone,
+    pub locked_at: Option<DateTimeWithTimeZone>,
+    pub locked_by: Option<String>,
+    pub last_error: Option<String>,
+    pub finished_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl From<JobModel> for ReadJobSerializer {
+    fn from(value: JobModel) -> Self {
+        map_fields!(value, ReadJobSerializer, {
+            job_id,
+            queue,
+            kind,
+            payload,
+            status,
+            attempts,
+            max_attempts,
+            run_at,
+            locked_at,
+            locked_by,
+            last_error,
+            finished_at,
+            created_at
+        })
+    }
+}
@@ -1,8 +1,15 @@
+pub mod audit;
 pub mod auth;
 pub mod categories;
+pub mod exchange_rates;
 pub mod gallery_categories;
+pub mod health;
+pub mod jobs;
+pub mod organizations;
 pub mod retreat_galleries;
 pub mod retreat_reviews;
 pub mod retreats;
+pub mod translations;
 pub mod users;
+pub mod webhooks;
 pub mod wishlists;
@@ -0,0 +1,98 @@
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{
+    entities_helper::{OrganizationModel, OrganizationUserModel},
+    map_fields,
+    utils::serializer::deserialize_some,
+};
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateOrganizationSerializer {
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    #[validate(email)]
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadOrganizationSerializer {
+    organization_id: i64,
+    name: String,
+    slug: String,
+    description: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    created_at: DateTimeWithTimeZone,
+    updated_at: DateTimeWithTimeZone,
+}
+
+impl From<OrganizationModel> for ReadOrganizationSerializer {
+    fn from(value: OrganizationModel) -> Self {
+        map_fields!(value, ReadOrganizationSerializer, {
+            organization_id,
+            name,
+            slug,
+            description,
+            email,
+            phone,
+            created_at,
+            updated_at
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub struct UpdateOrganizationSerializer {
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub email: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub phone: Option<Option<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateOrganizationUserSerializer {
+    pub name: String,
+    #[validate(email)]
+    pub email: String,
+    pub role: Option<String>,
+    #[serde(default)]
+    pub is_owner: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub struct UpdateOrganizationUserSerializer {
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub role: Option<Option<String>>,
+    pub is_owner: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadOrganizationUserSerializer {
+    organization_user_id: i64,
+    organization_id: i64,
+    user_id: i64,
+    is_owner: bool,
+    role: Option<String>,
+    created_at: DateTimeWithTimeZone,
+}
+
+impl From<OrganizationUserModel> for ReadOrganizationUserSerializer {
+    fn from(value: OrganizationUserModel) -> Self {
+        map_fields!(value, ReadOrganizationUserSerializer, {
+            organization_user_id,
+            organization_id,
+            user_id,
+            is_owner,
+            role,
+            created_at
+        })
+    }
+}
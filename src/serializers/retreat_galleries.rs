@@ -1,6 +1,6 @@
 use serde::Serialize;
 
-use crate::{entities_helper::RetreatGalleriesModel, map_fields};
+use crate::{entities_helper::RetreatGalleriesModel, map_fields, serializers::audit::AuditFieldsSerializer};
 
 #[derive(Serialize, Clone, Debug)]
 pub struct ReadRetreatGallerySerializer{
@@ -9,8 +9,8 @@ pub struct ReadRetreatGallerySerializer{
     caption: Option<String>,
     order: Option<i32>,
     gallery_category_id: Option<i64>,
-    created_by: Option<i64>,
-    updated_by: Option<i64>
+    #[serde(flatten)]
+    pub audit: AuditFieldsSerializer
 }
 
 impl From<RetreatGalleriesModel> for ReadRetreatGallerySerializer {
@@ -20,9 +20,9 @@ impl From<RetreatGalleriesModel> for ReadRetreatGallerySerializer {
             retreat_id,
             caption,
             order,
-            gallery_category_id,
-            created_by,
-            updated_by
+            gallery_category_id
+        }, {
+            audit: AuditFieldsSerializer::new(value.created_by, value.updated_by)
         })
     }
 }
\ No newline at end of file
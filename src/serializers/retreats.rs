@@ -1,8 +1,18 @@
-use crate::{entities_helper::{RetreatModel}, map_fields, utils::serializer::deserialize_some};
+use std::borrow::Cow;
+
+use crate::{entities_helper::{RetreatModel}, map_fields, serializers::audit::AuditFieldsSerializer, utils::{currency::is_currency_code, serializer::deserialize_some}};
 use sea_orm::prelude::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+fn validate_currency(currency: &str) -> Result<(), ValidationError> {
+    if !is_currency_code(currency) {
+        return Err(ValidationError::new("Validation")
+            .with_message(Cow::from("Currency must be an uppercase ISO 4217 code")));
+    }
+    Ok(())
+}
 
 #[derive(Debug, Clone, Deserialize, Validate)]
 pub struct CreateRetreatSerializer {
@@ -16,6 +26,10 @@ pub struct CreateRetreatSerializer {
     pub latitude: Option<Decimal>,
     pub longitude: Option<Decimal>,
     pub address: Option<String>,
+    pub organization_id: Option<i64>,
+    /// The currency of the budget, the base currency if left out.
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -33,7 +47,11 @@ pub struct ReadRetreatSerializer {
     address: Option<String>,
     budget_min: Option<Decimal>,
     budget_max: Option<Decimal>,
+    currency: String,
     is_published: bool,
+    organization_id: Option<i64>,
+    #[serde(flatten)]
+    pub audit: AuditFieldsSerializer,
 }
 
 impl From<RetreatModel> for ReadRetreatSerializer {
@@ -52,7 +70,11 @@ impl From<RetreatModel> for ReadRetreatSerializer {
             address,
             budget_min,
             budget_max,
-            is_published
+            currency,
+            is_published,
+            organization_id
+        }, {
+            audit: AuditFieldsSerializer::new(value.created_by, value.updated_by)
         })
     }
 }
@@ -79,7 +101,11 @@ pub struct UpdateRetreatSerializer {
     pub budget_min: Option<Option<Decimal>>,
     #[serde(default, deserialize_with = "deserialize_some")]
     pub budget_max: Option<Option<Decimal>>,
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
     pub is_published: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub organization_id: Option<Option<i64>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Validate)]
@@ -94,3 +120,20 @@ pub struct UpdateRetreatUserSerializer {
     #[serde(default, deserialize_with = "deserialize_some")]
     pub role: Option<Option<String>>,
 }
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadRetreatStaffSerializer {
+    pub retreat_user_id: i64,
+    pub user_id: i64,
+    pub name: Option<String>,
+    pub is_owner: bool,
+    pub role: Option<String>,
+    #[serde(flatten)]
+    pub audit: AuditFieldsSerializer,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RetreatReviewSummarySerializer {
+    pub count: i64,
+    pub average_rating: Option<f64>,
+}
@@ -0,0 +1,98 @@
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{
+    entities_helper::{CategoryTranslationModel, GalleryTranslationModel, RetreatTranslationModel},
+    map_fields,
+};
+
+/// Replaces the translation of a retreat or category, a missing field falls back along
+/// the language chain.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UpsertTranslationSerializer {
+    #[validate(length(min = 1))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UpsertGalleryTranslationSerializer {
+    pub caption: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadRetreatTranslationSerializer {
+    retreat_translation_id: i64,
+    retreat_id: i64,
+    locale: String,
+    name: Option<String>,
+    description: Option<String>,
+    updated_at: DateTimeWithTimeZone,
+}
+
+impl From<RetreatTranslationModel> for ReadRetreatTranslationSerializer {
+    fn from(value: RetreatTranslationModel) -> Self {
+        map_fields!(value, ReadRetreatTranslationSerializer, {
+            retreat_translation_id,
+            retreat_id,
+            locale,
+            name,
+            description,
+            updated_at
+        })
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadCategoryTranslationSerializer {
+    category_translation_id: i64,
+    category_id: i64,
+    locale: String,
+    name: Option<String>,
+    description: Option<String>,
+    updated_at: DateTimeWithTimeZone,
+}
+
+impl From<CategoryTranslationModel> for ReadCategoryTranslationSerializer {
+    fn from(value: CategoryTranslationModel) -> Self {
+        map_fields!(value, ReadCategoryTranslationSerializer, {
+            category_translation_id,
+            category_id,
+            locale,
+            name,
+            description,
+            updated_at
+        })
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadGalleryTranslationSerializer {
+    gallery_translation_id: i64,
+    gallery_id: i64,
+    locale: String,
+    caption: Option<String>,
+    updated_at: DateTimeWithTimeZone,
+}
+
+impl From<GalleryTranslationModel> for ReadGalleryTranslationSerializer {
+    fn from(value: GalleryTranslationModel) -> Self {
+        map_fields!(value, ReadGalleryTranslationSerializer, {
+            gallery_translation_id,
+            gallery_id,
+            locale,
+            caption,
+            updated_at
+        })
+    }
+}
+
+/// Which source fields of a retreat still lack a translation in `locale`, gallery
+/// captions as `galleries.<gallery_id>.caption`.
+#[derive(Serialize, Debug, Clone)]
+pub struct TranslationStatusSerializer {
+    pub locale: String,
+    pub complete: bool,
+    pub missing: Vec<String>,
+}
@@ -36,6 +36,19 @@ impl From<UserModel> for ReadUserSerializer{
     }
 }
 
+/// What anyone may see of a user, e.g. the author of a review.
+#[derive(Serialize, Debug, Clone)]
+pub struct PublicUserSerializer{
+    user_id: i64,
+    name: String,
+}
+
+impl From<UserModel> for PublicUserSerializer{
+    fn from(value: UserModel) -> Self {
+        PublicUserSerializer { user_id: value.user_id, name: value.name }
+    }
+}
+
 
 #[derive(Debug, Clone, Deserialize, Validate, Serialize)]
 pub struct UpdateUserSerializer{
@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    entities_helper::{WebhookDeliveryModel, WebhookSubscriptionModel},
+    map_fields,
+    webhooks::WebhookEvent,
+};
+
+/// The format only, where the host resolves to is checked by `delivery::check_target`.
+fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    if !(url.starts_with("https://") || url.starts_with("http://")) || url.len() > 2048 {
+        return Err(ValidationError::new("Validation")
+            .with_message(Cow::from("Webhook URL must be an http(s) URL")));
+    }
+    Ok(())
+}
+
+fn validate_webhook_events(events: &[String]) -> Result<(), ValidationError> {
+    for event in events {
+        event
+            .parse::<WebhookEvent>()
+            .map_err(|e| ValidationError::new("Validation").with_message(Cow::from(e)))?;
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, Clone, Debug, Validate)]
+pub struct CreateWebhookSubscriptionSerializer {
+    #[validate(custom(function = "validate_webhook_url"))]
+    pub url: String,
+    /// Leave empty to receive every event.
+    #[serde(default)]
+    #[validate(custom(function = "validate_webhook_events"))]
+    pub events: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, Validate)]
+pub struct UpdateWebhookSubscriptionSerializer {
+    #[validate(custom(function = "validate_webhook_url"))]
+    pub url: Option<String>,
+    #[validate(custom(function = "validate_webhook_events"))]
+    pub events: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReadWebhookSubscriptionSerializer {
+    pub webhook_subscription_id: i64,
+    pub retreat_id: Option<i64>,
+    pub url: String,
+    pub events: JsonValue,
+    pub is_active: bool,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl From<WebhookSubscriptionModel> for ReadWebhookSubscriptionSerializer {
+    fn from(value: WebhookSubscriptionModel) -> Self {
+        map_fields!(value, ReadWebhookSubscriptionSerializer, {
+            webhook_subscription_id,
+            retreat_id,
+            url,
+            events,
+            is_active,
+            created_at,
+            updated_at
+        })
+    }
+}
+
+/// Only returned on creation, the secret is never shown again.
+#[derive(Serialize, Clone, Debug)]
+pub struct CreatedWebhookSubscriptionSerializer {
+    #[serde(flatten)]
+    pub subscription: ReadWebhookSubscriptionSerializer,
+    pub secret: String,
+}
+
+impl From<WebhookSubscriptionModel> for CreatedWebhookSubscriptionSerializer {
+    fn from(value: WebhookSubscriptionModel) -> Self {
+        let secret: String = value.secret.clone();
+        CreatedWebhookSubscriptionSerializer {
+            subscription: value.into(),
+            secret,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReadWebhookDeliverySerializer {
+    pub webhook_delivery_id: i64,
+    pub webhook_subscription_id: i64,
+    pub event: String,
+    pub payload: JsonValue,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTimeWithTimeZone,
+    pub last_status_code: Option<i16>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl From<WebhookDeliveryModel> for ReadWebhookDeliverySerializer {
+    fn from(value: WebhookDeliveryModel) -> Self {
+        map_fields!(value, ReadWebhookDeliverySerializer, {
+            webhook_delivery_id,
+            webhook_subscription_id,
+            event,
+            payload,
+            status,
+            attempts,
+            next_attempt_at,
+            last_status_code,
+            last_error,
+            delivered_at,
+            created_at
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ConnectionTrait, EntityTrait, IntoActiveModel, TransactionTrait,
+};
+
+use crate::{
+    audit::{AuditAction, record_change},
+    entities_helper::{CategoryActiveModel, CategoryColumn, CategoryEntity, CategoryModel},
+    serializers::categories::{CreateCategorySerializer, UpdateCategorySerializer},
+    services::ServiceError,
+    set_active_model_fields, set_fields,
+    utils::etag::{delete_unmodified, save_unmodified},
+};
+
+pub async fn find_category<C: ConnectionTrait>(
+    db: &C,
+    category_id: i64,
+) -> Result<CategoryModel, ServiceError> {
+    CategoryEntity::find_by_id(category_id)
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound("Category not found."))
+}
+
+pub async fn create_category<C>(
+    db: &C,
+    payload: CreateCategorySerializer,
+) -> Result<CategoryModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    let active_model: CategoryActiveModel =
+        set_active_model_fields!(payload, CategoryActiveModel, { name, description });
+    let instance: CategoryModel = active_model.insert(&transaction).await?;
+    record_change(&transaction, AuditAction::Create, None, Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Applies `payload` to `instance` as it was read, failing with `ServiceError::Modified`
+/// if the category changed meanwhile.
+pub async fn update_category<C>(
+    db: &C,
+    instance: CategoryModel,
+    payload: UpdateCategorySerializer,
+) -> Result<CategoryModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let updated_at = instance.updated_at;
+    let before: CategoryModel = instance.clone();
+    let mut active_model: CategoryActiveModel = instance.into_active_model();
+    set_fields!(active_model, payload, name, description);
+
+    let transaction = db.begin().await?;
+    let instance: CategoryModel = save_unmodified(
+        &transaction,
+        active_model,
+        CategoryColumn::UpdatedAt,
+        updated_at,
+    )
+    .await?;
+    record_change(&transaction, AuditAction::Update, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Deletes the category for good, unless it changed since `instance` was read.
+pub async fn delete_category<C>(db: &C, instance: CategoryModel) -> Result<(), ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let updated_at = instance.updated_at;
+    let transaction = db.begin().await?;
+    delete_unmodified(
+        &transaction,
+        instance.clone().into_active_model(),
+        CategoryColumn::UpdatedAt,
+        updated_at,
+    )
+    .await?;
+    record_change(&transaction, AuditAction::Delete, Some(&instance), None).await?;
+    transaction.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use serde_json::json;
+
+use crate::{
+    audit::{AuditAction, record_change},
+    entities_helper::{
+        GalleryCategoriesEntity, RetreatGalleriesActiveModel, RetreatGalleriesColumn,
+        RetreatGalleriesEntity, RetreatGalleriesModel,
+    },
+    events::{DomainEvent, record_event},
+    serializers::retreat_galleries::ReadRetreatGallerySerializer,
+    services::{ServiceError, retreats::find_retreat},
+    utils::{etag::save_unmodified, soft_delete::SoftDelete},
+};
+
+/// The fields of a gallery item read from a form, `None` leaves a field as it is. The
+/// image itself is stored by the caller, only its path ends up here.
+#[derive(Debug, Clone, Default)]
+pub struct GalleryChanges {
+    pub caption: Option<String>,
+    pub image_path: Option<String>,
+    pub gallery_category_id: Option<i64>,
+}
+
+impl GalleryChanges {
+    async fn apply<C: ConnectionTrait>(
+        self,
+        db: &C,
+        active_model: &mut RetreatGalleriesActiveModel,
+    ) -> Result<(), ServiceError> {
+        if let Some(gallery_category_id) = self.gallery_category_id {
+            GalleryCategoriesEntity::find_by_id(gallery_category_id)
+                .one(db)
+                .await?
+                .ok_or(ServiceError::NotFound("Gallery Category not found."))?;
+            active_model.gallery_category_id = Set(Some(gallery_category_id));
+        }
+        if let Some(caption) = self.caption {
+            active_model.caption = Set(Some(caption));
+        }
+        if let Some(image_path) = self.image_path {
+            active_model.image_path = Set(image_path);
+        }
+        Ok(())
+    }
+}
+
+pub async fn create_gallery<C>(
+    db: &C,
+    retreat_id: i64,
+    changes: GalleryChanges,
+) -> Result<RetreatGalleriesModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    find_retreat(&transaction, retreat_id).await?;
+    let mut active_model: RetreatGalleriesActiveModel = RetreatGalleriesActiveModel {
+        caption: Set(None),
+        image_path: Set(String::new()),
+        retreat_id: Set(retreat_id),
+        gallery_category_id: Set(None),
+        ..Default::default()
+    };
+    changes.apply(&transaction, &mut active_model).await?;
+    let instance: RetreatGalleriesModel = active_model.insert(&transaction).await?;
+    record_change(&transaction, AuditAction::Create, None, Some(&instance)).await?;
+
+    let event = DomainEvent::GalleryCreated {
+        retreat_id,
+        gallery: json!(ReadRetreatGallerySerializer::from(instance.clone())),
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+pub async fn find_gallery<C: ConnectionTrait>(
+    db: &C,
+    retreat_id: i64,
+    gallery_id: i64,
+) -> Result<RetreatGalleriesModel, ServiceError> {
+    RetreatGalleriesEntity::find_active()
+        .filter(RetreatGalleriesColumn::GalleryId.eq(gallery_id))
+        .filter(RetreatGalleriesColumn::RetreatId.eq(retreat_id))
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound("Retreat gallery not found."))
+}
+
+/// Applies `changes` to `instance` as it was read, failing with `ServiceError::Modified`
+/// if the item changed meanwhile.
+pub async fn update_gallery<C>(
+    db: &C,
+    instance: RetreatGalleriesModel,
+    changes: GalleryChanges,
+) -> Result<RetreatGalleriesModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let retreat_id: i64 = instance.retreat_id;
+    let updated_at = instance.updated_at;
+    let before: RetreatGalleriesModel = instance.clone();
+    let mut active_model: RetreatGalleriesActiveModel = instance.into_active_model();
+
+    let transaction = db.begin().await?;
+    changes.apply(&transaction, &mut active_model).await?;
+    let instance: RetreatGalleriesModel = save_unmodified(
+        &transaction,
+        active_model,
+        RetreatGalleriesColumn::UpdatedAt,
+        updated_at,
+    )
+    .await?;
+    record_change(&transaction, AuditAction::Update, Some(&before), Some(&instance)).await?;
+    let event = DomainEvent::GalleryUpdated {
+        retreat_id,
+        gallery: json!(ReadRetreatGallerySerializer::from(instance.clone())),
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Only hides the item, the image is kept until the retention purge so it can be restored.
+pub async fn delete_gallery<C>(
+    db: &C,
+    instance: RetreatGalleriesModel,
+    now: DateTime<FixedOffset>,
+) -> Result<(), ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let retreat_id: i64 = instance.retreat_id;
+    let updated_at = instance.updated_at;
+    let event = DomainEvent::GalleryDeleted {
+        retreat_id,
+        gallery: json!(ReadRetreatGallerySerializer::from(instance.clone())),
+    };
+    let before: RetreatGalleriesModel = instance.clone();
+    let mut active_model: RetreatGalleriesActiveModel = instance.into_active_model();
+    active_model.deleted_at = Set(Some(now));
+
+    let transaction = db.begin().await?;
+    let instance: RetreatGalleriesModel = save_unmodified(
+        &transaction,
+        active_model,
+        RetreatGalleriesColumn::UpdatedAt,
+        updated_at,
+    )
+    .await?;
+    record_change(&transaction, AuditAction::Delete, Some(&before), Some(&instance)).await?;
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn restore_gallery<C>(
+    db: &C,
+    retreat_id: i64,
+    gallery_id: i64,
+) -> Result<RetreatGalleriesModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    let instance: RetreatGalleriesModel = RetreatGalleriesEntity::find_deleted()
+        .filter(RetreatGalleriesColumn::GalleryId.eq(gallery_id))
+        .filter(RetreatGalleriesColumn::RetreatId.eq(retreat_id))
+        .one(&transaction)
+        .await?
+        .ok_or(ServiceError::NotFound("Deleted retreat gallery not found."))?;
+
+    let before: RetreatGalleriesModel = instance.clone();
+    let mut active_model: RetreatGalleriesActiveModel = instance.into_active_model();
+    active_model.deleted_at = Set(None);
+    let instance: RetreatGalleriesModel = active_model.update(&transaction).await?;
+    record_change(&transaction, AuditAction::Restore, Some(&before), Some(&instance)).await?;
+
+    let event = DomainEvent::GalleryRestored {
+        retreat_id,
+        gallery: json!(ReadRetreatGallerySerializer::from(instance.clone())),
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
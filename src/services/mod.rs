@@ -0,0 +1,84 @@
+//! Use cases shared by the REST handlers and the GraphQL mutations. Each takes a
+//! connection, or the transaction of a larger change to join, and saves its writes
+//! together with their events and audit log entries all or nothing. Caching and HTTP details stay with the callers.
+
+pub mod categories;
+pub mod gallery;
+pub mod retreats;
+pub mod reviews;
+pub mod staff;
+pub mod users;
+pub mod wishlists;
+
+use std::fmt;
+
+use axum::{
+    body::Body,
+    http::{Response, StatusCode},
+};
+use sea_orm::DbErr;
+
+use crate::utils::{
+    etag::precondition_failed,
+    response::{to_error_response, to_error_response_with_message},
+};
+
+/// Why a use case did not go through, independent of whether REST or GraphQL asked.
+#[derive(Debug)]
+pub enum ServiceError {
+    NotFound(&'static str),
+    Invalid(String),
+    /// The acting user may not make this change.
+    Forbidden(&'static str),
+    /// The row changed since the caller read it.
+    Modified,
+    /// A unique value is held by another row, possibly one soft deleted and pending purge.
+    Conflict(&'static str),
+    Database(DbErr),
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceError::NotFound(message) => f.write_str(message),
+            ServiceError::Invalid(message) => f.write_str(message),
+            ServiceError::Forbidden(message) => f.write_str(message),
+            ServiceError::Modified => {
+                f.write_str("Resource has been modified, fetch it again before updating.")
+            }
+            ServiceError::Conflict(message) => f.write_str(message),
+            ServiceError::Database(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<DbErr> for ServiceError {
+    /// `save_unmodified` reports a concurrent writer as a record it could not update.
+    fn from(e: DbErr) -> Self {
+        match e {
+            DbErr::RecordNotUpdated => ServiceError::Modified,
+            e => ServiceError::Database(e),
+        }
+    }
+}
+
+impl From<ServiceError> for Response<Body> {
+    fn from(e: ServiceError) -> Self {
+        match e {
+            ServiceError::NotFound(message) => {
+                to_error_response_with_message(message, StatusCode::NOT_FOUND)
+            }
+            ServiceError::Invalid(message) => {
+                to_error_response_with_message(&message, StatusCode::BAD_REQUEST)
+            }
+            ServiceError::Forbidden(message) => {
+                to_error_response_with_message(message, StatusCode::FORBIDDEN)
+            }
+            ServiceError::Modified => precondition_failed(),
+            ServiceError::Conflict(message) => {
+                to_error_response_with_message(message, StatusCode::CONFLICT)
+            }
+            ServiceError::Database(e) => to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+}
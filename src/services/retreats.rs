@@ -0,0 +1,251 @@
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use serde_json::{Value as JsonValue, json};
+
+use crate::{
+    audit::{AuditAction, record_change},
+    entities_helper::{
+        OrganizationEntity, RetreatActiveModel, RetreatColumn, RetreatEntity, RetreatModel,
+    },
+    events::{DomainEvent, record_event},
+    serializers::retreats::{
+        CreateRetreatSerializer, ReadRetreatSerializer, UpdateRetreatSerializer,
+    },
+    services::ServiceError,
+    set_active_model_fields, set_fields,
+    utils::{
+        etag::save_unmodified,
+        permissions::organization_membership,
+        soft_delete::{SoftDelete, slug_conflict},
+    },
+};
+
+pub async fn find_retreat<C: ConnectionTrait>(
+    db: &C,
+    retreat_id: i64,
+) -> Result<RetreatModel, ServiceError> {
+    RetreatEntity::find_active()
+        .filter(RetreatColumn::RetreatId.eq(retreat_id))
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound("Retreat not found."))
+}
+
+/// Moving a retreat into an organization, or out of one, takes an owner of it. `actor`
+/// is the acting user, anonymous requests cannot move retreats at all.
+async fn ensure_organization_owner<C: ConnectionTrait>(
+    db: &C,
+    organization_id: i64,
+    actor: Option<i64>,
+) -> Result<(), ServiceError> {
+    OrganizationEntity::find_by_id(organization_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ServiceError::Invalid("Organization not found.".to_string()))?;
+    let is_owner: bool = match actor {
+        Some(user_id) => organization_membership(db, organization_id, user_id)
+            .await?
+            .is_some_and(|member| member.is_owner),
+        None => false,
+    };
+    if !is_owner {
+        return Err(ServiceError::Forbidden(
+            "Only owners of an organization can move retreats into or out of it.",
+        ));
+    }
+    Ok(())
+}
+
+/// `retreat_id` is the retreat keeping its own slug on update.
+async fn ensure_slug_available<C: ConnectionTrait>(
+    db: &C,
+    slug: &str,
+    retreat_id: Option<i64>,
+) -> Result<(), ServiceError> {
+    match slug_conflict(db, slug, retreat_id).await? {
+        Some(message) => Err(ServiceError::Conflict(message)),
+        None => Ok(()),
+    }
+}
+
+/// Creates the retreat in `base_currency` unless the payload names one, an organization
+/// given must be owned by `actor`.
+pub async fn create_retreat<C>(
+    db: &C,
+    payload: CreateRetreatSerializer,
+    base_currency: &str,
+    actor: Option<i64>,
+) -> Result<RetreatModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    if let Some(organization_id) = payload.organization_id {
+        ensure_organization_owner(&transaction, organization_id, actor).await?;
+    }
+    ensure_slug_available(&transaction, &payload.slug, None).await?;
+
+    let mut active_model: RetreatActiveModel = set_active_model_fields!(payload, RetreatActiveModel, {
+        name,
+        description,
+        category_id,
+        slug,
+        social_links,
+        email,
+        phone,
+        latitude,
+        longitude,
+        address,
+        organization_id
+    });
+    active_model.currency = Set(payload
+        .currency
+        .clone()
+        .unwrap_or_else(|| base_currency.to_string()));
+    let instance: RetreatModel = active_model.insert(&transaction).await?;
+    record_change(&transaction, AuditAction::Create, None, Some(&instance)).await?;
+
+    let event = DomainEvent::RetreatCreated {
+        retreat_id: instance.retreat_id,
+        retreat: json!(ReadRetreatSerializer::from(instance.clone())),
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Applies `payload` to `instance` as it was read, failing with `ServiceError::Modified`
+/// if someone else changed the retreat meanwhile. Changing its organization takes `actor`
+/// to own both the old and the new one.
+pub async fn update_retreat<C>(
+    db: &C,
+    instance: RetreatModel,
+    payload: UpdateRetreatSerializer,
+    actor: Option<i64>,
+) -> Result<RetreatModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    if let Some(organization_id) = payload.organization_id
+        && organization_id != instance.organization_id
+    {
+        for organization_id in [organization_id, instance.organization_id].into_iter().flatten() {
+            ensure_organization_owner(&transaction, organization_id, actor).await?;
+        }
+    }
+    if let Some(slug) = &payload.slug {
+        ensure_slug_available(&transaction, slug, Some(instance.retreat_id)).await?;
+    }
+
+    let retreat_id: i64 = instance.retreat_id;
+    let updated_at = instance.updated_at;
+    let was_published: bool = instance.is_published;
+    let before: RetreatModel = instance.clone();
+    let mut active_model: RetreatActiveModel = instance.into_active_model();
+    set_fields!(
+        active_model,
+        payload,
+        name,
+        description,
+        category_id,
+        slug,
+        social_links,
+        email,
+        phone,
+        longitude,
+        latitude,
+        address,
+        budget_min,
+        budget_max,
+        currency,
+        is_published,
+        organization_id
+    );
+    let instance: RetreatModel = save_unmodified(
+        &transaction,
+        active_model,
+        RetreatColumn::UpdatedAt,
+        updated_at,
+    )
+    .await?;
+    record_change(&transaction, AuditAction::Update, Some(&before), Some(&instance)).await?;
+
+    let retreat: JsonValue = json!(ReadRetreatSerializer::from(instance.clone()));
+    let event: DomainEvent = if instance.is_published && !was_published {
+        DomainEvent::RetreatPublished {
+            retreat_id,
+            retreat,
+        }
+    } else {
+        DomainEvent::RetreatUpdated {
+            retreat_id,
+            retreat,
+        }
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Only hides the retreat, its reviews, galleries and staff stay until the retention purge.
+pub async fn delete_retreat<C>(
+    db: &C,
+    instance: RetreatModel,
+    now: DateTime<FixedOffset>,
+) -> Result<(), ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let retreat_id: i64 = instance.retreat_id;
+    let updated_at = instance.updated_at;
+    let event = DomainEvent::RetreatDeleted {
+        retreat_id,
+        retreat: json!(ReadRetreatSerializer::from(instance.clone())),
+    };
+    let before: RetreatModel = instance.clone();
+    let mut active_model: RetreatActiveModel = instance.into_active_model();
+    active_model.deleted_at = Set(Some(now));
+
+    let transaction = db.begin().await?;
+    let instance: RetreatModel = save_unmodified(
+        &transaction,
+        active_model,
+        RetreatColumn::UpdatedAt,
+        updated_at,
+    )
+    .await?;
+    record_change(&transaction, AuditAction::Delete, Some(&before), Some(&instance)).await?;
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn restore_retreat<C>(db: &C, retreat_id: i64) -> Result<RetreatModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    let instance: RetreatModel = RetreatEntity::find_deleted()
+        .filter(RetreatColumn::RetreatId.eq(retreat_id))
+        .one(&transaction)
+        .await?
+        .ok_or(ServiceError::NotFound("Deleted retreat not found."))?;
+
+    let before: RetreatModel = instance.clone();
+    let mut active_model: RetreatActiveModel = instance.into_active_model();
+    active_model.deleted_at = Set(None);
+    let instance: RetreatModel = active_model.update(&transaction).await?;
+    record_change(&transaction, AuditAction::Restore, Some(&before), Some(&instance)).await?;
+
+    let event = DomainEvent::RetreatRestored {
+        retreat_id,
+        retreat: json!(ReadRetreatSerializer::from(instance.clone())),
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
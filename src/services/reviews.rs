@@ -0,0 +1,150 @@
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, IntoActiveModel,
+    QueryFilter, TransactionTrait,
+};
+use serde_json::json;
+
+use crate::{
+    audit::{AuditAction, record_change},
+    entities_helper::{
+        RetreatReviewActiveModel, RetreatReviewColumn, RetreatReviewEntity, RetreatReviewModel,
+    },
+    events::{DomainEvent, record_event},
+    serializers::retreat_reviews::{
+        CreateRetreatReviewSerializer, ReadRetreatReviewSerializer, UpdateRetreatReviewSerializer,
+    },
+    services::{ServiceError, retreats::find_retreat},
+    set_fields,
+    utils::{etag::save_unmodified, soft_delete::SoftDelete},
+};
+
+pub async fn post_review<C>(
+    db: &C,
+    retreat_id: i64,
+    user_id: i64,
+    payload: CreateRetreatReviewSerializer,
+) -> Result<RetreatReviewModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    find_retreat(&transaction, retreat_id).await?;
+    let instance: RetreatReviewModel = RetreatReviewActiveModel {
+        rating: Set(payload.rating),
+        review: Set(payload.review),
+        user_id: Set(user_id),
+        retreat_id: Set(retreat_id),
+        ..Default::default()
+    }
+    .insert(&transaction)
+    .await?;
+    record_change(&transaction, AuditAction::Create, None, Some(&instance)).await?;
+
+    let event = DomainEvent::ReviewPosted {
+        retreat_id,
+        review: json!(ReadRetreatReviewSerializer::from(instance.clone())),
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Reviews can only be changed by their author. `retreat_id` narrows the lookup to one
+/// retreat, for routes nested under it.
+pub async fn find_own_review<C: ConnectionTrait>(
+    db: &C,
+    retreat_id: Option<i64>,
+    review_id: i64,
+    user_id: i64,
+) -> Result<RetreatReviewModel, ServiceError> {
+    let mut select = RetreatReviewEntity::find_active()
+        .filter(RetreatReviewColumn::ReviewId.eq(review_id))
+        .filter(RetreatReviewColumn::UserId.eq(user_id));
+    if let Some(retreat_id) = retreat_id {
+        select = select.filter(RetreatReviewColumn::RetreatId.eq(retreat_id));
+    }
+    select
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound("Retreat review not found."))
+}
+
+/// Applies `payload` to `instance` as it was read, failing with `ServiceError::Modified`
+/// if the review changed meanwhile.
+pub async fn update_review<C>(
+    db: &C,
+    instance: RetreatReviewModel,
+    payload: UpdateRetreatReviewSerializer,
+) -> Result<RetreatReviewModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let updated_at = instance.updated_at;
+    let before: RetreatReviewModel = instance.clone();
+    let mut active_model: RetreatReviewActiveModel = instance.into_active_model();
+    set_fields!(active_model, payload, rating, review);
+
+    let transaction = db.begin().await?;
+    let instance: RetreatReviewModel = save_unmodified(
+        &transaction,
+        active_model,
+        RetreatReviewColumn::UpdatedAt,
+        updated_at,
+    )
+    .await?;
+    record_change(&transaction, AuditAction::Update, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Only hides the review until the retention purge, so it can still be restored.
+pub async fn delete_review<C>(
+    db: &C,
+    instance: RetreatReviewModel,
+    now: DateTime<FixedOffset>,
+) -> Result<(), ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let updated_at = instance.updated_at;
+    let before: RetreatReviewModel = instance.clone();
+    let mut active_model: RetreatReviewActiveModel = instance.into_active_model();
+    active_model.deleted_at = Set(Some(now));
+
+    let transaction = db.begin().await?;
+    let instance: RetreatReviewModel = save_unmodified(
+        &transaction,
+        active_model,
+        RetreatReviewColumn::UpdatedAt,
+        updated_at,
+    )
+    .await?;
+    record_change(&transaction, AuditAction::Delete, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn restore_review<C>(
+    db: &C,
+    retreat_id: i64,
+    review_id: i64,
+) -> Result<RetreatReviewModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    let instance: RetreatReviewModel = RetreatReviewEntity::find_deleted()
+        .filter(RetreatReviewColumn::ReviewId.eq(review_id))
+        .filter(RetreatReviewColumn::RetreatId.eq(retreat_id))
+        .one(&transaction)
+        .await?
+        .ok_or(ServiceError::NotFound("Deleted retreat review not found."))?;
+    let before: RetreatReviewModel = instance.clone();
+    let mut active_model: RetreatReviewActiveModel = instance.into_active_model();
+    active_model.deleted_at = Set(None);
+    let instance: RetreatReviewModel = active_model.update(&transaction).await?;
+    record_change(&transaction, AuditAction::Restore, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
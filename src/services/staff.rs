@@ -0,0 +1,123 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+
+use crate::{
+    audit::{AuditAction, record_change},
+    entities_helper::{
+        RetreatUserActiveModel, RetreatUserColumn, RetreatUserEntity, RetreatUserModel,
+        UserColumn, UserEntity, UserModel,
+    },
+    events::{DomainEvent, record_event},
+    serializers::retreats::{CreateRetreatUserSerializer, UpdateRetreatUserSerializer},
+    services::{ServiceError, retreats::find_retreat, users::invite_user},
+    set_fields,
+    state::Clock,
+    utils::soft_delete::SoftDelete,
+};
+
+#[derive(Debug)]
+pub enum AddedStaff {
+    Added(RetreatUserModel),
+    /// The email belongs to a user going by another name, nothing was saved.
+    NameMismatch(String),
+}
+
+/// Adds the user with the payload's email as staff, inviting them first if they have no
+/// account yet. The user, their password job, the staff row and the event are saved all
+/// or nothing.
+pub async fn add_staff<C>(
+    db: &C,
+    clock: &Clock,
+    retreat_id: i64,
+    payload: CreateRetreatUserSerializer,
+) -> Result<AddedStaff, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    find_retreat(&transaction, retreat_id).await?;
+
+    let user: Option<UserModel> = UserEntity::find_active()
+        .filter(UserColumn::Email.eq(&payload.email))
+        .one(&transaction)
+        .await?;
+    let user_id: i64 = match user {
+        Some(user) if user.name != payload.name => {
+            return Ok(AddedStaff::NameMismatch(user.name));
+        }
+        Some(user) => user.user_id,
+        None => invite_user(&transaction, clock, payload.name, payload.email)
+            .await?
+            .user_id,
+    };
+
+    let instance: RetreatUserModel = RetreatUserActiveModel {
+        retreat_id: Set(retreat_id),
+        user_id: Set(user_id),
+        role: Set(Some(payload.role.clone())),
+        ..Default::default()
+    }
+    .insert(&transaction)
+    .await?;
+    record_change(&transaction, AuditAction::Create, None, Some(&instance)).await?;
+
+    let event = DomainEvent::StaffAdded {
+        retreat_id,
+        user_id,
+        role: payload.role,
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(AddedStaff::Added(instance))
+}
+
+pub async fn find_staff<C: ConnectionTrait>(
+    db: &C,
+    retreat_id: i64,
+    retreat_user_id: i64,
+) -> Result<RetreatUserModel, ServiceError> {
+    find_retreat(db, retreat_id).await?;
+    RetreatUserEntity::find()
+        .filter(RetreatUserColumn::RetreatUserId.eq(retreat_user_id))
+        .filter(RetreatUserColumn::RetreatId.eq(retreat_id))
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound("Staff not found."))
+}
+
+pub async fn update_staff<C>(
+    db: &C,
+    retreat_id: i64,
+    retreat_user_id: i64,
+    payload: UpdateRetreatUserSerializer,
+) -> Result<RetreatUserModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    let before: RetreatUserModel = find_staff(&transaction, retreat_id, retreat_user_id).await?;
+    let mut active_model: RetreatUserActiveModel = before.clone().into_active_model();
+    set_fields!(active_model, payload, role);
+    let instance: RetreatUserModel = active_model.update(&transaction).await?;
+    record_change(&transaction, AuditAction::Update, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+pub async fn remove_staff<C>(
+    db: &C,
+    retreat_id: i64,
+    retreat_user_id: i64,
+) -> Result<(), ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    let instance: RetreatUserModel = find_staff(&transaction, retreat_id, retreat_user_id).await?;
+    instance.clone().into_active_model().delete(&transaction).await?;
+    record_change(&transaction, AuditAction::Delete, Some(&instance), None).await?;
+    transaction.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, IntoActiveModel,
+    QueryFilter, TransactionTrait,
+};
+
+use crate::{
+    audit::{AuditAction, record_change, record_password_change},
+    entities_helper::{UserActiveModel, UserColumn, UserEntity, UserModel},
+    jobs::{enqueue, handlers::SetInvitedUserPassword},
+    serializers::users::{CreateUserSerializer, UpdateUserSerializer},
+    services::ServiceError,
+    set_fields,
+    state::Clock,
+    utils::{
+        etag::save_unmodified,
+        soft_delete::{SoftDelete, email_conflict},
+    },
+};
+
+pub async fn find_user<C: ConnectionTrait>(db: &C, user_id: i64) -> Result<UserModel, ServiceError> {
+    UserEntity::find_active()
+        .filter(UserColumn::UserId.eq(user_id))
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound("User not found."))
+}
+
+/// Emails stay unique across soft deleted users, see `email_conflict`.
+pub async fn ensure_email_available<C: ConnectionTrait>(
+    db: &C,
+    email: &str,
+) -> Result<(), ServiceError> {
+    match email_conflict(db, email).await? {
+        Some(message) => Err(ServiceError::Conflict(message)),
+        None => Ok(()),
+    }
+}
+
+/// Signs a user up, `hashed_password` is the payload's password hashed by the caller.
+pub async fn create_user<C>(
+    db: &C,
+    payload: CreateUserSerializer,
+    hashed_password: String,
+) -> Result<UserModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    ensure_email_available(&transaction, &payload.email).await?;
+    let instance: UserModel = UserActiveModel {
+        name: Set(payload.name),
+        email: Set(payload.email),
+        password: Set(hashed_password),
+        phone: Set(payload.phone),
+        ..Default::default()
+    }
+    .insert(&transaction)
+    .await?;
+    record_change(&transaction, AuditAction::Create, None, Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Creates a user invited by email, as staff or as a member, in the caller's transaction.
+/// The password is hashed by a background job, the user cannot sign in until it ran.
+pub async fn invite_user<C: ConnectionTrait>(
+    db: &C,
+    clock: &Clock,
+    name: String,
+    email: String,
+) -> Result<UserModel, ServiceError> {
+    ensure_email_available(db, &email).await?;
+    let instance: UserModel = UserActiveModel {
+        name: Set(name),
+        email: Set(email),
+        password: Set(String::new()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    record_change(db, AuditAction::Create, None, Some(&instance)).await?;
+    enqueue(
+        db,
+        clock,
+        &SetInvitedUserPassword {
+            user_id: instance.user_id,
+        },
+    )
+    .await?;
+    Ok(instance)
+}
+
+/// Creates an admin for the `create-admin` command, the only way to make one.
+pub async fn create_admin<C>(
+    db: &C,
+    name: String,
+    email: String,
+    hashed_password: String,
+) -> Result<UserModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    ensure_email_available(&transaction, &email).await?;
+    let instance: UserModel = UserActiveModel {
+        name: Set(name),
+        email: Set(email),
+        password: Set(hashed_password),
+        is_admin: Set(true),
+        ..Default::default()
+    }
+    .insert(&transaction)
+    .await?;
+    record_change(&transaction, AuditAction::Create, None, Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Gives an existing user admin rights, keeping their password.
+pub async fn grant_admin<C>(db: &C, instance: UserModel) -> Result<UserModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let before: UserModel = instance.clone();
+    let mut active_model: UserActiveModel = instance.into_active_model();
+    active_model.is_admin = Set(true);
+
+    let transaction = db.begin().await?;
+    let instance: UserModel = active_model.update(&transaction).await?;
+    record_change(&transaction, AuditAction::Update, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Replaces the password with `hashed_password`, hashed by the caller.
+pub async fn reset_password<C>(
+    db: &C,
+    instance: UserModel,
+    hashed_password: String,
+) -> Result<UserModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let mut active_model: UserActiveModel = instance.into_active_model();
+    active_model.password = Set(hashed_password);
+
+    let transaction = db.begin().await?;
+    let instance: UserModel = active_model.update(&transaction).await?;
+    record_password_change(&transaction, &instance).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Applies `payload` to `instance` as it was read, failing with `ServiceError::Modified`
+/// if the user changed meanwhile.
+pub async fn update_user<C>(
+    db: &C,
+    instance: UserModel,
+    payload: UpdateUserSerializer,
+) -> Result<UserModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let updated_at = instance.updated_at;
+    let before: UserModel = instance.clone();
+    let mut active_model: UserActiveModel = instance.into_active_model();
+    set_fields!(active_model, payload, name, email, phone);
+
+    let transaction = db.begin().await?;
+    let instance: UserModel =
+        save_unmodified(&transaction, active_model, UserColumn::UpdatedAt, updated_at).await?;
+    record_change(&transaction, AuditAction::Update, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+/// Only hides the user until the retention purge, so they can still be restored.
+pub async fn delete_user<C>(
+    db: &C,
+    instance: UserModel,
+    now: DateTime<FixedOffset>,
+) -> Result<(), ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let updated_at = instance.updated_at;
+    let before: UserModel = instance.clone();
+    let mut active_model: UserActiveModel = instance.into_active_model();
+    active_model.deleted_at = Set(Some(now));
+
+    let transaction = db.begin().await?;
+    let instance: UserModel =
+        save_unmodified(&transaction, active_model, UserColumn::UpdatedAt, updated_at).await?;
+    record_change(&transaction, AuditAction::Delete, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+pub async fn restore_user<C>(db: &C, user_id: i64) -> Result<UserModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    let before: UserModel = UserEntity::find_deleted()
+        .filter(UserColumn::UserId.eq(user_id))
+        .one(&transaction)
+        .await?
+        .ok_or(ServiceError::NotFound("Deleted user not found."))?;
+    let mut active_model: UserActiveModel = before.clone().into_active_model();
+    active_model.deleted_at = Set(None);
+    let instance: UserModel = active_model.update(&transaction).await?;
+    record_change(&transaction, AuditAction::Restore, Some(&before), Some(&instance)).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
@@ -0,0 +1,63 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+
+use crate::{
+    entities_helper::{WishlistActiveModel, WishlistColumn, WishlistEntity, WishlistModel},
+    events::{DomainEvent, record_event},
+    services::{ServiceError, retreats::find_retreat},
+};
+
+/// Adding a retreat twice is not an error, the existing item is returned.
+pub async fn add_to_wishlist<C>(
+    db: &C,
+    retreat_id: i64,
+    user_id: i64,
+) -> Result<WishlistModel, ServiceError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    find_retreat(&transaction, retreat_id).await?;
+    let existing: Option<WishlistModel> = WishlistEntity::find()
+        .filter(WishlistColumn::RetreatId.eq(retreat_id))
+        .filter(WishlistColumn::UserId.eq(user_id))
+        .one(&transaction)
+        .await?;
+    if let Some(existing) = existing {
+        return Ok(existing);
+    }
+
+    let instance: WishlistModel = WishlistActiveModel {
+        retreat_id: Set(retreat_id),
+        user_id: Set(user_id),
+        ..Default::default()
+    }
+    .insert(&transaction)
+    .await?;
+    let event = DomainEvent::WishlistAdded {
+        retreat_id,
+        user_id,
+    };
+    record_event(&transaction, &event).await?;
+    transaction.commit().await?;
+    Ok(instance)
+}
+
+pub async fn remove_from_wishlist<C: ConnectionTrait>(
+    db: &C,
+    retreat_id: i64,
+    user_id: i64,
+) -> Result<(), ServiceError> {
+    WishlistEntity::find()
+        .filter(WishlistColumn::RetreatId.eq(retreat_id))
+        .filter(WishlistColumn::UserId.eq(user_id))
+        .one(db)
+        .await?
+        .ok_or(ServiceError::NotFound("Wishlist not found."))?
+        .into_active_model()
+        .delete(db)
+        .await?;
+    Ok(())
+}
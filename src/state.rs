@@ -1,17 +1,111 @@
-use sea_orm::Database;
+use std::{sync::Arc, time::Duration};
 
-use crate::env;
+use sea_orm::{DatabaseConnection, DbErr};
 
+use crate::{
+    config::Config,
+    live::LiveHub,
+    utils::{
+        cache::ResponseCache,
+        database::{RecentWriters, ReplicatedDatabase, connect},
+        shutdown::Shutdown,
+    },
+};
+
+pub use crate::utils::{clock::Clock, storage::Storage};
 
 #[derive(Clone, Debug)]
 pub struct AppState {
-    pub database: sea_orm::DatabaseConnection,
+    pub config: Arc<Config>,
+    pub database: ReplicatedDatabase,
+    pub recent_writers: RecentWriters,
+    pub storage: Storage,
+    pub clock: Clock,
+    pub shutdown: Shutdown,
+    pub live: LiveHub,
+    pub cache: ResponseCache,
 }
 
 impl AppState {
-    pub async fn new() -> Self {
-        Self {
-            database: Database::connect(&env::ENV.database_url).await.unwrap(),
+    pub async fn new(config: Arc<Config>) -> Self {
+        Self::try_new(config).await.unwrap()
+    }
+
+    /// Like `new`, but hands a failed database connection back to the caller.
+    pub async fn try_new(config: Arc<Config>) -> Result<Self, DbErr> {
+        Self::builder(config).build().await
+    }
+
+    /// Everything not given to the builder comes from `config`.
+    pub fn builder(config: Arc<Config>) -> AppStateBuilder {
+        AppStateBuilder {
+            config,
+            database: None,
+            replica: None,
+            storage: None,
+            clock: Clock::system(),
         }
     }
 }
+
+pub struct AppStateBuilder {
+    config: Arc<Config>,
+    database: Option<DatabaseConnection>,
+    replica: Option<DatabaseConnection>,
+    storage: Option<Storage>,
+    clock: Clock,
+}
+
+impl AppStateBuilder {
+    /// An existing connection, instead of connecting to `DATABASE_URL`.
+    pub fn database(mut self, database: DatabaseConnection) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// A read replica, instead of connecting to `DATABASE_REPLICA_URL` if it is set.
+    pub fn replica(mut self, replica: DatabaseConnection) -> Self {
+        self.replica = Some(replica);
+        self
+    }
+
+    /// Another upload directory than `UPLOAD_DIR`.
+    pub fn storage(mut self, storage: Storage) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub async fn build(self) -> Result<AppState, DbErr> {
+        let config: Arc<Config> = self.config;
+        let database: DatabaseConnection = match self.database {
+            Some(database) => database,
+            None => connect(&config, &config.database_url).await?,
+        };
+        let replica: Option<DatabaseConnection> = match (self.replica, &config.database_replica_url) {
+            (Some(replica), _) => Some(replica),
+            (None, Some(url)) => Some(connect(&config, url).await?),
+            (None, None) => None,
+        };
+        Ok(AppState {
+            database: ReplicatedDatabase::new(database, replica),
+            recent_writers: RecentWriters::default(),
+            storage: self
+                .storage
+                .unwrap_or_else(|| Storage::new(config.upload_dir.clone())),
+            clock: self.clock,
+            live: LiveHub::new(config.live_channel_capacity),
+            cache: ResponseCache::new(
+                Duration::from_secs(config.cache_ttl_in_sec),
+                config.cache_max_entries,
+                config.cache_max_size_in_mb * 1024 * 1024,
+            ),
+            config,
+            shutdown: Shutdown::new(),
+        })
+    }
+}
@@ -0,0 +1,164 @@
+//! One factory per entity, inserting straight into the database. Each fills in unique
+//! defaults so tests only spell out what they assert on.
+
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, prelude::Decimal};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::TestApp;
+use crate::{
+    entities_helper::{
+        CategoryActiveModel, CategoryModel, GalleryCategoriesActiveModel, GalleryCategoriesModel,
+        RetreatActiveModel, RetreatGalleriesActiveModel, RetreatGalleriesModel, RetreatModel,
+        RetreatReviewActiveModel, RetreatReviewModel, RetreatUserActiveModel, RetreatUserModel,
+        UserActiveModel, UserModel, WishlistActiveModel, WishlistModel,
+    },
+    utils::password::create_password,
+};
+
+/// The password of every user the factories create.
+pub const PASSWORD: &str = "password";
+
+fn unique() -> String {
+    Uuid::new_v4().simple().to_string()[..12].to_string()
+}
+
+impl TestApp {
+    pub async fn create_user(&self) -> UserModel {
+        let suffix: String = unique();
+        let password: String = create_password(PASSWORD, &self.state.config.password_salt)
+            .await
+            .unwrap();
+        UserActiveModel {
+            name: Set(format!("User {suffix}")),
+            email: Set(format!("user-{suffix}@example.com")),
+            password: Set(password),
+            ..Default::default()
+        }
+        .insert(&self.state.database)
+        .await
+        .unwrap()
+    }
+
+    pub async fn create_admin(&self) -> UserModel {
+        let user: UserModel = self.create_user().await;
+        let mut active_model: UserActiveModel = user.into();
+        active_model.is_admin = Set(true);
+        active_model.update(&self.state.database).await.unwrap()
+    }
+
+    pub async fn create_category(&self) -> CategoryModel {
+        CategoryActiveModel {
+            name: Set(format!("Category {}", unique())),
+            description: Set(Some("A test category.".to_string())),
+            ..Default::default()
+        }
+        .insert(&self.state.database)
+        .await
+        .unwrap()
+    }
+
+    pub async fn create_gallery_category(&self) -> GalleryCategoriesModel {
+        GalleryCategoriesActiveModel {
+            name: Set(format!("Gallery category {}", unique())),
+            ..Default::default()
+        }
+        .insert(&self.state.database)
+        .await
+        .unwrap()
+    }
+
+    /// A published retreat in a new category.
+    pub async fn create_retreat(&self) -> RetreatModel {
+        let category: CategoryModel = self.create_category().await;
+        let suffix: String = unique();
+        RetreatActiveModel {
+            name: Set(format!("Retreat {suffix}")),
+            slug: Set(format!("retreat-{suffix}")),
+            category_id: Set(category.category_id),
+            social_links: Set(json!({})),
+            budget_min: Set(Some(Decimal::from(50))),
+            budget_max: Set(Some(Decimal::from(150))),
+            currency: Set(self.state.config.base_currency.clone()),
+            is_published: Set(true),
+            ..Default::default()
+        }
+        .insert(&self.state.database)
+        .await
+        .unwrap()
+    }
+
+    pub async fn create_staff(
+        &self,
+        retreat: &RetreatModel,
+        user: &UserModel,
+        is_owner: bool,
+    ) -> RetreatUserModel {
+        RetreatUserActiveModel {
+            retreat_id: Set(retreat.retreat_id),
+            user_id: Set(user.user_id),
+            is_owner: Set(is_owner),
+            role: Set(Some(if is_owner { "Owner" } else { "Staff" }.to_string())),
+            ..Default::default()
+        }
+        .insert(&self.state.database)
+        .await
+        .unwrap()
+    }
+
+    /// A new user who owns a new retreat.
+    pub async fn create_owned_retreat(&self) -> (UserModel, RetreatModel) {
+        let owner: UserModel = self.create_user().await;
+        let retreat: RetreatModel = self.create_retreat().await;
+        self.create_staff(&retreat, &owner, true).await;
+        (owner, retreat)
+    }
+
+    pub async fn create_review(
+        &self,
+        retreat: &RetreatModel,
+        user: &UserModel,
+        rating: f64,
+    ) -> RetreatReviewModel {
+        RetreatReviewActiveModel {
+            retreat_id: Set(retreat.retreat_id),
+            user_id: Set(user.user_id),
+            rating: Set(rating),
+            review: Set(Some("A test review.".to_string())),
+            ..Default::default()
+        }
+        .insert(&self.state.database)
+        .await
+        .unwrap()
+    }
+
+    /// A gallery image, with a file in the storage behind it.
+    pub async fn create_gallery(&self, retreat: &RetreatModel) -> RetreatGalleriesModel {
+        let image_path: String = format!("retreat/gallery/{}.png", unique());
+        let path = self.state.storage.upload_dir().join(&image_path);
+        tokio::fs::create_dir_all(path.parent().unwrap())
+            .await
+            .unwrap();
+        tokio::fs::write(&path, b"not really a png").await.unwrap();
+        RetreatGalleriesActiveModel {
+            retreat_id: Set(retreat.retreat_id),
+            image_path: Set(image_path),
+            caption: Set(Some("A test image.".to_string())),
+            ..Default::default()
+        }
+        .insert(&self.state.database)
+        .await
+        .unwrap()
+    }
+
+    pub async fn create_wishlist(&self, user: &UserModel, retreat: &RetreatModel) -> WishlistModel {
+        WishlistActiveModel {
+            user_id: Set(user.user_id),
+            retreat_id: Set(retreat.retreat_id),
+            ..Default::default()
+        }
+        .insert(&self.state.database)
+        .await
+        .unwrap()
+    }
+}
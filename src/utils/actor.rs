@@ -0,0 +1,115 @@
+//! The user a request acts for, and the `created_by` / `updated_by` columns stamped from it.
+//!
+//! `track_actor` reads the user id from the access token, without a database lookup,
+//! and keeps it for the rest of the request. The `before_save` of every entity with
+//! audit columns stamps them through `stamp_actor`, so handlers and services never set
+//! them by hand. Saves outside a request, by jobs or commands, leave them as they are.
+
+use std::{collections::HashMap, future::Future};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{Response, header},
+    middleware::Next,
+};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+
+use crate::{
+    entities_helper::{UserColumn, UserEntity},
+    serializers::audit::AuditFieldsSerializer,
+    state::AppState,
+    utils::jwt::get_access_token_claim,
+};
+
+tokio::task_local! {
+    /// The authenticated user of the request being served, if any.
+    static ACTOR: Option<i64>;
+}
+
+/// The user the current request acts for, `None` for anonymous requests and outside one.
+pub fn current_actor() -> Option<i64> {
+    ACTOR.try_with(|actor| *actor).ok().flatten()
+}
+
+/// Runs `future` acting for `actor`, for work started outside a request.
+pub async fn with_actor<F: Future>(actor: Option<i64>, future: F) -> F::Output {
+    ACTOR.scope(actor, future).await
+}
+
+/// Remembers the user of a valid bearer token for the request. Invalid tokens are left
+/// to the auth extractors to reject.
+pub async fn track_actor(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response<Body> {
+    let access_token: Option<&str> = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(' ').nth(1));
+    let actor: Option<i64> = match access_token {
+        Some(access_token) => get_access_token_claim(&state.config, access_token)
+            .await
+            .ok()
+            .map(|claim| claim.user_id),
+        None => None,
+    };
+    ACTOR.scope(actor, next.run(request)).await
+}
+
+/// Stamps both columns on insert, unless set already, and `updated_by` on an update that
+/// changes something, so a noop update stays one.
+pub fn stamp_actor<A: ActiveModelTrait>(
+    mut active_model: A,
+    insert: bool,
+    created_by: <A::Entity as EntityTrait>::Column,
+    updated_by: <A::Entity as EntityTrait>::Column,
+) -> A {
+    let Some(actor) = current_actor() else {
+        return active_model;
+    };
+    if insert {
+        for column in [created_by, updated_by] {
+            if active_model.get(column).is_not_set() {
+                active_model.set(column, actor.into());
+            }
+        }
+    } else if active_model.is_changed() {
+        active_model.set(updated_by, actor.into());
+    }
+    active_model
+}
+
+/// Fills in the names of the users in the audit fields with one query.
+pub async fn load_actor_names<'a, C: ConnectionTrait>(
+    database: &C,
+    audits: impl IntoIterator<Item = &'a mut AuditFieldsSerializer>,
+) -> Result<(), DbErr> {
+    let mut audits: Vec<&mut AuditFieldsSerializer> = audits.into_iter().collect();
+    let mut ids: Vec<i64> = audits
+        .iter()
+        .flat_map(|audit| [audit.created_by, audit.updated_by])
+        .flatten()
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    // Deleted users still made the change, so they are looked up too
+    let names: HashMap<i64, String> = UserEntity::find()
+        .filter(UserColumn::UserId.is_in(ids))
+        .all(database)
+        .await?
+        .into_iter()
+        .map(|user| (user.user_id, user.name))
+        .collect();
+    for audit in audits.iter_mut() {
+        audit.created_by_name = audit.created_by.and_then(|id| names.get(&id).cloned());
+        audit.updated_by_name = audit.updated_by.and_then(|id| names.get(&id).cloned());
+    }
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use std::time::Duration;
+
+/// Exponential backoff after the `attempts`-th failure: `base`, `2 * base`, `4 * base`, ...
+/// capped at `max`.
+pub fn retry_delay(attempts: u32, base: Duration, max: Duration) -> Duration {
+    let factor: u32 = 1_u32
+        .checked_shl(attempts.saturating_sub(1))
+        .unwrap_or(u32::MAX);
+    base.checked_mul(factor).unwrap_or(max).min(max)
+}
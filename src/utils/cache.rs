@@ -0,0 +1,231 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, StatusCode},
+};
+use serde::Serialize;
+
+/// What a cached response was built from. Writes invalidate every response carrying
+/// the tag of what they changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheTag {
+    Retreats,
+    Categories,
+    Galleries,
+    Reviews,
+    Staff,
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+pub enum Lookup {
+    Hit(CachedResponse),
+    /// Pass the generation back to `insert`, so a response read before an invalidation
+    /// is not stored after it.
+    Miss(u64),
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub stores: u64,
+    pub evictions: u64,
+    pub invalidations: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    response: CachedResponse,
+    tags: Vec<CacheTag>,
+    expires_at: Instant,
+    size: usize,
+    sequence: u64,
+}
+
+#[derive(Debug, Default)]
+struct Store {
+    entries: HashMap<String, Entry>,
+    /// Insertion order for eviction, entries replaced or invalidated since are skipped.
+    order: VecDeque<(String, u64)>,
+    bytes: usize,
+    sequence: u64,
+    generation: u64,
+}
+
+impl Store {
+    fn remove(&mut self, key: &str) -> bool {
+        match self.entries.remove(key) {
+            Some(entry) => {
+                self.bytes -= entry.size;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stores: AtomicU64,
+    evictions: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+/// In-memory cache of whole responses, bounded by entry count and total body size.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    store: Arc<Mutex<Store>>,
+    metrics: Arc<Metrics>,
+    ttl: Duration,
+    max_entries: usize,
+    max_bytes: usize,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration, max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(Store::default())),
+            metrics: Arc::new(Metrics::default()),
+            ttl,
+            max_entries,
+            max_bytes,
+        }
+    }
+
+    fn store(&self) -> std::sync::MutexGuard<'_, Store> {
+        // The store holds no invariants a panicking holder could break halfway
+        self.store
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn lookup(&self, key: &str) -> Lookup {
+        let mut store = self.store();
+        match store.entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                self.metrics.hits.fetch_add(1, Ordering::Relaxed);
+                Lookup::Hit(entry.response.clone())
+            }
+            expired => {
+                if expired.is_some() {
+                    store.remove(key);
+                }
+                self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+                Lookup::Miss(store.generation)
+            }
+        }
+    }
+
+    pub fn insert(
+        &self,
+        key: String,
+        tags: Vec<CacheTag>,
+        response: CachedResponse,
+        generation: u64,
+    ) {
+        let size: usize = key.len() + response.body.len();
+        if size > self.max_bytes {
+            return;
+        }
+        let mut store = self.store();
+        if store.generation != generation {
+            return;
+        }
+
+        store.remove(&key);
+        while store.entries.len() >= self.max_entries || store.bytes + size > self.max_bytes {
+            let Some((oldest, sequence)) = store.order.pop_front() else {
+                break;
+            };
+            if store
+                .entries
+                .get(&oldest)
+                .is_some_and(|entry| entry.sequence == sequence)
+            {
+                store.remove(&oldest);
+                self.metrics.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        store.sequence += 1;
+        let sequence: u64 = store.sequence;
+        store.order.push_back((key.clone(), sequence));
+        store.bytes += size;
+        store.entries.insert(
+            key,
+            Entry {
+                response,
+                tags,
+                expires_at: Instant::now() + self.ttl,
+                size,
+                sequence,
+            },
+        );
+        // Drop the order of entries that are long gone, so it cannot outgrow the cache
+        if store.order.len() > self.max_entries * 2 {
+            let Store { entries, order, .. } = &mut *store;
+            order.retain(|(key, sequence)| {
+                entries
+                    .get(key)
+                    .is_some_and(|entry| entry.sequence == *sequence)
+            });
+        }
+        self.metrics.stores.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Call once the change to `tag` has committed.
+    pub fn invalidate(&self, tag: CacheTag) {
+        let mut store = self.store();
+        store.generation += 1;
+        let keys: Vec<String> = store
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.tags.contains(&tag))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            store.remove(&key);
+        }
+        self.metrics.invalidations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        let mut store = self.store();
+        let generation: u64 = store.generation + 1;
+        *store = Store {
+            generation,
+            ..Default::default()
+        };
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let store = self.store();
+        CacheStats {
+            entries: store.entries.len(),
+            bytes: store.bytes,
+            hits: self.metrics.hits.load(Ordering::Relaxed),
+            misses: self.metrics.misses.load(Ordering::Relaxed),
+            stores: self.metrics.stores.load(Ordering::Relaxed),
+            evictions: self.metrics.evictions.load(Ordering::Relaxed),
+            invalidations: self.metrics.invalidations.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+
+/// Source of the current time. The system clock unless fixed, so tests can stamp rows and
+/// age them (retention, backoff, expiry) without waiting.
+#[derive(Clone, Debug, Default)]
+pub struct Clock {
+    fixed: Option<Arc<Mutex<DateTime<Utc>>>>,
+}
+
+impl Clock {
+    pub fn system() -> Self {
+        Self::default()
+    }
+
+    /// A clock standing still at `at` until advanced.
+    pub fn fixed(at: DateTime<Utc>) -> Self {
+        Self {
+            fixed: Some(Arc::new(Mutex::new(at))),
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        match &self.fixed {
+            Some(at) => *at.lock().unwrap(),
+            None => Utc::now(),
+        }
+    }
+
+    /// `now` in the offset the timestamp columns are mapped to.
+    pub fn now_fixed(&self) -> DateTime<FixedOffset> {
+        self.now().fixed_offset()
+    }
+
+    /// Moves a fixed clock forward, a system clock moves on its own.
+    pub fn advance(&self, by: Duration) {
+        if let Some(at) = &self.fixed {
+            let mut at = at.lock().unwrap();
+            *at += by;
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    prelude::Decimal,
+};
+use serde::Deserialize;
+
+use crate::{
+    config::Config,
+    entities_helper::{
+        ExchangeRateColumn, ExchangeRateEntity, ExchangeRateModel, RetreatColumn, RetreatModel,
+    },
+};
+
+/// Three uppercase letters, the shape of an ISO 4217 code.
+pub fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// The exchange rates in effect on a day, each the units of a currency one unit of the
+/// base currency buys.
+#[derive(Debug, Clone, Default)]
+pub struct Rates {
+    rates: HashMap<String, Decimal>,
+}
+
+impl Rates {
+    pub async fn load<C: ConnectionTrait>(
+        database: &C,
+        config: &Config,
+        on: NaiveDate,
+    ) -> Result<Self, DbErr> {
+        let instances: Vec<ExchangeRateModel> = ExchangeRateEntity::find()
+            .filter(ExchangeRateColumn::EffectiveFrom.lte(on))
+            .order_by_asc(ExchangeRateColumn::EffectiveFrom)
+            .all(database)
+            .await?;
+
+        // Later rates of a currency replace the earlier ones
+        let mut rates: HashMap<String, Decimal> = instances
+            .into_iter()
+            .map(|instance| (instance.currency, instance.rate))
+            .collect();
+        rates.insert(config.base_currency.clone(), Decimal::ONE);
+        Ok(Rates { rates })
+    }
+
+    pub fn knows(&self, currency: &str) -> bool {
+        self.rates.contains_key(currency)
+    }
+
+    /// `amount` of `from` in `to`, unrounded. `None` without a rate for either.
+    fn exchange(&self, amount: Decimal, from: &str, to: &str) -> Option<Decimal> {
+        if from == to {
+            return Some(amount);
+        }
+        amount
+            .checked_div(*self.rates.get(from)?)?
+            .checked_mul(*self.rates.get(to)?)
+    }
+
+    /// `amount` of `from` in `to`, rounded to cents for display.
+    pub fn convert(&self, amount: Decimal, from: &str, to: &str) -> Option<Decimal> {
+        self.exchange(amount, from, to)
+            .map(|amount| amount.round_dp(2))
+    }
+}
+
+/// The currency to show budgets in, from `?currency=`. Without it every retreat keeps
+/// its own.
+#[derive(Debug, Clone, Default)]
+pub struct Currency {
+    pub code: Option<String>,
+    /// Loaded only when a currency was asked for.
+    pub rates: Rates,
+}
+
+impl Currency {
+    pub fn is_default(&self) -> bool {
+        self.code.is_none()
+    }
+
+    /// Budgets in a currency without a rate are left as they are, `currency` still names it.
+    pub fn convert_retreats<'a>(&self, instances: impl IntoIterator<Item = &'a mut RetreatModel>) {
+        let Some(code) = &self.code else {
+            return;
+        };
+        for instance in instances {
+            if !self.rates.knows(&instance.currency) {
+                continue;
+            }
+            let convert = |amount: Decimal| self.rates.convert(amount, &instance.currency, code);
+            instance.budget_min = instance.budget_min.and_then(convert);
+            instance.budget_max = instance.budget_max.and_then(convert);
+            instance.currency = code.clone();
+        }
+    }
+}
+
+/// `?budget_min=` and `?budget_max=`, in the `?currency=` if given, else the base currency.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BudgetQuery {
+    pub budget_min: Option<Decimal>,
+    pub budget_max: Option<Decimal>,
+}
+
+impl BudgetQuery {
+    pub fn is_default(&self) -> bool {
+        self.budget_min.is_none() && self.budget_max.is_none()
+    }
+}
+
+/// Retreats whose budget range reaches into the asked one, compared in each retreat's own
+/// currency at the rates in effect `on`, unless `currency` came with its own. Retreats
+/// without a budget, or in a currency without a rate, never match.
+pub async fn budget_condition<C: ConnectionTrait>(
+    database: &C,
+    config: &Config,
+    currency: &Currency,
+    query: &BudgetQuery,
+    on: NaiveDate,
+) -> Result<Option<Condition>, DbErr> {
+    if query.is_default() {
+        return Ok(None);
+    }
+    let rates: Rates = match currency.code {
+        Some(_) => currency.rates.clone(),
+        None => Rates::load(database, config, on).await?,
+    };
+    let asked: &str = currency.code.as_deref().unwrap_or(&config.base_currency);
+
+    // An empty `IN ()` matches nothing, should no currency be comparable
+    let mut condition: Condition =
+        Condition::any().add(RetreatColumn::RetreatId.is_in(Vec::<i64>::new()));
+    for code in rates.rates.keys() {
+        let mut matches: Condition = Condition::all().add(RetreatColumn::Currency.eq(code));
+        if let Some(min) = query.budget_min {
+            let Some(min) = rates.exchange(min, asked, code) else {
+                continue;
+            };
+            matches = matches.add(
+                Condition::any().add(RetreatColumn::BudgetMax.gte(min)).add(
+                    Condition::all()
+                        .add(RetreatColumn::BudgetMax.is_null())
+                        .add(RetreatColumn::BudgetMin.gte(min)),
+                ),
+            );
+        }
+        if let Some(max) = query.budget_max {
+            let Some(max) = rates.exchange(max, asked, code) else {
+                continue;
+            };
+            matches = matches.add(
+                Condition::any().add(RetreatColumn::BudgetMin.lte(max)).add(
+                    Condition::all()
+                        .add(RetreatColumn::BudgetMin.is_null())
+                        .add(RetreatColumn::BudgetMax.lte(max)),
+                ),
+            );
+        }
+        condition = condition.add(matches);
+    }
+    Ok(Some(condition))
+}
@@ -0,0 +1,269 @@
+//! Connection pools built from the config, and which of them a statement goes to.
+//!
+//! Writes and transactions always go to the primary. Plain `SELECT`s go to the read replica,
+//! when one is configured, but only while serving a `GET` or `HEAD` request marked by
+//! `route_reads`, and not for a caller who wrote within the last
+//! `DATABASE_REPLICA_STICKINESS_IN_SEC`, so they read their own writes back.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{Method, Response, header},
+    middleware::Next,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    AccessMode, ConnectOptions, ConnectionTrait, Database, DatabaseConnection,
+    DatabaseTransaction, DbBackend, DbErr, ExecResult, IsolationLevel, QueryResult, Statement,
+    TransactionError, TransactionTrait,
+};
+
+use crate::{
+    config::Config,
+    state::AppState,
+    utils::{clock::Clock, middlewares::idempotency::hex_digest},
+};
+
+tokio::task_local! {
+    /// Set while serving a request whose reads may come from the replica.
+    static READ_FROM_REPLICA: bool;
+}
+
+/// Pool options from the `DATABASE_*` keys. A SQLite pool keeps a single connection,
+/// as SQLite lets one writer in at a time anyway.
+pub fn connect_options(config: &Config, url: &str) -> ConnectOptions {
+    let mut options: ConnectOptions = ConnectOptions::new(url);
+    if !url.starts_with("sqlite:") {
+        options
+            .max_connections(config.database_max_connections)
+            .min_connections(config.database_min_connections);
+    }
+    options
+        .connect_timeout(Duration::from_secs(config.database_connect_timeout_in_sec))
+        .acquire_timeout(Duration::from_secs(config.database_acquire_timeout_in_sec))
+        .idle_timeout(Duration::from_secs(config.database_idle_timeout_in_sec))
+        .max_lifetime(Duration::from_secs(config.database_max_lifetime_in_sec))
+        .sqlx_logging(config.database_log_statements || config.database_slow_statement_in_ms > 0)
+        .sqlx_logging_level(if config.database_log_statements {
+            log::LevelFilter::Info
+        } else {
+            log::LevelFilter::Off
+        });
+    if config.database_slow_statement_in_ms > 0 {
+        options.sqlx_slow_statements_logging_settings(
+            log::LevelFilter::Warn,
+            Duration::from_millis(config.database_slow_statement_in_ms),
+        );
+    }
+    options
+}
+
+pub async fn connect(config: &Config, url: &str) -> Result<DatabaseConnection, DbErr> {
+    Database::connect(connect_options(config, url)).await
+}
+
+/// Prints the statements `connect_options` logs to stderr. Nothing else in the app logs
+/// through `tracing`, so this is only installed when statement logging is on.
+pub fn init_statement_logging(config: &Config) {
+    if !(config.database_log_statements || config.database_slow_statement_in_ms > 0) {
+        return;
+    }
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::new("sqlx::query=info"))
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+/// The primary connection, and the replica reads may be routed to. Shared behind `Arc`s,
+/// as a `DatabaseConnection` cannot be cloned with SeaORM's `mock` feature on.
+#[derive(Clone, Debug)]
+pub struct ReplicatedDatabase {
+    primary: Arc<DatabaseConnection>,
+    replica: Option<Arc<DatabaseConnection>>,
+}
+
+impl ReplicatedDatabase {
+    pub fn new(primary: DatabaseConnection, replica: Option<DatabaseConnection>) -> Self {
+        Self {
+            primary: Arc::new(primary),
+            replica: replica.map(Arc::new),
+        }
+    }
+
+    pub fn primary(&self) -> &DatabaseConnection {
+        &self.primary
+    }
+
+    pub fn replica(&self) -> Option<&DatabaseConnection> {
+        self.replica.as_deref()
+    }
+
+    pub async fn close(&self) -> Result<(), DbErr> {
+        if let Some(replica) = &self.replica {
+            replica.close_by_ref().await?;
+        }
+        self.primary.close_by_ref().await
+    }
+
+    /// The replica for a `SELECT` inside `route_reads`, the primary for anything else,
+    /// including `INSERT ... RETURNING`, which also comes through `query_one`.
+    fn reader(&self, stmt: &Statement) -> &DatabaseConnection {
+        let Some(replica) = self.replica.as_deref() else {
+            return &self.primary;
+        };
+        let is_select: bool = stmt
+            .sql
+            .trim_start()
+            .get(..6)
+            .is_some_and(|verb| verb.eq_ignore_ascii_case("SELECT"));
+        if is_select && READ_FROM_REPLICA.try_with(|read| *read).unwrap_or(false) {
+            replica
+        } else {
+            &self.primary
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ConnectionTrait for ReplicatedDatabase {
+    fn get_database_backend(&self) -> DbBackend {
+        self.primary.get_database_backend()
+    }
+
+    async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        self.primary.execute(stmt).await
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        self.primary.execute_unprepared(sql).await
+    }
+
+    async fn query_one(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        self.reader(&stmt).query_one(stmt).await
+    }
+
+    async fn query_all(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        self.reader(&stmt).query_all(stmt).await
+    }
+
+    fn support_returning(&self) -> bool {
+        self.primary.support_returning()
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.primary.is_mock_connection()
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionTrait for ReplicatedDatabase {
+    async fn begin(&self) -> Result<DatabaseTransaction, DbErr> {
+        self.primary.begin().await
+    }
+
+    async fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<DatabaseTransaction, DbErr> {
+        self.primary
+            .begin_with_config(isolation_level, access_mode)
+            .await
+    }
+
+    async fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        self.primary.transaction(callback).await
+    }
+
+    async fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        self.primary
+            .transaction_with_config(callback, isolation_level, access_mode)
+            .await
+    }
+}
+
+/// Callers who wrote recently, by a digest of their `Authorization` header, with the
+/// time until which their reads stay on the primary.
+#[derive(Clone, Debug, Default)]
+pub struct RecentWriters {
+    until: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
+}
+
+impl RecentWriters {
+    pub fn mark(&self, caller: String, until: DateTime<Utc>, now: DateTime<Utc>) {
+        let mut writers = self.until.lock().unwrap();
+        writers.retain(|_, until| *until > now);
+        writers.insert(caller, until);
+    }
+
+    pub fn is_sticky(&self, caller: &str, now: DateTime<Utc>) -> bool {
+        self.until
+            .lock()
+            .unwrap()
+            .get(caller)
+            .is_some_and(|until| *until > now)
+    }
+}
+
+/// Lets the reads of `GET` and `HEAD` requests go to the replica, and keeps a caller's
+/// reads on the primary for a while after they changed something.
+pub async fn route_reads(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response<Body> {
+    if state.database.replica().is_none() {
+        return next.run(request).await;
+    }
+    let caller: Option<String> = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .map(|value| hex_digest(value.as_bytes()));
+    let clock: &Clock = &state.clock;
+
+    if matches!(*request.method(), Method::GET | Method::HEAD) {
+        let sticky: bool = caller
+            .as_deref()
+            .is_some_and(|caller| state.recent_writers.is_sticky(caller, clock.now()));
+        return READ_FROM_REPLICA.scope(!sticky, next.run(request)).await;
+    }
+
+    let response: Response<Body> = next.run(request).await;
+    if let Some(caller) = caller
+        && response.status().is_success()
+    {
+        let now: DateTime<Utc> = clock.now();
+        let window = chrono::Duration::seconds(state.config.database_replica_stickiness_in_sec as i64);
+        state.recent_writers.mark(caller, now + window, now);
+    }
+    response
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    http::{Response, StatusCode},
+};
+use sea_orm::{
+    ColumnTrait, DbErr, LoaderTrait, QueryFilter, QuerySelect,
+    sea_query::{Expr, Func},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+    entities_helper::{
+        CategoryEntity, RetreatEntity, RetreatGalleriesEntity, RetreatModel, RetreatReviewColumn,
+        RetreatReviewEntity, RetreatReviewModel, RetreatUserEntity, UserColumn, UserEntity,
+        WishlistModel,
+    },
+    serializers::{
+        audit::AuditFieldsSerializer,
+        categories::ReadCategorySerializer,
+        retreat_galleries::ReadRetreatGallerySerializer,
+        retreat_reviews::ReadRetreatReviewSerializer,
+        retreats::{
+            ReadRetreatSerializer, ReadRetreatStaffSerializer, RetreatReviewSummarySerializer,
+        },
+        users::PublicUserSerializer,
+        wishlists::ReadWishlistSerializer,
+    },
+    utils::{
+        actor::load_actor_names,
+        currency::Currency,
+        database::ReplicatedDatabase,
+        i18n::{Language, translate_categories, translate_galleries, translate_retreats},
+        response::{to_error_response, to_error_response_with_message},
+        soft_delete::SoftDelete,
+    },
+};
+
+pub const RETREAT_INCLUDES: &[&str] = &["category", "galleries", "reviews_summary", "staff"];
+pub const REVIEW_INCLUDES: &[&str] = &["user", "retreat"];
+pub const WISHLIST_INCLUDES: &[&str] = &["retreat"];
+
+/// `?include=a,b` embeds related resources, `?fields=x,y` trims the resource itself.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EmbedQuery {
+    pub include: Option<String>,
+    pub fields: Option<String>,
+}
+
+impl EmbedQuery {
+    /// Whether the default representation was asked for, so model ETags still apply.
+    pub fn is_default(&self) -> bool {
+        self.include.is_none() && self.fields.is_none()
+    }
+
+    fn includes(&self, allowed: &[&str]) -> Result<Vec<String>, String> {
+        let includes: Vec<String> = split_list(self.include.as_deref());
+        if let Some(unknown) = includes
+            .iter()
+            .find(|name| !allowed.contains(&name.as_str()))
+        {
+            return Err(format!(
+                "Unknown include `{unknown}`, expected one of: {}.",
+                allowed.join(", ")
+            ));
+        }
+        Ok(includes)
+    }
+
+    fn fields(&self) -> Option<Vec<String>> {
+        self.fields
+            .as_deref()
+            .map(|fields| split_list(Some(fields)))
+    }
+}
+
+fn split_list(value: Option<&str>) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    for item in value.unwrap_or("").split(',').map(str::trim) {
+        if !item.is_empty() && !items.iter().any(|existing| existing == item) {
+            items.push(item.to_string());
+        }
+    }
+    items
+}
+
+fn to_object<T: Serialize>(value: T) -> Map<String, JsonValue> {
+    match serde_json::to_value(value) {
+        Ok(JsonValue::Object(object)) => object,
+        _ => Map::new(),
+    }
+}
+
+fn to_json<T: Serialize>(value: T) -> JsonValue {
+    serde_json::to_value(value).unwrap_or(JsonValue::Null)
+}
+
+fn database_error(e: DbErr) -> Response<Body> {
+    to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn bad_request(message: String) -> Response<Body> {
+    to_error_response_with_message(&message, StatusCode::BAD_REQUEST)
+}
+
+/// Keeps only the requested fields, included relations are always kept.
+fn select_fields(
+    objects: Vec<Map<String, JsonValue>>,
+    fields: Option<Vec<String>>,
+    includes: &[String],
+) -> Result<Vec<JsonValue>, String> {
+    let Some(fields) = fields else {
+        return Ok(objects.into_iter().map(JsonValue::Object).collect());
+    };
+    let unknown = objects
+        .first()
+        .and_then(|first| fields.iter().find(|field| !first.contains_key(*field)));
+    if let Some(unknown) = unknown {
+        return Err(format!("Unknown field `{unknown}`."));
+    }
+    Ok(objects
+        .into_iter()
+        .map(|mut object| {
+            object.retain(|key, _| fields.contains(key) || includes.contains(key));
+            JsonValue::Object(object)
+        })
+        .collect())
+}
+
+pub async fn embed_retreats(
+    database: &ReplicatedDatabase,
+    instances: Vec<RetreatModel>,
+    query: &EmbedQuery,
+    language: &Language,
+) -> Result<Vec<JsonValue>, Response<Body>> {
+    let includes: Vec<String> = query.includes(RETREAT_INCLUDES).map_err(bad_request)?;
+    let mut serializers: Vec<ReadRetreatSerializer> =
+        instances.iter().cloned().map(ReadRetreatSerializer::from).collect();
+    load_actor_names(database, serializers.iter_mut().map(|s| &mut s.audit))
+        .await
+        .map_err(database_error)?;
+    let mut objects: Vec<Map<String, JsonValue>> =
+        serializers.into_iter().map(to_object).collect();
+
+    // One query per relation, however many retreats are in the page.
+    for include in &includes {
+        match include.as_str() {
+            "category" => {
+                let mut categories = instances
+                    .load_one(CategoryEntity, database)
+                    .await
+                    .map_err(database_error)?;
+                translate_categories(database, categories.iter_mut().flatten(), language)
+                    .await
+                    .map_err(database_error)?;
+                let mut categories: Vec<Option<ReadCategorySerializer>> = categories
+                    .into_iter()
+                    .map(|category| category.map(ReadCategorySerializer::from))
+                    .collect();
+                load_actor_names(
+                    database,
+                    categories.iter_mut().flatten().map(|s| &mut s.audit),
+                )
+                .await
+                .map_err(database_error)?;
+                for (object, category) in objects.iter_mut().zip(categories) {
+                    object.insert(include.clone(), to_json(category));
+                }
+            }
+            "galleries" => {
+                let mut galleries = instances
+                    .load_many(RetreatGalleriesEntity::find_active(), database)
+                    .await
+                    .map_err(database_error)?;
+                translate_galleries(database, galleries.iter_mut().flatten(), language)
+                    .await
+                    .map_err(database_error)?;
+                let mut galleries: Vec<Vec<ReadRetreatGallerySerializer>> = galleries
+                    .into_iter()
+                    .map(|galleries| galleries.into_iter().map(|model| model.into()).collect())
+                    .collect();
+                load_actor_names(
+                    database,
+                    galleries.iter_mut().flatten().map(|s| &mut s.audit),
+                )
+                .await
+                .map_err(database_error)?;
+                for (object, galleries) in objects.iter_mut().zip(galleries) {
+                    object.insert(include.clone(), to_json(galleries));
+                }
+            }
+            "reviews_summary" => {
+                let retreat_ids: Vec<i64> = instances.iter().map(|m| m.retreat_id).collect();
+                let summaries: HashMap<i64, (i64, Option<f64>)> =
+                    RetreatReviewEntity::find_active()
+                        .select_only()
+                        .column(RetreatReviewColumn::RetreatId)
+                        .column_as(
+                            Expr::expr(Func::count(Expr::col(RetreatReviewColumn::ReviewId))),
+                            "count",
+                        )
+                        .column_as(
+                            Expr::expr(Func::avg(Expr::col(RetreatReviewColumn::Rating))),
+                            "average_rating",
+                        )
+                        .filter(RetreatReviewColumn::RetreatId.is_in(retreat_ids))
+                        .group_by(RetreatReviewColumn::RetreatId)
+                        .into_tuple::<(i64, i64, Option<f64>)>()
+                        .all(database)
+                        .await
+                        .map_err(database_error)?
+                        .into_iter()
+                        .map(|(retreat_id, count, average)| (retreat_id, (count, average)))
+                        .collect();
+                for (object, instance) in objects.iter_mut().zip(&instances) {
+                    let (count, average_rating) = summaries
+                        .get(&instance.retreat_id)
+                        .copied()
+                        .unwrap_or((0, None));
+                    let summary = RetreatReviewSummarySerializer {
+                        count,
+                        average_rating,
+                    };
+                    object.insert(include.clone(), to_json(summary));
+                }
+            }
+            "staff" => {
+                let staff = instances
+                    .load_many(RetreatUserEntity, database)
+                    .await
+                    .map_err(database_error)?;
+                // `retreat_users` points at users through several columns, so there is
+                // no single `Related` impl to load them with.
+                let user_ids: Vec<i64> = staff.iter().flatten().map(|m| m.user_id).collect();
+                let names: HashMap<i64, String> = UserEntity::find_active()
+                    .filter(UserColumn::UserId.is_in(user_ids))
+                    .all(database)
+                    .await
+                    .map_err(database_error)?
+                    .into_iter()
+                    .map(|user| (user.user_id, user.name))
+                    .collect();
+                let mut staff: Vec<Vec<ReadRetreatStaffSerializer>> = staff
+                    .into_iter()
+                    .map(|staff| {
+                        staff
+                            .into_iter()
+                            .map(|member| ReadRetreatStaffSerializer {
+                                retreat_user_id: member.retreat_user_id,
+                                user_id: member.user_id,
+                                name: names.get(&member.user_id).cloned(),
+                                is_owner: member.is_owner,
+                                role: member.role,
+                                audit: AuditFieldsSerializer::new(
+                                    member.created_by,
+                                    member.updated_by,
+                                ),
+                            })
+                            .collect()
+                    })
+                    .collect();
+                load_actor_names(database, staff.iter_mut().flatten().map(|s| &mut s.audit))
+                    .await
+                    .map_err(database_error)?;
+                for (object, staff) in objects.iter_mut().zip(staff) {
+                    object.insert(include.clone(), to_json(staff));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    select_fields(objects, query.fields(), &includes).map_err(bad_request)
+}
+
+pub async fn embed_reviews(
+    database: &ReplicatedDatabase,
+    instances: Vec<RetreatReviewModel>,
+    query: &EmbedQuery,
+    language: &Language,
+    currency: &Currency,
+) -> Result<Vec<JsonValue>, Response<Body>> {
+    let includes: Vec<String> = query.includes(REVIEW_INCLUDES).map_err(bad_request)?;
+    let mut objects: Vec<Map<String, JsonValue>> = instances
+        .iter()
+        .cloned()
+        .map(|model| to_object(ReadRetreatReviewSerializer::from(model)))
+        .collect();
+
+    for include in &includes {
+        match include.as_str() {
+            "user" => {
+                let users = instances
+                    .load_one(UserEntity::find_active(), database)
+                    .await
+                    .map_err(database_error)?;
+                for (object, user) in objects.iter_mut().zip(users) {
+                    let user = user.map(PublicUserSerializer::from);
+                    object.insert(include.clone(), to_json(user));
+                }
+            }
+            "retreat" => {
+                let mut retreats = instances
+                    .load_one(RetreatEntity::find_active(), database)
+                    .await
+                    .map_err(database_error)?;
+                translate_retreats(database, retreats.iter_mut().flatten(), language)
+                    .await
+                    .map_err(database_error)?;
+                currency.convert_retreats(retreats.iter_mut().flatten());
+                for (object, retreat) in objects.iter_mut().zip(retreats) {
+                    let retreat = retreat.map(ReadRetreatSerializer::from);
+                    object.insert(include.clone(), to_json(retreat));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    select_fields(objects, query.fields(), &includes).map_err(bad_request)
+}
+
+pub async fn embed_wishlists(
+    database: &ReplicatedDatabase,
+    instances: Vec<WishlistModel>,
+    query: &EmbedQuery,
+    language: &Language,
+    currency: &Currency,
+) -> Result<Vec<JsonValue>, Response<Body>> {
+    let includes: Vec<String> = query.includes(WISHLIST_INCLUDES).map_err(bad_request)?;
+    let mut objects: Vec<Map<String, JsonValue>> = instances
+        .iter()
+        .cloned()
+        .map(|model| to_object(ReadWishlistSerializer::from(model)))
+        .collect();
+
+    if includes.iter().any(|include| include == "retreat") {
+        let mut retreats = instances
+            .load_one(RetreatEntity::find_active(), database)
+            .await
+            .map_err(database_error)?;
+        translate_retreats(database, retreats.iter_mut().flatten(), language)
+            .await
+            .map_err(database_error)?;
+        currency.convert_retreats(retreats.iter_mut().flatten());
+        for (object, retreat) in objects.iter_mut().zip(retreats) {
+            let retreat = retreat.map(ReadRetreatSerializer::from);
+            object.insert("retreat".to_string(), to_json(retreat));
+        }
+    }
+
+    select_fields(objects, query.fields(), &includes).map_err(bad_request)
+}
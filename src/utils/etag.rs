@@ -0,0 +1,205 @@
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, Response, StatusCode, header},
+};
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbBackend, DbErr,
+    EntityTrait, IntoActiveModel, QueryFilter, Value,
+    sea_query::{Expr, Func, SimpleExpr},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    entities_helper::{
+        CategoryModel, RetreatGalleriesModel, RetreatModel, RetreatReviewModel, UserModel,
+    },
+    utils::response::to_error_response_with_message,
+};
+
+/// Models whose representation changes whenever `updated_at` does.
+pub trait ETag {
+    fn etag(&self) -> String;
+}
+
+fn versioned_etag(id: i64, updated_at_micros: i64) -> String {
+    format!("\"{id}-{updated_at_micros}\"")
+}
+
+impl ETag for RetreatModel {
+    fn etag(&self) -> String {
+        versioned_etag(self.retreat_id, self.updated_at.timestamp_micros())
+    }
+}
+
+impl ETag for CategoryModel {
+    fn etag(&self) -> String {
+        versioned_etag(self.category_id, self.updated_at.timestamp_micros())
+    }
+}
+
+impl ETag for UserModel {
+    fn etag(&self) -> String {
+        versioned_etag(self.user_id, self.updated_at.and_utc().timestamp_micros())
+    }
+}
+
+impl ETag for RetreatGalleriesModel {
+    fn etag(&self) -> String {
+        versioned_etag(self.gallery_id, self.updated_at.timestamp_micros())
+    }
+}
+
+impl ETag for RetreatReviewModel {
+    fn etag(&self) -> String {
+        versioned_etag(self.review_id, self.updated_at.timestamp_micros())
+    }
+}
+
+/// ETag of a list, changes when any member is added, removed or updated.
+pub fn collection_etag<T: ETag>(instances: &[T]) -> String {
+    let mut hasher = Sha256::new();
+    for instance in instances {
+        hasher.update(instance.etag().as_bytes());
+    }
+    digest_etag(hasher)
+}
+
+/// ETag of a representation that depends on more than the model, e.g. embedded relations.
+pub fn content_etag<T: Serialize>(value: &T) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(value).unwrap_or_default());
+    digest_etag(hasher)
+}
+
+fn digest_etag(hasher: Sha256) -> String {
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .take(16)
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("\"{digest}\"")
+}
+
+fn header_etags(headers: &HeaderMap, name: header::HeaderName) -> Vec<&str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// `If-None-Match` uses weak comparison, so `W/` prefixes are ignored.
+pub fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    header_etags(headers, header::IF_NONE_MATCH)
+        .iter()
+        .any(|candidate| *candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// `If-Match` uses strong comparison. A missing header always passes.
+// Same error type as the handlers, so it can be used with `?`.
+#[allow(clippy::result_large_err)]
+pub fn check_if_match(headers: &HeaderMap, etag: &str) -> Result<(), Response<Body>> {
+    let candidates = header_etags(headers, header::IF_MATCH);
+    if candidates.is_empty() || candidates.iter().any(|c| *c == "*" || *c == etag) {
+        return Ok(());
+    }
+    Err(precondition_failed())
+}
+
+pub fn precondition_failed() -> Response<Body> {
+    to_error_response_with_message(
+        "Resource has been modified, fetch it again before updating.",
+        StatusCode::PRECONDITION_FAILED,
+    )
+}
+
+pub fn not_modified(etag: &str) -> Response<Body> {
+    let mut response = Response::builder()
+        .status(StatusCode::NOT_MODIFIED)
+        .body(Body::empty())
+        .unwrap();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
+pub fn with_etag(mut response: Response<Body>, etag: &str) -> Response<Body> {
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
+/// `column = seen`, comparing instants rather than text on SQLite, where column defaults,
+/// the `updated_at` trigger and bound parameters each spell timestamps differently.
+fn still_holds<C: ColumnTrait>(
+    backend: DbBackend,
+    column: C,
+    seen: impl Into<Value>,
+) -> SimpleExpr {
+    match backend {
+        DbBackend::Sqlite => {
+            let instant = |expr: SimpleExpr| -> SimpleExpr {
+                Func::cust("strftime")
+                    .arg("%Y-%m-%d %H:%M:%f")
+                    .arg(expr)
+                    .into()
+            };
+            Expr::expr(instant(column.into_expr().into())).eq(instant(Expr::val(seen).into()))
+        }
+        _ => column.eq(seen),
+    }
+}
+
+/// Updates the row only if `updated_at` still holds the value the caller read, failing
+/// with `DbErr::RecordNotUpdated` if a concurrent writer got there first.
+pub async fn save_unmodified<A, C, V>(
+    db: &C,
+    active_model: A,
+    updated_at: <A::Entity as EntityTrait>::Column,
+    seen: V,
+) -> Result<<A::Entity as EntityTrait>::Model, DbErr>
+where
+    A: ActiveModelTrait + ActiveModelBehavior + Send,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+    C: ConnectionTrait,
+    V: Into<Value>,
+{
+    let active_model = active_model.before_save(db, false).await?;
+    let model = A::Entity::update(active_model)
+        .filter(still_holds(db.get_database_backend(), updated_at, seen))
+        .exec(db)
+        .await?;
+    A::after_save(model, db, false).await
+}
+
+/// Deletes the row only if `updated_at` still holds the value the caller read, failing
+/// with `DbErr::RecordNotUpdated` otherwise.
+pub async fn delete_unmodified<A, C, V>(
+    db: &C,
+    active_model: A,
+    updated_at: <A::Entity as EntityTrait>::Column,
+    seen: V,
+) -> Result<(), DbErr>
+where
+    A: ActiveModelTrait + ActiveModelBehavior + Send,
+    C: ConnectionTrait,
+    V: Into<Value>,
+{
+    let active_model = active_model.before_delete(db).await?;
+    let deleted = A::Entity::delete(active_model.clone())
+        .filter(still_holds(db.get_database_backend(), updated_at, seen))
+        .exec(db)
+        .await?;
+    if deleted.rows_affected == 0 {
+        return Err(DbErr::RecordNotUpdated);
+    }
+    active_model.after_delete(db).await?;
+    Ok(())
+}
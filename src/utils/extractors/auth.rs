@@ -4,13 +4,13 @@ use axum::{
     extract::FromRequestParts,
     http::{StatusCode, header, request::Parts},
 };
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::{ColumnTrait, QueryFilter};
 
 use crate::{
     entities_helper::{UserColumn, UserEntity, UserModel},
     serializers::auth::TokenClaim,
     state::AppState,
-    utils::jwt::get_access_token_claim,
+    utils::{jwt::get_access_token_claim, soft_delete::SoftDelete},
 };
 
 #[derive(Clone)]
@@ -36,14 +36,6 @@ where
 
         let (_schema, access_token) = (splitted_auth_header[0], splitted_auth_header[1]);
 
-        let token_claim: TokenClaim = get_access_token_claim(access_token)
-            .await
-            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid Token".to_string()))?;
-
-        let email: String = token_claim.email;
-        let user_id: i64 = token_claim.user_id;
-        let name: String = token_claim.name;
-
         let state: AppState = (state as &dyn Any)
             .downcast_ref::<AppState>()
             .ok_or_else(|| {
@@ -55,7 +47,15 @@ where
             .unwrap()
             .clone();
 
-        let user: UserModel = UserEntity::find()
+        let token_claim: TokenClaim = get_access_token_claim(&state.config, access_token)
+            .await
+            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid Token".to_string()))?;
+
+        let email: String = token_claim.email;
+        let user_id: i64 = token_claim.user_id;
+        let name: String = token_claim.name;
+
+        let user: UserModel = UserEntity::find_active()
             .filter(UserColumn::Email.eq(email))
             .filter(UserColumn::UserId.eq(user_id))
             .filter(UserColumn::Name.eq(name))
@@ -68,6 +68,8 @@ where
     }
 }
 
+// Mostly a guard, not every admin route reads the user
+#[allow(unused)]
 #[derive(Clone)]
 pub struct AuthAdmin(pub UserModel);
 
@@ -91,14 +93,6 @@ where
 
         let (_schema, access_token) = (splitted_auth_header[0], splitted_auth_header[1]);
 
-        let token_claim: TokenClaim = get_access_token_claim(access_token)
-            .await
-            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid Token".to_string()))?;
-
-        let email: String = token_claim.email;
-        let user_id: i64 = token_claim.user_id;
-        let name: String = token_claim.name;
-
         let state: AppState = (state as &dyn Any)
             .downcast_ref::<AppState>()
             .ok_or_else(|| {
@@ -110,7 +104,15 @@ where
             .unwrap()
             .clone();
 
-        let user: UserModel = UserEntity::find()
+        let token_claim: TokenClaim = get_access_token_claim(&state.config, access_token)
+            .await
+            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid Token".to_string()))?;
+
+        let email: String = token_claim.email;
+        let user_id: i64 = token_claim.user_id;
+        let name: String = token_claim.name;
+
+        let user: UserModel = UserEntity::find_active()
             .filter(UserColumn::Email.eq(email))
             .filter(UserColumn::UserId.eq(user_id))
             .filter(UserColumn::Name.eq(name))
@@ -118,6 +120,9 @@ where
             .await
             .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
             .ok_or_else(|| (StatusCode::NOT_FOUND, "User not found".to_string()))?;
+        if !user.is_admin {
+            return Err((StatusCode::FORBIDDEN, "Admin access required".to_string()));
+        }
 
         Ok(AuthAdmin(user))
     }
@@ -0,0 +1,64 @@
+use std::any::Any;
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{StatusCode, request::Parts},
+};
+use serde::Deserialize;
+
+use crate::{
+    state::AppState,
+    utils::currency::{Currency, Rates, is_currency_code},
+};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct CurrencyQuery {
+    currency: Option<String>,
+}
+
+/// Reads `?currency=` and loads the rates in effect today to convert budgets into it.
+impl<S> FromRequestParts<S> for Currency
+where
+    S: Send + Sync + std::fmt::Debug + Clone + 'static,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state: &AppState = (state as &dyn Any)
+            .downcast_ref::<AppState>()
+            .ok_or_else(|| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to type cast app state".to_string(),
+                )
+            })?;
+
+        // A malformed query string is left to the handler's own `Query` extractor
+        let query: CurrencyQuery = Query::try_from_uri(&parts.uri)
+            .map(|Query(query)| query)
+            .unwrap_or_default();
+        let Some(code) = query.currency.map(|code| code.to_ascii_uppercase()) else {
+            return Ok(Currency::default());
+        };
+        if !is_currency_code(&code) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("`{code}` is not a three letter currency code"),
+            ));
+        }
+
+        let rates: Rates = Rates::load(&state.database, &state.config, state.clock.now().date_naive())
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        if !rates.knows(&code) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("No exchange rate for `{code}`"),
+            ));
+        }
+        Ok(Currency {
+            code: Some(code),
+            rates,
+        })
+    }
+}
@@ -0,0 +1,48 @@
+use std::any::Any;
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{StatusCode, header, request::Parts},
+};
+use serde::Deserialize;
+
+use crate::{state::AppState, utils::i18n::Language};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct LanguageQuery {
+    lang: Option<String>,
+}
+
+/// Negotiates the response language, `?lang=` wins over `Accept-Language`.
+impl<S> FromRequestParts<S> for Language
+where
+    S: Send + Sync + std::fmt::Debug + Clone + 'static,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state: &AppState = (state as &dyn Any)
+            .downcast_ref::<AppState>()
+            .ok_or_else(|| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to type cast app state".to_string(),
+                )
+            })?;
+
+        // A malformed query string is left to the handler's own `Query` extractor
+        let query: LanguageQuery = Query::try_from_uri(&parts.uri)
+            .map(|Query(query)| query)
+            .unwrap_or_default();
+        let accept_language: Option<&str> = parts
+            .headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok());
+
+        Ok(Language::negotiate(
+            &state.config,
+            query.lang.as_deref(),
+            accept_language,
+        ))
+    }
+}
@@ -1 +1 @@
-pub mod auth;
\ No newline at end of file
+pub mod auth;pub mod currency;pub mod language;
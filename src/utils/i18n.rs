@@ -0,0 +1,228 @@
+use axum::{
+    body::Body,
+    http::{HeaderValue, Response, header},
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+
+use crate::{
+    config::Config,
+    entities_helper::{
+        CategoryModel, CategoryTranslationColumn, CategoryTranslationEntity,
+        CategoryTranslationModel, GalleryTranslationColumn, GalleryTranslationEntity,
+        GalleryTranslationModel, RetreatGalleriesModel, RetreatModel, RetreatTranslationColumn,
+        RetreatTranslationEntity, RetreatTranslationModel,
+    },
+};
+
+/// The locales to look translations up in, most preferred first. The chain ends before
+/// the default locale, whose content is the source columns themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Language {
+    pub chain: Vec<String>,
+}
+
+impl Language {
+    /// Builds the chain from `?lang=a,b` if given, else from `Accept-Language`. Regional
+    /// tags fall back to their language (`ne-NP` to `ne`), unsupported ones are skipped.
+    pub fn negotiate(config: &Config, lang: Option<&str>, accept_language: Option<&str>) -> Self {
+        let requested: Vec<String> = match lang {
+            Some(lang) => lang
+                .split(',')
+                .map(|tag| tag.trim().to_ascii_lowercase())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            None => parse_accept_language(accept_language.unwrap_or_default()),
+        };
+
+        let mut chain: Vec<String> = Vec::new();
+        for tag in requested {
+            let language: String = tag.split('-').next().unwrap_or_default().to_string();
+            for candidate in [tag, language] {
+                if candidate == config.default_locale {
+                    return Language { chain };
+                }
+                if config.supported_locales.contains(&candidate) && !chain.contains(&candidate) {
+                    chain.push(candidate);
+                }
+            }
+        }
+        Language { chain }
+    }
+
+    /// Whether the source columns are served as they are.
+    pub fn is_default(&self) -> bool {
+        self.chain.is_empty()
+    }
+
+    /// The field from the first translation along the chain that has it.
+    fn pick<T>(
+        &self,
+        translations: &[&T],
+        locale: fn(&T) -> &str,
+        field: fn(&T) -> Option<&String>,
+    ) -> Option<String> {
+        self.chain.iter().find_map(|wanted| {
+            translations
+                .iter()
+                .find(|translation| locale(translation) == wanted)
+                .and_then(|translation| field(translation))
+                .cloned()
+        })
+    }
+}
+
+/// Marks a response as negotiated on `Accept-Language`. Fields fall back one by one, so
+/// `Content-Language` lists every locale along the chain down to the default.
+pub fn with_language(
+    mut response: Response<Body>,
+    language: &Language,
+    config: &Config,
+) -> Response<Body> {
+    let locales: Vec<&str> = language
+        .chain
+        .iter()
+        .map(String::as_str)
+        .chain([config.default_locale.as_str()])
+        .collect();
+    let headers = response.headers_mut();
+    headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+    if let Ok(value) = HeaderValue::from_str(&locales.join(", ")) {
+        headers.insert(header::CONTENT_LANGUAGE, value);
+    }
+    response
+}
+
+/// Tags ordered by their `q` weight, ties keep the client's order.
+fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut weighted: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag: String = parts.next()?.trim().to_ascii_lowercase();
+            let quality: f32 = parts
+                .filter_map(|part| part.trim().strip_prefix("q="))
+                .find_map(|quality| quality.parse().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    weighted.sort_by(|a, b| b.1.total_cmp(&a.1));
+    weighted.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Checks a locale translations can be written in, the default one lives in the source columns.
+pub fn translatable_locale(config: &Config, locale: &str) -> Result<String, String> {
+    let locale: String = locale.to_ascii_lowercase();
+    if locale == config.default_locale {
+        return Err(format!(
+            "`{locale}` is the default locale, change the resource itself instead."
+        ));
+    }
+    if !config.supported_locales.contains(&locale) {
+        return Err(format!(
+            "Unsupported locale `{locale}`, expected one of: {}.",
+            translatable_locales(config).join(", ")
+        ));
+    }
+    Ok(locale)
+}
+
+/// Every supported locale except the default one.
+pub fn translatable_locales(config: &Config) -> Vec<String> {
+    config
+        .supported_locales
+        .iter()
+        .filter(|locale| **locale != config.default_locale)
+        .cloned()
+        .collect()
+}
+
+pub async fn translate_retreats<'a, C: ConnectionTrait>(
+    database: &C,
+    instances: impl IntoIterator<Item = &'a mut RetreatModel>,
+    language: &Language,
+) -> Result<(), DbErr> {
+    if language.is_default() {
+        return Ok(());
+    }
+    let mut instances: Vec<&mut RetreatModel> = instances.into_iter().collect();
+    let ids: Vec<i64> = instances.iter().map(|m| m.retreat_id).collect();
+    let translations: Vec<RetreatTranslationModel> = RetreatTranslationEntity::find()
+        .filter(RetreatTranslationColumn::RetreatId.is_in(ids))
+        .filter(RetreatTranslationColumn::Locale.is_in(language.chain.clone()))
+        .all(database)
+        .await?;
+
+    for instance in instances.iter_mut() {
+        let own: Vec<&RetreatTranslationModel> = translations
+            .iter()
+            .filter(|translation| translation.retreat_id == instance.retreat_id)
+            .collect();
+        if let Some(name) = language.pick(&own, |t| &t.locale, |t| t.name.as_ref()) {
+            instance.name = name;
+        }
+        if let Some(description) = language.pick(&own, |t| &t.locale, |t| t.description.as_ref()) {
+            instance.description = Some(description);
+        }
+    }
+    Ok(())
+}
+
+pub async fn translate_categories<'a, C: ConnectionTrait>(
+    database: &C,
+    instances: impl IntoIterator<Item = &'a mut CategoryModel>,
+    language: &Language,
+) -> Result<(), DbErr> {
+    if language.is_default() {
+        return Ok(());
+    }
+    let mut instances: Vec<&mut CategoryModel> = instances.into_iter().collect();
+    let ids: Vec<i64> = instances.iter().map(|m| m.category_id).collect();
+    let translations: Vec<CategoryTranslationModel> = CategoryTranslationEntity::find()
+        .filter(CategoryTranslationColumn::CategoryId.is_in(ids))
+        .filter(CategoryTranslationColumn::Locale.is_in(language.chain.clone()))
+        .all(database)
+        .await?;
+
+    for instance in instances.iter_mut() {
+        let own: Vec<&CategoryTranslationModel> = translations
+            .iter()
+            .filter(|translation| translation.category_id == instance.category_id)
+            .collect();
+        if let Some(name) = language.pick(&own, |t| &t.locale, |t| t.name.as_ref()) {
+            instance.name = name;
+        }
+        if let Some(description) = language.pick(&own, |t| &t.locale, |t| t.description.as_ref()) {
+            instance.description = Some(description);
+        }
+    }
+    Ok(())
+}
+
+pub async fn translate_galleries<'a, C: ConnectionTrait>(
+    database: &C,
+    instances: impl IntoIterator<Item = &'a mut RetreatGalleriesModel>,
+    language: &Language,
+) -> Result<(), DbErr> {
+    if language.is_default() {
+        return Ok(());
+    }
+    let mut instances: Vec<&mut RetreatGalleriesModel> = instances.into_iter().collect();
+    let ids: Vec<i64> = instances.iter().map(|m| m.gallery_id).collect();
+    let translations: Vec<GalleryTranslationModel> = GalleryTranslationEntity::find()
+        .filter(GalleryTranslationColumn::GalleryId.is_in(ids))
+        .filter(GalleryTranslationColumn::Locale.is_in(language.chain.clone()))
+        .all(database)
+        .await?;
+
+    for instance in instances.iter_mut() {
+        let own: Vec<&GalleryTranslationModel> = translations
+            .iter()
+            .filter(|translation| translation.gallery_id == instance.gallery_id)
+            .collect();
+        if let Some(caption) = language.pick(&own, |t| &t.locale, |t| t.caption.as_ref()) {
+            instance.caption = Some(caption);
+        }
+    }
+    Ok(())
+}
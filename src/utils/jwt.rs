@@ -7,7 +7,7 @@ use jwt_simple::{
     reexports::ct_codecs::{Decoder, Encoder, Hex},
 };
 
-use crate::{env, serializers::auth::TokenClaim};
+use crate::{config::Config, serializers::auth::TokenClaim};
 
 #[allow(unused)]
 pub async fn generate_jwt_key() -> String {
@@ -27,44 +27,53 @@ pub async fn get_jwt_key(key_str: &str) -> Vec<u8> {
     key
 }
 
-pub async fn get_access_jwt_key() -> HS256Key {
-    let access_key: String = env::ENV.jwt_access_key.to_string();
+pub async fn get_access_jwt_key(config: &Config) -> HS256Key {
+    let access_key: String = config.jwt_access_key.to_string();
     let byte_access_key: Vec<u8> = get_jwt_key(&access_key).await;
     let h256_access_key: HS256Key = HS256Key::from_bytes(&byte_access_key);
     h256_access_key
 }
 
-pub async fn get_refresh_jwt_key() -> HS256Key {
-    let refresh_key: String = env::ENV.jwt_refresh_key.to_string();
+pub async fn get_refresh_jwt_key(config: &Config) -> HS256Key {
+    let refresh_key: String = config.jwt_refresh_key.to_string();
     let byte_refresh_key: Vec<u8> = get_jwt_key(&refresh_key).await;
     let h256_refresh_key: HS256Key = HS256Key::from_bytes(&byte_refresh_key);
     h256_refresh_key
 }
 
-pub async fn generate_access_token(token_claim: TokenClaim) -> Result<String, Box<dyn Error>> {
-    let h256_access_key: HS256Key = get_access_jwt_key().await;
+pub async fn generate_access_token(
+    config: &Config,
+    token_claim: TokenClaim,
+) -> Result<String, Box<dyn Error>> {
+    let h256_access_key: HS256Key = get_access_jwt_key(config).await;
 
     let access_claims: JWTClaims<TokenClaim> = Claims::with_custom_claims(
         token_claim,
-        Duration::from_mins(env::ENV.jwt_access_lifetime_in_min),
+        Duration::from_mins(config.jwt_access_lifetime_in_min),
     );
     let access_token: String = h256_access_key.authenticate(access_claims)?;
     Ok(access_token)
 }
 
-pub async fn generate_refresh_token(token_claim: TokenClaim) -> Result<String, Box<dyn Error>> {
-    let h256_refresh_key: HS256Key = get_refresh_jwt_key().await;
+pub async fn generate_refresh_token(
+    config: &Config,
+    token_claim: TokenClaim,
+) -> Result<String, Box<dyn Error>> {
+    let h256_refresh_key: HS256Key = get_refresh_jwt_key(config).await;
 
     let refresh_claims: JWTClaims<TokenClaim> = Claims::with_custom_claims(
         token_claim,
-        Duration::from_mins(env::ENV.jwt_refresh_lifetime_in_min),
+        Duration::from_mins(config.jwt_refresh_lifetime_in_min),
     );
     let refresh_token: String = h256_refresh_key.authenticate(refresh_claims)?;
     Ok(refresh_token)
 }
 
-pub async fn get_access_token_claim(access_token: &str) -> Result<TokenClaim, Box<dyn Error>> {
-    let h256_access_key: HS256Key = get_access_jwt_key().await;
+pub async fn get_access_token_claim(
+    config: &Config,
+    access_token: &str,
+) -> Result<TokenClaim, Box<dyn Error>> {
+    let h256_access_key: HS256Key = get_access_jwt_key(config).await;
 
     let mut options: VerificationOptions = VerificationOptions::default();
     // Accept tokens that will only be valid in the future.
@@ -81,8 +90,11 @@ pub async fn get_access_token_claim(access_token: &str) -> Result<TokenClaim, Bo
     Ok(claims.custom)
 }
 
-pub async fn get_refresh_token_claim(refresh_token: &str) -> Result<TokenClaim, Box<dyn Error>> {
-    let h256_refresh_key: HS256Key = get_refresh_jwt_key().await;
+pub async fn get_refresh_token_claim(
+    config: &Config,
+    refresh_token: &str,
+) -> Result<TokenClaim, Box<dyn Error>> {
+    let h256_refresh_key: HS256Key = get_refresh_jwt_key(config).await;
 
     let mut options: VerificationOptions = VerificationOptions::default();
     // Accept tokens that will only be valid in the future.
@@ -26,6 +26,12 @@ macro_rules! map_fields {
             $( $field: $from.$field, )*
         }
     };
+    ($from:expr, $to:ident, { $( $field:ident ),* $(,)? }, { $( $extra:ident: $value:expr ),* $(,)? }) => {
+        $to {
+            $( $field: $from.$field, )*
+            $( $extra: $value, )*
+        }
+    };
 }
 
 
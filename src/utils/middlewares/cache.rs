@@ -0,0 +1,130 @@
+use axum::{
+    body::{Body, Bytes, to_bytes},
+    extract::{MatchedPath, Request, State},
+    http::{HeaderName, HeaderValue, Method, Response, StatusCode, header},
+    middleware::Next,
+};
+
+use crate::{
+    state::AppState,
+    utils::{
+        cache::{CacheTag, CachedResponse, Lookup},
+        etag::{is_not_modified, not_modified},
+        middlewares::idempotency::hex_digest,
+    },
+};
+
+pub const X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+/// The public catalog routes whose GET responses are cached, with the tags that
+/// invalidate them. Embedded relations add their own tags.
+fn route_tags(path: &str, query: Option<&str>) -> Option<Vec<CacheTag>> {
+    let mut tags: Vec<CacheTag> = match path {
+        "/retreats/" | "/retreats/{retreat_id}/" => vec![CacheTag::Retreats],
+        "/categories/" => vec![CacheTag::Categories],
+        "/retreats/{retreat_id}/galleries/" => vec![CacheTag::Galleries],
+        _ => return None,
+    };
+    let includes = query
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("include="))
+        .flat_map(|value| value.split(','));
+    for include in includes {
+        let tag: CacheTag = match include {
+            "category" => CacheTag::Categories,
+            "galleries" => CacheTag::Galleries,
+            "reviews_summary" => CacheTag::Reviews,
+            "staff" => CacheTag::Staff,
+            _ => continue,
+        };
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    Some(tags)
+}
+
+/// Varies on the query string, on who is asking and on the languages they accept.
+fn cache_key(request: &Request) -> String {
+    let caller: String = match request.headers().get(header::AUTHORIZATION) {
+        Some(value) => hex_digest(value.as_bytes()),
+        None => "anonymous".to_string(),
+    };
+    let languages: &str = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    format!("{} {} {}", request.uri(), caller, languages)
+}
+
+fn with_cache_status(mut response: Response<Body>, status: &'static str) -> Response<Body> {
+    response
+        .headers_mut()
+        .insert(X_CACHE, HeaderValue::from_static(status));
+    response
+}
+
+fn replay(cached: CachedResponse, request: &Request) -> Response<Body> {
+    let etag: Option<&str> = cached
+        .headers
+        .get(header::ETAG)
+        .and_then(|value| value.to_str().ok());
+    if let Some(etag) = etag
+        && is_not_modified(request.headers(), etag)
+    {
+        return with_cache_status(not_modified(etag), "HIT");
+    }
+
+    let mut response: Response<Body> = Response::new(Body::from(cached.body));
+    *response.status_mut() = cached.status;
+    *response.headers_mut() = cached.headers;
+    with_cache_status(response, "HIT")
+}
+
+/// Serves repeated GETs of the public catalog from memory for `CACHE_TTL_IN_SEC`.
+/// Only complete `200 OK` responses are stored, `X-Cache` tells hits from misses.
+pub async fn cache_responses(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response<Body> {
+    if !state.config.cache_enabled || request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let tags: Option<Vec<CacheTag>> = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| route_tags(path.as_str(), request.uri().query()));
+    let Some(tags) = tags else {
+        return next.run(request).await;
+    };
+
+    let key: String = cache_key(&request);
+    let generation: u64 = match state.cache.lookup(&key) {
+        Lookup::Hit(cached) => return replay(cached, &request),
+        Lookup::Miss(generation) => generation,
+    };
+
+    let response: Response<Body> = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return with_cache_status(response, "MISS");
+    }
+    let (parts, body) = response.into_parts();
+    let body: Bytes = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("Warning: failed to buffer a cacheable response: {e}");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let cached: CachedResponse = CachedResponse {
+        status: parts.status,
+        headers: parts.headers.clone(),
+        body: body.clone(),
+    };
+    state.cache.insert(key, tags, cached, generation);
+
+    with_cache_status(Response::from_parts(parts, Body::from(body)), "MISS")
+}
@@ -0,0 +1,385 @@
+use std::{
+    panic::{AssertUnwindSafe, resume_unwind},
+    time::Duration,
+};
+
+use axum::{
+    body::{Body, Bytes, to_bytes},
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, Method, Response, StatusCode, header, request::Parts},
+    middleware::Next,
+};
+use chrono::{DateTime, Utc};
+use futures_util::FutureExt;
+use sea_orm::{
+    ActiveValue::Set,
+    ColumnTrait, DbErr, EntityTrait, QueryFilter,
+    sea_query::{Expr, OnConflict},
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::Config,
+    entities_helper::{
+        IdempotencyKeyActiveModel, IdempotencyKeyColumn, IdempotencyKeyEntity, IdempotencyKeyModel,
+    },
+    state::{AppState, Clock},
+    utils::{
+        actor::current_actor,
+        database::ReplicatedDatabase,
+        response::{to_error_response, to_error_response_with_message},
+    },
+};
+
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+const MAX_KEY_LENGTH: usize = 255;
+
+enum Claim {
+    Acquired,
+    Existing(IdempotencyKeyModel),
+}
+
+/// Makes POST requests that carry an `Idempotency-Key` header safe to retry.
+/// The first response is stored and replayed for retries with the same key,
+/// keys are scoped to the path and user so two users can pick the same key.
+/// Only layered on the create routes, whose responses hold nothing secret.
+pub async fn idempotency(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response<Body> {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let key: String = match request.headers().get(IDEMPOTENCY_KEY) {
+        None => return next.run(request).await,
+        Some(value) => match value.to_str() {
+            Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+            _ => {
+                return to_error_response_with_message(
+                    "Idempotency-Key must be 1 to 255 visible ASCII characters.",
+                    StatusCode::BAD_REQUEST,
+                );
+            }
+        },
+    };
+
+    // Buffer the body to fingerprint it, bounded by the largest limit any route accepts.
+    let (parts, body) = request.into_parts();
+    let max_body_size: usize = state.config.upload_max_body_size_in_mb * 1024 * 1024;
+    let body: Bytes = match to_bytes(body, max_body_size).await {
+        Ok(body) => body,
+        Err(_) => {
+            return to_error_response_with_message(
+                "Request body is too large.",
+                StatusCode::PAYLOAD_TOO_LARGE,
+            );
+        }
+    };
+    let scope: String = request_scope(&parts);
+    let fingerprint: String = request_fingerprint(&parts, &body);
+
+    let claim = claim_key(
+        &state.database,
+        &state.config,
+        &state.clock,
+        &key,
+        &scope,
+        &fingerprint,
+    )
+    .await;
+    match claim {
+        Ok(Claim::Acquired) => {}
+        Ok(Claim::Existing(record)) => return replay(record, &fingerprint),
+        Err(e) => return to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    let request = Request::from_parts(parts, Body::from(body));
+    let response: Response<Body> = match AssertUnwindSafe(next.run(request)).catch_unwind().await {
+        Ok(response) => response,
+        Err(panic) => {
+            // Released before the panic handler answers, so the retry runs again
+            release_key(&state.database, &key, &scope).await;
+            resume_unwind(panic);
+        }
+    };
+    store_response(&state.database, &key, &scope, response).await
+}
+
+/// The user rather than the token, which changes on every refresh a retry may follow.
+fn request_scope(parts: &Parts) -> String {
+    let caller: String = match current_actor() {
+        Some(user_id) => format!("user:{user_id}"),
+        None => "anonymous".to_string(),
+    };
+    format!("{} {} {}", parts.method, parts.uri.path(), caller)
+}
+
+/// Hash of what makes two requests "the same", tolerant of the differences
+/// clients introduce on retry: JSON key order and multipart boundaries.
+fn request_fingerprint(parts: &Parts, body: &Bytes) -> String {
+    let content_type: &str = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    let mime: &str = content_type.split(';').next().unwrap_or("").trim();
+
+    let normalized_body: Vec<u8> = if mime == "application/json" {
+        serde_json::from_slice::<serde_json::Value>(body)
+            .ok()
+            .and_then(|value| serde_json::to_vec(&value).ok())
+            .unwrap_or_else(|| body.to_vec())
+    } else if let Some(boundary) = multipart_boundary(content_type) {
+        replace_all(body, boundary.as_bytes(), b"boundary")
+    } else {
+        body.to_vec()
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(parts.uri.query().unwrap_or("").as_bytes());
+    hasher.update([0]);
+    hasher.update(mime.as_bytes());
+    hasher.update([0]);
+    hasher.update(&normalized_body);
+    hex(&hasher.finalize())
+}
+
+fn multipart_boundary(content_type: &str) -> Option<&str> {
+    content_type
+        .split(';')
+        .map(str::trim)
+        .find_map(|param| param.strip_prefix("boundary="))
+        .map(|boundary| boundary.trim_matches('"'))
+        .filter(|boundary| !boundary.is_empty())
+}
+
+fn replace_all(haystack: &[u8], needle: &[u8], replacement: &[u8]) -> Vec<u8> {
+    let mut result: Vec<u8> = Vec::with_capacity(haystack.len());
+    let mut index: usize = 0;
+    while index < haystack.len() {
+        if haystack[index..].starts_with(needle) {
+            result.extend_from_slice(replacement);
+            index += needle.len();
+        } else {
+            result.push(haystack[index]);
+            index += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn hex_digest(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+async fn claim_key(
+    database: &ReplicatedDatabase,
+    config: &Config,
+    clock: &Clock,
+    key: &str,
+    scope: &str,
+    fingerprint: &str,
+) -> Result<Claim, DbErr> {
+    let ttl = chrono::Duration::hours(config.idempotency_key_ttl_in_hours as i64);
+    // A request cannot outlive its timeout, so an older in-progress row was abandoned.
+    let lock_timeout = chrono::Duration::seconds(
+        config
+            .request_timeout_in_sec
+            .max(config.upload_request_timeout_in_sec) as i64,
+    );
+
+    // The row may be removed by the cleanup task between the insert and the select.
+    for _ in 0..3 {
+        let now: DateTime<Utc> = clock.now();
+        let active_model: IdempotencyKeyActiveModel = IdempotencyKeyActiveModel {
+            key: Set(key.to_string()),
+            scope: Set(scope.to_string()),
+            request_fingerprint: Set(fingerprint.to_string()),
+            expires_at: Set((now + ttl).fixed_offset()),
+            ..Default::default()
+        };
+        let inserted: u64 = IdempotencyKeyEntity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([IdempotencyKeyColumn::Key, IdempotencyKeyColumn::Scope])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(database)
+            .await?;
+        if inserted > 0 {
+            return Ok(Claim::Acquired);
+        }
+
+        let Some(record) = IdempotencyKeyEntity::find()
+            .filter(IdempotencyKeyColumn::Key.eq(key))
+            .filter(IdempotencyKeyColumn::Scope.eq(scope))
+            .one(database)
+            .await?
+        else {
+            continue;
+        };
+
+        let is_expired: bool = record.expires_at < now;
+        let is_abandoned: bool =
+            record.response_status_code.is_none() && record.created_at + lock_timeout < now;
+        if !(is_expired || is_abandoned) {
+            return Ok(Claim::Existing(record));
+        }
+
+        // Take the row over, unless another retry got to it first.
+        let taken_over = IdempotencyKeyEntity::update_many()
+            .col_expr(
+                IdempotencyKeyColumn::RequestFingerprint,
+                Expr::value(fingerprint),
+            )
+            .col_expr(
+                IdempotencyKeyColumn::ResponseStatusCode,
+                Expr::value(Option::<i16>::None),
+            )
+            .col_expr(
+                IdempotencyKeyColumn::ResponseContentType,
+                Expr::value(Option::<String>::None),
+            )
+            .col_expr(
+                IdempotencyKeyColumn::ResponseBody,
+                Expr::value(Option::<Vec<u8>>::None),
+            )
+            .col_expr(IdempotencyKeyColumn::CreatedAt, Expr::value(now))
+            .col_expr(IdempotencyKeyColumn::ExpiresAt, Expr::value(now + ttl))
+            .filter(IdempotencyKeyColumn::IdempotencyKeyId.eq(record.idempotency_key_id))
+            .filter(IdempotencyKeyColumn::CreatedAt.eq(record.created_at))
+            .exec(database)
+            .await?;
+        if taken_over.rows_affected > 0 {
+            return Ok(Claim::Acquired);
+        }
+    }
+    Err(DbErr::Custom(
+        "Failed to claim the idempotency key, please retry.".to_string(),
+    ))
+}
+
+fn replay(record: IdempotencyKeyModel, fingerprint: &str) -> Response<Body> {
+    if record.request_fingerprint != fingerprint {
+        return to_error_response_with_message(
+            "Idempotency-Key has already been used with a different request.",
+            StatusCode::UNPROCESSABLE_ENTITY,
+        );
+    }
+    let Some(status_code) = record
+        .response_status_code
+        .and_then(|code| StatusCode::from_u16(code as u16).ok())
+    else {
+        return to_error_response_with_message(
+            "A request with this Idempotency-Key is still being processed.",
+            StatusCode::CONFLICT,
+        );
+    };
+
+    let mut response = Response::builder()
+        .status(status_code)
+        .header(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    if let Some(content_type) = record
+        .response_content_type
+        .and_then(|value| HeaderValue::from_str(&value).ok())
+    {
+        response = response.header(header::CONTENT_TYPE, content_type);
+    }
+    response
+        .body(Body::from(record.response_body.unwrap_or_default()))
+        .unwrap()
+}
+
+async fn release_key(database: &ReplicatedDatabase, key: &str, scope: &str) {
+    let result = IdempotencyKeyEntity::delete_many()
+        .filter(IdempotencyKeyColumn::Key.eq(key))
+        .filter(IdempotencyKeyColumn::Scope.eq(scope))
+        .exec(database)
+        .await;
+    if let Err(e) = result {
+        eprintln!("Warning: failed to release idempotency key: {e}");
+    }
+}
+
+async fn store_response(
+    database: &ReplicatedDatabase,
+    key: &str,
+    scope: &str,
+    response: Response<Body>,
+) -> Response<Body> {
+    // Failures that may succeed on a retry are not remembered.
+    let status: StatusCode = response.status();
+    if status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+    {
+        release_key(database, key, scope).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body: Bytes = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            release_key(database, key, scope).await;
+            return to_error_response(e, StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let content_type: Option<String> = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let result = IdempotencyKeyEntity::update_many()
+        .col_expr(
+            IdempotencyKeyColumn::ResponseStatusCode,
+            Expr::value(status.as_u16() as i16),
+        )
+        .col_expr(
+            IdempotencyKeyColumn::ResponseContentType,
+            Expr::value(content_type),
+        )
+        .col_expr(
+            IdempotencyKeyColumn::ResponseBody,
+            Expr::value(body.to_vec()),
+        )
+        .filter(IdempotencyKeyColumn::Key.eq(key))
+        .filter(IdempotencyKeyColumn::Scope.eq(scope))
+        .exec(database)
+        .await;
+    if let Err(e) = result {
+        eprintln!("Warning: failed to store idempotent response: {e}");
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Deletes expired keys every `IDEMPOTENCY_CLEANUP_INTERVAL_IN_MIN` until shutdown.
+pub async fn expire_idempotency_keys(state: AppState) {
+    let period = Duration::from_secs(state.config.idempotency_cleanup_interval_in_min * 60);
+    let mut interval = tokio::time::interval(period);
+    loop {
+        tokio::select! {
+            _ = state.shutdown.triggered() => break,
+            _ = interval.tick() => {}
+        }
+        let result = IdempotencyKeyEntity::delete_many()
+            .filter(IdempotencyKeyColumn::ExpiresAt.lt(state.clock.now()))
+            .exec(&state.database)
+            .await;
+        match result {
+            Ok(result) if result.rows_affected > 0 => {
+                println!("Expired {} idempotency key(s).", result.rows_affected)
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: failed to expire idempotency keys: {e}"),
+        }
+    }
+}
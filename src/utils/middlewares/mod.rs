@@ -1 +1,4 @@
-pub mod panic;
\ No newline at end of file
+pub mod cache;
+pub mod idempotency;
+pub mod panic;
+pub mod security;
\ No newline at end of file
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::DefaultBodyLimit,
+    http::{HeaderName, HeaderValue, Response, StatusCode, header},
+};
+use tower_http::{
+    cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer},
+    set_header::SetResponseHeaderLayer,
+    timeout::TimeoutLayer,
+};
+
+use crate::config::Config;
+
+// Values are checked by `Config::validate`, so parsing here does not fail.
+pub fn cors_layer(config: &Config) -> CorsLayer {
+    let allow_origin: AllowOrigin = if config.cors_allowed_origins.iter().any(|o| o == "*") {
+        AllowOrigin::from(Any)
+    } else {
+        AllowOrigin::list(
+            config
+                .cors_allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        )
+    };
+    let expose_headers: Vec<HeaderName> = config
+        .cors_exposed_headers
+        .iter()
+        .filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        // Mirroring keeps the behaviour of `Any` while staying valid with credentials.
+        .allow_methods(AllowMethods::mirror_request())
+        .allow_headers(AllowHeaders::mirror_request())
+        .allow_credentials(config.cors_allow_credentials)
+        .expose_headers(expose_headers)
+        .max_age(Duration::from_secs(config.cors_max_age_in_sec))
+}
+
+pub fn with_security_headers<S>(router: Router<S>, config: &Config) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let mut router = router
+        .layer(SetResponseHeaderLayer::if_not_present(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        ))
+        .layer(SetResponseHeaderLayer::if_not_present(
+            header::REFERRER_POLICY,
+            HeaderValue::from_static("no-referrer"),
+        ));
+
+    if let Ok(value) = HeaderValue::from_str(&config.frame_options) {
+        router = router.layer(SetResponseHeaderLayer::if_not_present(
+            header::X_FRAME_OPTIONS,
+            value,
+        ));
+    }
+    // The policy only means something to documents a browser renders, so JSON and files
+    // go without it. Routes serving their own HTML set a policy fitting it, kept here.
+    if let Ok(value) = HeaderValue::from_str(&config.content_security_policy) {
+        router = router.layer(SetResponseHeaderLayer::if_not_present(
+            header::CONTENT_SECURITY_POLICY,
+            move |response: &Response<Body>| is_html(response).then(|| value.clone()),
+        ));
+    }
+    if config.hsts_max_age_in_sec > 0 {
+        let value = format!("max-age={}; includeSubDomains", config.hsts_max_age_in_sec);
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            router = router.layer(SetResponseHeaderLayer::if_not_present(
+                header::STRICT_TRANSPORT_SECURITY,
+                value,
+            ));
+        }
+    }
+    router
+}
+
+fn is_html(response: &Response<Body>) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/html"))
+}
+
+/// Caps body size and request duration for every route currently in `router`.
+/// Routes merged afterwards keep their own limits.
+pub fn with_request_limits<S>(
+    router: Router<S>,
+    max_body_size: usize,
+    timeout: Duration,
+) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router
+        .layer(DefaultBodyLimit::max(max_body_size))
+        .layer(TimeoutLayer::with_status_code(StatusCode::REQUEST_TIMEOUT, timeout))
+}
@@ -1,8 +1,20 @@
+pub mod actor;
+pub mod backoff;
+pub mod cache;
+pub mod clock;
+pub mod currency;
+pub mod database;
+pub mod embed;
+pub mod etag;
+pub mod i18n;
 pub mod extractors;
 pub mod jwt;
 pub mod macros;
 pub mod middlewares;
 pub mod password;
+pub mod permissions;
 pub mod response;
 pub mod serializer;
+pub mod shutdown;
+pub mod soft_delete;
 pub mod storage;
@@ -1,11 +1,9 @@
 use password_worker::{Argon2idConfig, PasswordWorker};
 
-use crate::env::ENV;
-
 const MAX_TRHEADS: usize = 8;
 
-pub async fn create_password(password: &str) -> Result<String, Box<dyn std::error::Error>>{
-    let salt : Vec<u8>= ENV.password_salt.clone().into();
+pub async fn create_password(password: &str, salt: &str) -> Result<String, Box<dyn std::error::Error>>{
+    let salt : Vec<u8>= salt.into();
     let password_worker = PasswordWorker::new_argon2id(MAX_TRHEADS)?;
     let hashed_password = password_worker
         .hash(
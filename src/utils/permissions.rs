@@ -0,0 +1,69 @@
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::{
+    entities_helper::{
+        OrganizationUserColumn, OrganizationUserEntity, OrganizationUserModel, RetreatColumn,
+        RetreatEntity, RetreatUserColumn, RetreatUserEntity,
+    },
+    utils::soft_delete::SoftDelete,
+};
+
+/// The user's membership of an organization, if any.
+pub async fn organization_membership<C: ConnectionTrait>(
+    database: &C,
+    organization_id: i64,
+    user_id: i64,
+) -> Result<Option<OrganizationUserModel>, DbErr> {
+    OrganizationUserEntity::find()
+        .filter(OrganizationUserColumn::OrganizationId.eq(organization_id))
+        .filter(OrganizationUserColumn::UserId.eq(user_id))
+        .one(database)
+        .await
+}
+
+/// Owners of a retreat's organization own the retreat as well.
+pub async fn is_retreat_owner<C: ConnectionTrait>(
+    database: &C,
+    retreat_id: i64,
+    user_id: i64,
+) -> Result<bool, DbErr> {
+    has_retreat_access(database, retreat_id, user_id, true).await
+}
+
+/// Members of a retreat's organization are staff of the retreat as well.
+pub async fn is_retreat_staff<C: ConnectionTrait>(
+    database: &C,
+    retreat_id: i64,
+    user_id: i64,
+) -> Result<bool, DbErr> {
+    has_retreat_access(database, retreat_id, user_id, false).await
+}
+
+async fn has_retreat_access<C: ConnectionTrait>(
+    database: &C,
+    retreat_id: i64,
+    user_id: i64,
+    owner_only: bool,
+) -> Result<bool, DbErr> {
+    let mut staff = RetreatUserEntity::find()
+        .filter(RetreatUserColumn::RetreatId.eq(retreat_id))
+        .filter(RetreatUserColumn::UserId.eq(user_id));
+    if owner_only {
+        staff = staff.filter(RetreatUserColumn::IsOwner.eq(true));
+    }
+    if staff.count(database).await? > 0 {
+        return Ok(true);
+    }
+
+    let organization_id: Option<i64> = RetreatEntity::find_active()
+        .filter(RetreatColumn::RetreatId.eq(retreat_id))
+        .one(database)
+        .await?
+        .and_then(|retreat| retreat.organization_id);
+    let Some(organization_id) = organization_id else {
+        return Ok(false);
+    };
+    Ok(organization_membership(database, organization_id, user_id)
+        .await?
+        .is_some_and(|member| member.is_owner || !owner_only))
+}
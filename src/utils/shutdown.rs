@@ -0,0 +1,68 @@
+use std::future::Future;
+
+use tokio::signal;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+/// Shared shutdown handle. Handlers check it to fail readiness once shutdown has begun,
+/// and background workers are spawned through it so they can be drained before exit.
+#[derive(Clone, Debug, Default)]
+pub struct Shutdown {
+    token: CancellationToken,
+    workers: TaskTracker,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    pub async fn triggered(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Spawns a background worker. The worker should stop once `triggered` resolves.
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.workers.spawn(future);
+    }
+
+    pub async fn wait_for_workers(&self) {
+        self.workers.close();
+        self.workers.wait().await;
+    }
+}
+
+/// Resolves on SIGINT (Ctrl+C) or, on unix, SIGTERM.
+pub async fn wait_for_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, QueryTrait,
+    Select, TransactionTrait, prelude::DateTimeWithTimeZone,
+};
+
+use crate::{
+    entities_helper::{
+        RetreatColumn, RetreatEntity, RetreatGalleriesColumn, RetreatGalleriesEntity,
+        RetreatReviewColumn, RetreatReviewEntity, UserColumn, UserEntity,
+    },
+    jobs::{enqueue, handlers::RemoveGalleryImage},
+    state::AppState,
+};
+
+/// Entities whose rows are hidden by setting `deleted_at`, so they can be restored until
+/// the retention purge removes them for good.
+pub trait SoftDelete: EntityTrait {
+    fn deleted_at() -> Self::Column;
+
+    /// Rows that have not been deleted, what every query should start from.
+    fn find_active() -> Select<Self> {
+        Self::find().filter(Self::deleted_at().is_null())
+    }
+
+    /// Rows waiting to be restored or purged.
+    fn find_deleted() -> Select<Self> {
+        Self::find().filter(Self::deleted_at().is_not_null())
+    }
+}
+
+impl SoftDelete for RetreatEntity {
+    fn deleted_at() -> Self::Column {
+        RetreatColumn::DeletedAt
+    }
+}
+
+impl SoftDelete for UserEntity {
+    fn deleted_at() -> Self::Column {
+        UserColumn::DeletedAt
+    }
+}
+
+impl SoftDelete for RetreatReviewEntity {
+    fn deleted_at() -> Self::Column {
+        RetreatReviewColumn::DeletedAt
+    }
+}
+
+impl SoftDelete for RetreatGalleriesEntity {
+    fn deleted_at() -> Self::Column {
+        RetreatGalleriesColumn::DeletedAt
+    }
+}
+
+/// Emails stay unique across soft deleted users, whose rows keep theirs until restored
+/// or purged. The reason `email` cannot be used, if it cannot.
+pub async fn email_conflict<C: ConnectionTrait>(
+    db: &C,
+    email: &str,
+) -> Result<Option<&'static str>, DbErr> {
+    let user = UserEntity::find()
+        .filter(UserColumn::Email.eq(email))
+        .one(db)
+        .await?;
+    Ok(match user {
+        None => None,
+        Some(user) if user.deleted_at.is_some() => {
+            Some("Email belongs to a deleted user pending purge, it can be reused once purged.")
+        }
+        Some(_) => Some("Email is already taken."),
+    })
+}
+
+/// Same as [`email_conflict`] for retreat slugs, `retreat_id` is the retreat keeping its
+/// own slug on update.
+pub async fn slug_conflict<C: ConnectionTrait>(
+    db: &C,
+    slug: &str,
+    retreat_id: Option<i64>,
+) -> Result<Option<&'static str>, DbErr> {
+    let mut select = RetreatEntity::find().filter(RetreatColumn::Slug.eq(slug));
+    if let Some(retreat_id) = retreat_id {
+        select = select.filter(RetreatColumn::RetreatId.ne(retreat_id));
+    }
+    Ok(match select.one(db).await? {
+        None => None,
+        Some(retreat) if retreat.deleted_at.is_some() => {
+            Some("Slug belongs to a deleted retreat pending purge, it can be reused once purged.")
+        }
+        Some(_) => Some("Slug is already taken."),
+    })
+}
+
+/// Removes rows deleted more than `SOFT_DELETE_RETENTION_IN_DAYS` ago, runs every
+/// `SOFT_DELETE_PURGE_INTERVAL_IN_MIN`.
+pub async fn purge_soft_deleted(state: AppState) {
+    let period = Duration::from_secs(state.config.soft_delete_purge_interval_in_min * 60);
+    let mut interval = tokio::time::interval(period);
+    loop {
+        tokio::select! {
+            _ = state.shutdown.triggered() => break,
+            _ = interval.tick() => {}
+        }
+        match purge(&state).await {
+            Ok(purged) if purged > 0 => println!("Purged {purged} soft deleted row(s)."),
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: failed to purge soft deleted rows: {e}"),
+        }
+    }
+}
+
+async fn purge(state: &AppState) -> Result<u64, DbErr> {
+    let retention = Duration::from_secs(state.config.soft_delete_retention_in_days * 24 * 3600);
+    let cutoff: DateTimeWithTimeZone = (state.clock.now() - retention).fixed_offset();
+    let expired_retreats = || {
+        RetreatEntity::find()
+            .select_only()
+            .column(RetreatColumn::RetreatId)
+            .filter(RetreatColumn::DeletedAt.lt(cutoff))
+            .into_query()
+    };
+
+    let transaction = state.database.begin().await?;
+
+    // Galleries go with their own deletion or their retreat's, the cascade takes the rows
+    // of the latter, so collect every image first. The files are removed once this commits.
+    let image_paths: Vec<String> = RetreatGalleriesEntity::find()
+        .select_only()
+        .column(RetreatGalleriesColumn::ImagePath)
+        .filter(
+            RetreatGalleriesColumn::DeletedAt
+                .lt(cutoff)
+                .or(RetreatGalleriesColumn::RetreatId.in_subquery(expired_retreats())),
+        )
+        .into_tuple()
+        .all(&transaction)
+        .await?;
+    for image_path in image_paths {
+        enqueue(&transaction, &state.clock, &RemoveGalleryImage { image_path }).await?;
+    }
+
+    let mut purged: u64 = 0;
+    purged += RetreatGalleriesEntity::delete_many()
+        .filter(RetreatGalleriesColumn::DeletedAt.lt(cutoff))
+        .exec(&transaction)
+        .await?
+        .rows_affected;
+    purged += RetreatReviewEntity::delete_many()
+        .filter(RetreatReviewColumn::DeletedAt.lt(cutoff))
+        .exec(&transaction)
+        .await?
+        .rows_affected;
+    purged += RetreatEntity::delete_many()
+        .filter(RetreatColumn::DeletedAt.lt(cutoff))
+        .exec(&transaction)
+        .await?
+        .rows_affected;
+    purged += UserEntity::delete_many()
+        .filter(UserColumn::DeletedAt.lt(cutoff))
+        .exec(&transaction)
+        .await?
+        .rows_affected;
+    transaction.commit().await?;
+    Ok(purged)
+}
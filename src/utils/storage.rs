@@ -5,14 +5,35 @@ use axum::{
         header::{CONTENT_DISPOSITION, CONTENT_TYPE},
     },
 };
-use std::error::Error;
+use std::{
+    error::Error,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::{
     fs::{self, File},
     io::{AsyncReadExt, AsyncWriteExt},
 };
 use uuid::Uuid;
 
-use crate::env::ENV;
+/// Where uploads are kept, `UPLOAD_DIR` unless the state was built with another directory.
+#[derive(Clone, Debug)]
+pub struct Storage {
+    upload_dir: Arc<PathBuf>,
+}
+
+impl Storage {
+    pub fn new(upload_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            upload_dir: Arc::new(upload_dir.into()),
+        }
+    }
+
+    pub fn upload_dir(&self) -> &Path {
+        &self.upload_dir
+    }
+}
 
 fn replace_file_name_with_uuid(file_path: &str) -> String {
     // Get the file extension, if any
@@ -31,16 +52,10 @@ fn replace_file_name_with_uuid(file_path: &str) -> String {
 }
 
 pub async fn store_retreat_gallery(
+    upload_dir: &Path,
     file_content: Bytes,
     file_name: String,
-    old_image_path: Option<String>,
 ) -> String {
-    // Remove old image if provided
-    if let Some(old_relative_path) = old_image_path {
-        remove_retreat_gallery(old_relative_path).await;
-    }
-
-    let upload_dir = ENV.upload_dir.clone();
     let sub_dir: &str = "retreat/gallery";
     let upload_directory_full_path = upload_dir.join(sub_dir);
     fs::create_dir_all(&upload_directory_full_path).await.ok();
@@ -55,20 +70,18 @@ pub async fn store_retreat_gallery(
     return relative_path;
 }
 
-pub async fn remove_retreat_gallery(image_path: String) {
-    let upload_dir = ENV.upload_dir.clone();
-
-    // Remove old image if provided
-    let full_old_path = upload_dir.join(image_path);
-    if fs::remove_file(&full_old_path).await.is_err() {
-        eprintln!("Warning: failed to remove file at {:?}", full_old_path);
+/// An image that is already gone counts as removed.
+pub async fn remove_retreat_gallery(upload_dir: &Path, image_path: &str) -> io::Result<()> {
+    match fs::remove_file(upload_dir.join(image_path)).await {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
     }
 }
 
 pub async fn read_retreat_gallery_with_headers(
+    upload_dir: &Path,
     image_path: String,
 ) -> Result<(Vec<u8>, HeaderMap), Box<dyn Error>> {
-    let upload_dir = ENV.upload_dir.clone();
     let gallery_path = upload_dir.join(image_path);
     let mut image_content: Vec<u8> = Vec::new();
 
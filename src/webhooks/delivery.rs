@@ -0,0 +1,272 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use hmac::{Hmac, Mac};
+use reqwest::{
+    Client, Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect::Policy,
+};
+use sha2::Sha256;
+
+pub use crate::utils::backoff::retry_delay;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// One signed POST to a subscriber.
+#[derive(Debug, Clone)]
+pub struct WebhookRequest<'a> {
+    pub url: &'a str,
+    pub secret: &'a str,
+    pub event: &'a str,
+    pub delivery_id: i64,
+    pub body: &'a [u8],
+    /// Unix seconds the signature is made for.
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    Delivered {
+        status_code: u16,
+    },
+    /// `status_code` is `None` when no response came back at all.
+    Failed {
+        status_code: Option<u16>,
+        error: String,
+    },
+}
+
+/// Resolves subscriber hosts for the client and refuses those answering with a blocked
+/// address, so the addresses connected to are the ones vetted, whatever DNS said before.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .collect();
+            if addresses.iter().any(|address| is_blocked_address(address.ip())) {
+                return Err("host resolves to a private or local address".into());
+            }
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Client shared by every delivery. Redirects are not followed, a subscriber has to
+/// register the final URL.
+pub fn http_client(timeout: Duration) -> Client {
+    Client::builder()
+        .timeout(timeout)
+        .redirect(Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .user_agent(concat!(
+            "MyRetreatNest-Webhooks/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()
+        .expect("Failed to build the webhook HTTP client")
+}
+
+/// Addresses inside our own network, which subscribers must never make us call.
+fn is_blocked_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => {
+            let [first, second, third, _] = address.octets();
+            address.is_loopback()
+                || address.is_private()
+                || address.is_link_local()
+                || address.is_broadcast()
+                || address.is_multicast()
+                // "This network", 0.0.0.0/8
+                || first == 0
+                // Carrier grade NAT, 100.64.0.0/10
+                || (first == 100 && second & 0xc0 == 64)
+                // IETF protocol assignments, 192.0.0.0/24
+                || (first == 192 && second == 0 && third == 0)
+                // Benchmarking, 198.18.0.0/15
+                || (first == 198 && second & 0xfe == 18)
+        }
+        // IPv4-mapped ::ffff:a.b.c.d and IPv4-compatible ::a.b.c.d, the latter taking in
+        // :: and ::1 as 0.0.0.0/8
+        IpAddr::V6(address) => match address.to_ipv4() {
+            Some(address) => is_blocked_address(IpAddr::V4(address)),
+            None => {
+                let segments: [u16; 8] = address.segments();
+                address.is_multicast()
+                    || address.is_unique_local()
+                    || address.is_unicast_link_local()
+                    // NAT64 64:ff9b::/96 and 6to4 2002::/16 reach IPv4 addresses through a gateway
+                    || segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0]
+                    || segments[0] == 0x2002
+            }
+        },
+    }
+}
+
+/// Checks that `url` is an http(s) URL whose host resolves only to public addresses. Run
+/// when a subscription is saved and again before every delivery, where `http_client`
+/// resolves the host once more and holds it to the same rule.
+pub async fn check_target(url: &str) -> Result<(), String> {
+    let url: Url = Url::parse(url).map_err(|_| "Webhook URL is not a valid URL".to_string())?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("Webhook URL must be an http(s) URL".to_string());
+    }
+    let port: u16 = url.port_or_known_default().unwrap_or(80);
+    let host: &str = url
+        .host_str()
+        .ok_or_else(|| "Webhook URL has no host".to_string())?;
+    // IPv6 hosts come in brackets
+    let addresses: Vec<IpAddr> = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(address) => vec![address],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| "Webhook URL host does not resolve".to_string())?
+            .map(|address| address.ip())
+            .collect(),
+    };
+    if addresses.is_empty() {
+        return Err("Webhook URL host does not resolve".to_string());
+    }
+    if addresses.into_iter().any(is_blocked_address) {
+        return Err("Webhook URL must not point to a private or local address".to_string());
+    }
+    Ok(())
+}
+
+fn signed_mac(secret: &str, timestamp: i64, body: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`. Signing the timestamp along with
+/// the body lets receivers reject replayed deliveries.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let signature: String = signed_mac(secret, timestamp, body)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("t={timestamp},v1={signature}")
+}
+
+/// Receiver side check of `sign_payload`, rejecting signatures older than `tolerance`.
+pub fn verify_signature(
+    secret: &str,
+    header: &str,
+    body: &[u8],
+    now: i64,
+    tolerance: Duration,
+) -> bool {
+    let mut timestamp: Option<i64> = None;
+    let mut signatures: Vec<&str> = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse().ok(),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+    if now.abs_diff(timestamp) > tolerance.as_secs() {
+        return false;
+    }
+    // `verify_slice` compares in constant time
+    signatures.into_iter().any(|signature| {
+        decode_hex(signature).is_some_and(|expected| {
+            signed_mac(secret, timestamp, body)
+                .verify_slice(&expected)
+                .is_ok()
+        })
+    })
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// The kind of a failed request, never its message, which may quote the subscriber.
+fn request_error_kind(error: &reqwest::Error) -> &'static str {
+    if error.is_timeout() {
+        "timeout"
+    } else if error.is_connect() {
+        "connection failed"
+    } else if error.is_redirect() {
+        "redirect"
+    } else if error.is_body() || error.is_decode() {
+        "body"
+    } else if error.is_builder() {
+        "invalid request"
+    } else {
+        "request failed"
+    }
+}
+
+/// Sends one delivery. Any 2xx counts as delivered, everything else is retried. Only the
+/// status code of a failure is kept, subscribers' response bodies never end up in the log.
+pub async fn send_webhook(client: &Client, request: &WebhookRequest<'_>) -> DeliveryOutcome {
+    let result = client
+        .post(request.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, request.event)
+        .header(DELIVERY_HEADER, request.delivery_id.to_string())
+        .header(
+            SIGNATURE_HEADER,
+            sign_payload(request.secret, request.timestamp, request.body),
+        )
+        .body(request.body.to_vec())
+        .send()
+        .await;
+
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            return DeliveryOutcome::Failed {
+                status_code: None,
+                error: request_error_kind(&e).to_string(),
+            };
+        }
+    };
+
+    let status_code: u16 = response.status().as_u16();
+    if response.status().is_success() {
+        return DeliveryOutcome::Delivered { status_code };
+    }
+    DeliveryOutcome::Failed {
+        status_code: Some(status_code),
+        error: format!("HTTP {status_code}"),
+    }
+}
+
+/// Random signing secret handed to the subscriber once, when the subscription is created.
+pub fn generate_secret() -> String {
+    format!(
+        "whsec_{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
@@ -0,0 +1,202 @@
+pub mod delivery;
+mod worker;
+
+use std::{fmt, str::FromStr};
+
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DatabaseTransaction, DbErr,
+    EntityTrait, QueryFilter, sea_query::Expr,
+};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    entities_helper::{
+        WebhookDeliveryActiveModel, WebhookDeliveryEntity, WebhookSubscriptionColumn,
+        WebhookSubscriptionEntity, WebhookSubscriptionModel,
+    },
+    events::{DomainEvent, SubscriberFuture},
+    state::AppState,
+};
+
+pub(crate) use worker::dispatch_webhooks;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_DELIVERED: &str = "delivered";
+/// Gave up after the configured number of attempts, only a manual retry sends it again.
+pub const STATUS_DEAD: &str = "dead";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    RetreatPublished,
+    RetreatUpdated,
+    RetreatDeleted,
+    ReviewPosted,
+    GalleryCreated,
+    GalleryUpdated,
+    GalleryDeleted,
+}
+
+impl WebhookEvent {
+    pub const ALL: &'static [WebhookEvent] = &[
+        WebhookEvent::RetreatPublished,
+        WebhookEvent::RetreatUpdated,
+        WebhookEvent::RetreatDeleted,
+        WebhookEvent::ReviewPosted,
+        WebhookEvent::GalleryCreated,
+        WebhookEvent::GalleryUpdated,
+        WebhookEvent::GalleryDeleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::RetreatPublished => "retreat.published",
+            WebhookEvent::RetreatUpdated => "retreat.updated",
+            WebhookEvent::RetreatDeleted => "retreat.deleted",
+            WebhookEvent::ReviewPosted => "review.posted",
+            WebhookEvent::GalleryCreated => "gallery.created",
+            WebhookEvent::GalleryUpdated => "gallery.updated",
+            WebhookEvent::GalleryDeleted => "gallery.deleted",
+        }
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        WebhookEvent::ALL
+            .iter()
+            .find(|event| event.as_str() == value)
+            .copied()
+            .ok_or_else(|| format!("Unknown webhook event `{value}`."))
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An empty event list subscribes to everything.
+fn subscribes_to(subscription: &WebhookSubscriptionModel, event: WebhookEvent) -> bool {
+    match subscription.events.as_array() {
+        Some(events) => {
+            events.is_empty()
+                || events
+                    .iter()
+                    .any(|name| name.as_str() == Some(event.as_str()))
+        }
+        None => true,
+    }
+}
+
+/// Outbox subscriber, queues the deliveries of an event in the relay's transaction.
+pub(crate) fn queue_webhooks<'a>(
+    _state: &'a AppState,
+    transaction: &'a DatabaseTransaction,
+    event: &'a DomainEvent,
+) -> SubscriberFuture<'a> {
+    Box::pin(async move {
+        let (webhook_event, retreat_id, data): (WebhookEvent, i64, &JsonValue) = match event {
+            DomainEvent::RetreatPublished {
+                retreat_id,
+                retreat,
+            } => (WebhookEvent::RetreatPublished, *retreat_id, retreat),
+            DomainEvent::RetreatUpdated {
+                retreat_id,
+                retreat,
+            } => (WebhookEvent::RetreatUpdated, *retreat_id, retreat),
+            DomainEvent::RetreatDeleted {
+                retreat_id,
+                retreat,
+            } => (WebhookEvent::RetreatDeleted, *retreat_id, retreat),
+            DomainEvent::ReviewPosted { retreat_id, review } => {
+                (WebhookEvent::ReviewPosted, *retreat_id, review)
+            }
+            DomainEvent::GalleryCreated {
+                retreat_id,
+                gallery,
+            } => (WebhookEvent::GalleryCreated, *retreat_id, gallery),
+            DomainEvent::GalleryUpdated {
+                retreat_id,
+                gallery,
+            } => (WebhookEvent::GalleryUpdated, *retreat_id, gallery),
+            DomainEvent::GalleryDeleted {
+                retreat_id,
+                gallery,
+            } => (WebhookEvent::GalleryDeleted, *retreat_id, gallery),
+            // Subscriptions of a deleted retreat are retired, so there is no one left to tell
+            // about its restore
+            DomainEvent::RetreatCreated { .. }
+            | DomainEvent::RetreatRestored { .. }
+            | DomainEvent::GalleryRestored { .. }
+            | DomainEvent::StaffAdded { .. }
+            | DomainEvent::WishlistAdded { .. } => return Ok(()),
+        };
+
+        queue_deliveries(transaction, webhook_event, retreat_id, data)
+            .await
+            .map_err(|e| e.to_string())?;
+        // Nothing more is recorded for a deleted retreat once its `retreat.deleted` is queued
+        if webhook_event == WebhookEvent::RetreatDeleted {
+            retire_retreat_subscriptions(transaction, retreat_id)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    })
+}
+
+/// Queues a delivery of `event` for every active subscription of the retreat and every
+/// platform wide one.
+async fn queue_deliveries<C>(
+    database: &C,
+    event: WebhookEvent,
+    retreat_id: i64,
+    payload: &JsonValue,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let subscriptions: Vec<WebhookSubscriptionModel> = WebhookSubscriptionEntity::find()
+        .filter(WebhookSubscriptionColumn::IsActive.eq(true))
+        .filter(
+            Condition::any()
+                .add(WebhookSubscriptionColumn::RetreatId.is_null())
+                .add(WebhookSubscriptionColumn::RetreatId.eq(retreat_id)),
+        )
+        .all(database)
+        .await?;
+
+    let deliveries: Vec<WebhookDeliveryActiveModel> = subscriptions
+        .iter()
+        .filter(|subscription| subscribes_to(subscription, event))
+        .map(|subscription| WebhookDeliveryActiveModel {
+            webhook_subscription_id: Set(subscription.webhook_subscription_id),
+            event: Set(event.as_str().to_string()),
+            payload: Set(payload.clone()),
+            ..Default::default()
+        })
+        .collect();
+    if deliveries.is_empty() {
+        return Ok(());
+    }
+
+    WebhookDeliveryEntity::insert_many(deliveries)
+        .exec_without_returning(database)
+        .await?;
+    Ok(())
+}
+
+async fn retire_retreat_subscriptions<C>(database: &C, retreat_id: i64) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    WebhookSubscriptionEntity::update_many()
+        .col_expr(WebhookSubscriptionColumn::IsActive, Expr::value(false))
+        .filter(WebhookSubscriptionColumn::RetreatId.eq(retreat_id))
+        .exec(database)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use reqwest::Client;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DbErr, EntityTrait, IntoActiveModel,
+    LoaderTrait, QueryFilter, QueryOrder, QuerySelect, prelude::DateTimeWithTimeZone,
+    sea_query::Expr,
+};
+use serde_json::json;
+use tokio::task::JoinSet;
+
+use crate::{
+    entities_helper::{
+        WebhookDeliveryActiveModel, WebhookDeliveryColumn, WebhookDeliveryEntity,
+        WebhookDeliveryModel, WebhookSubscriptionEntity, WebhookSubscriptionModel,
+    },
+    state::AppState,
+    webhooks::{
+        STATUS_DEAD, STATUS_DELIVERED, STATUS_PENDING,
+        delivery::{
+            DeliveryOutcome, WebhookRequest, check_target, http_client, retry_delay, send_webhook,
+        },
+    },
+};
+
+const DELIVERY_BATCH_SIZE: u64 = 50;
+
+/// Polls for due deliveries and sends them until shutdown.
+pub(crate) async fn dispatch_webhooks(state: AppState) {
+    let client: Client = http_client(Duration::from_secs(state.config.webhook_timeout_in_sec));
+    let period = Duration::from_secs(state.config.webhook_poll_interval_in_sec);
+    let mut interval = tokio::time::interval(period);
+    loop {
+        tokio::select! {
+            _ = state.shutdown.triggered() => break,
+            _ = interval.tick() => {}
+        }
+        if let Err(e) = dispatch_due(&state, &client).await {
+            eprintln!("Warning: failed to dispatch webhooks: {e}");
+        }
+    }
+}
+
+async fn dispatch_due(state: &AppState, client: &Client) -> Result<(), DbErr> {
+    let now: DateTimeWithTimeZone = state.clock.now_fixed();
+    let due: Vec<WebhookDeliveryModel> = WebhookDeliveryEntity::find()
+        .filter(WebhookDeliveryColumn::Status.eq(STATUS_PENDING))
+        .filter(WebhookDeliveryColumn::NextAttemptAt.lte(now))
+        .order_by_asc(WebhookDeliveryColumn::NextAttemptAt)
+        .limit(DELIVERY_BATCH_SIZE)
+        .all(&state.database)
+        .await?;
+    let subscriptions: Vec<Option<WebhookSubscriptionModel>> = due
+        .load_one(WebhookSubscriptionEntity, &state.database)
+        .await?;
+
+    // Push the next attempt past the request timeout while a delivery is in flight, so
+    // another instance polling meanwhile leaves it alone.
+    let lease: DateTimeWithTimeZone =
+        now + Duration::from_secs(state.config.webhook_timeout_in_sec * 2);
+
+    let mut tasks: JoinSet<()> = JoinSet::new();
+    for (delivery, subscription) in due.into_iter().zip(subscriptions) {
+        let Some(subscription) = subscription else {
+            continue;
+        };
+        let claimed = WebhookDeliveryEntity::update_many()
+            .col_expr(WebhookDeliveryColumn::NextAttemptAt, Expr::value(lease))
+            .filter(WebhookDeliveryColumn::WebhookDeliveryId.eq(delivery.webhook_delivery_id))
+            .filter(WebhookDeliveryColumn::Status.eq(STATUS_PENDING))
+            .filter(WebhookDeliveryColumn::NextAttemptAt.eq(delivery.next_attempt_at))
+            .exec(&state.database)
+            .await?;
+        if claimed.rows_affected == 0 {
+            continue;
+        }
+        tasks.spawn(deliver(
+            state.clone(),
+            client.clone(),
+            delivery,
+            subscription,
+        ));
+    }
+    while tasks.join_next().await.is_some() {}
+    Ok(())
+}
+
+async fn deliver(
+    state: AppState,
+    client: Client,
+    delivery: WebhookDeliveryModel,
+    subscription: WebhookSubscriptionModel,
+) {
+    let body: Vec<u8> = json!({
+        "delivery_id": delivery.webhook_delivery_id,
+        "event": delivery.event,
+        "created_at": delivery.created_at,
+        "data": delivery.payload,
+    })
+    .to_string()
+    .into_bytes();
+    let outcome: DeliveryOutcome = match check_target(&subscription.url).await {
+        Ok(()) => {
+            send_webhook(
+                &client,
+                &WebhookRequest {
+                    url: &subscription.url,
+                    secret: &subscription.secret,
+                    event: &delivery.event,
+                    delivery_id: delivery.webhook_delivery_id,
+                    body: &body,
+                    timestamp: state.clock.now().timestamp(),
+                },
+            )
+            .await
+        }
+        Err(error) => DeliveryOutcome::Failed {
+            status_code: None,
+            error,
+        },
+    };
+
+    let delivery_id: i64 = delivery.webhook_delivery_id;
+    let attempts: i32 = delivery.attempts + 1;
+    let now: DateTimeWithTimeZone = state.clock.now_fixed();
+    let mut active_model: WebhookDeliveryActiveModel = delivery.into_active_model();
+    active_model.attempts = Set(attempts);
+    match outcome {
+        DeliveryOutcome::Delivered { status_code } => {
+            active_model.status = Set(STATUS_DELIVERED.to_string());
+            active_model.last_status_code = Set(Some(status_code as i16));
+            active_model.last_error = Set(None);
+            active_model.delivered_at = Set(Some(now));
+        }
+        DeliveryOutcome::Failed { status_code, error } => {
+            active_model.last_status_code = Set(status_code.map(|code| code as i16));
+            active_model.last_error = Set(Some(error));
+            if attempts >= state.config.webhook_max_attempts as i32 {
+                active_model.status = Set(STATUS_DEAD.to_string());
+            } else {
+                let delay: Duration = retry_delay(
+                    attempts as u32,
+                    Duration::from_secs(state.config.webhook_retry_base_in_sec),
+                    Duration::from_secs(state.config.webhook_retry_max_in_sec),
+                );
+                active_model.next_attempt_at = Set(now + delay);
+            }
+        }
+    }
+
+    if let Err(e) = active_model.update(&state.database).await {
+        eprintln!("Warning: failed to record outcome of webhook delivery {delivery_id}: {e}");
+    }
+}
@@ -0,0 +1,616 @@
+//! API tests against a throwaway database, skipped unless `TEST_DATABASE_URL` is set.
+
+use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use my_retreat_nest::{
+    state::Clock,
+    testing::{PASSWORD, TestApp, TestResponse, UserModel},
+};
+use serde_json::{Value as JsonValue, json};
+
+#[tokio::test]
+async fn liveness_needs_nothing() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let response = app.get("/health/live").await;
+    assert_eq!(response.status, StatusCode::OK);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn login_issues_tokens() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+
+    let response = app
+        .request(Method::POST, "/auth/login/")
+        .json(json!({"email": user.email, "password": PASSWORD}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert!(response.data()["access_token"].is_string());
+
+    let response = app
+        .request(Method::POST, "/auth/login/")
+        .json(json!({"email": user.email, "password": "wrong"}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn replays_only_retried_creates() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let category = app.create_category().await;
+    let payload = json!({
+        "name": "Retried",
+        "category_id": category.category_id,
+        "slug": "retried",
+        "social_links": {}
+    });
+
+    let mut retreat_ids = Vec::new();
+    for _ in 0..2 {
+        let response = app
+            .request(Method::POST, "/retreats/")
+            .user(&user)
+            .header("Idempotency-Key", "create-retried")
+            .json(payload.clone())
+            .send()
+            .await;
+        assert_eq!(response.status, StatusCode::CREATED);
+        retreat_ids.push(response.data()["retreat_id"].clone());
+    }
+    assert_eq!(retreat_ids[0], retreat_ids[1]);
+
+    // Tokens are never stored for replay
+    for _ in 0..2 {
+        let response = app
+            .request(Method::POST, "/auth/login/")
+            .header("Idempotency-Key", "login-retried")
+            .json(json!({"email": user.email, "password": PASSWORD}))
+            .send()
+            .await;
+        assert!(!response.headers.contains_key("idempotent-replayed"));
+    }
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn refuses_values_held_by_deleted_rows() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    let signup = json!({"name": user.name, "email": user.email, "password": PASSWORD});
+
+    let response = app.request(Method::POST, "/users/").json(signup.clone()).send().await;
+    assert_eq!(response.status, StatusCode::CONFLICT);
+
+    let response = app
+        .request(Method::DELETE, &format!("/users/{}/", user.user_id))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+    let response = app.request(Method::POST, "/users/").json(signup).send().await;
+    assert_eq!(response.status, StatusCode::CONFLICT);
+    let response = app
+        .request(Method::POST, &format!("/retreats/{}/users/", retreat.retreat_id))
+        .json(json!({"name": user.name, "email": user.email, "role": "Host"}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::CONFLICT);
+
+    let response = app
+        .request(Method::DELETE, &format!("/retreats/{}/", retreat.retreat_id))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+    let payload = json!({
+        "name": "Reused",
+        "category_id": retreat.category_id,
+        "slug": retreat.slug,
+        "social_links": {}
+    });
+    let response = app.request(Method::POST, "/retreats/").json(payload).send().await;
+    assert_eq!(response.status, StatusCode::CONFLICT);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn only_owners_move_retreats_between_organizations() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let owner = app.create_user().await;
+    let outsider = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    let response = app
+        .post_as(&owner, "/organizations/", json!({"name": "Chain", "slug": "chain"}))
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    let organization_id = response.data()["organization_id"].clone();
+    let uri = format!("/retreats/{}/", retreat.retreat_id);
+
+    let response = app
+        .request(Method::PATCH, &uri)
+        .json(json!({"organization_id": organization_id}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+    let response = app
+        .patch_as(&outsider, &uri, json!({"organization_id": organization_id}))
+        .await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+    let response = app
+        .patch_as(&owner, &uri, json!({"organization_id": organization_id}))
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let response = app
+        .patch_as(&outsider, &uri, json!({"organization_id": null}))
+        .await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+    // Other changes leave the organization alone and need no ownership
+    let response = app.patch_as(&outsider, &uri, json!({"name": "Renamed"})).await;
+    assert_eq!(response.status, StatusCode::OK);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn reads_a_retreat() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let retreat = app.create_retreat().await;
+
+    let response = app.get(&format!("/retreats/{}/", retreat.retreat_id)).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.data()["slug"], retreat.slug);
+    assert_eq!(response.headers["content-language"], "en");
+    assert!(
+        response.headers.get_all("vary").iter().any(|value| value == "accept-language")
+    );
+
+    let response = app
+        .request(Method::GET, &format!("/retreats/{}/", retreat.retreat_id))
+        .header("Accept-Language", "ne-NP, hi;q=0.5")
+        .send()
+        .await;
+    assert_eq!(response.headers["content-language"], "ne, hi, en");
+
+    let response = app.get("/retreats/0/").await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn caches_retreat_lists_until_a_write() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    let cache_status = |response: &TestResponse| response.headers["x-cache"].clone();
+
+    assert_eq!(cache_status(&app.get("/retreats/").await), "MISS");
+    let response = app.get("/retreats/").await;
+    assert_eq!(cache_status(&response), "HIT");
+    assert_eq!(response.data().as_array().map(Vec::len), Some(1));
+
+    // Every caller and every language list gets an entry of its own
+    assert_eq!(cache_status(&app.get_as(&user, "/retreats/").await), "MISS");
+    assert_eq!(cache_status(&app.get_as(&user, "/retreats/").await), "HIT");
+    for (language, expected) in [("ne", "MISS"), ("ne", "HIT"), ("hi", "MISS")] {
+        let response = app
+            .request(Method::GET, "/retreats/")
+            .header("Accept-Language", language)
+            .send()
+            .await;
+        assert_eq!(cache_status(&response), expected, "{language}");
+    }
+
+    let response = app
+        .post_as(
+            &user,
+            "/retreats/",
+            json!({
+                "name": "Written",
+                "category_id": retreat.category_id,
+                "slug": "written",
+                "social_links": {}
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    let response = app.get("/retreats/").await;
+    assert_eq!(cache_status(&response), "MISS");
+    assert_eq!(response.data().as_array().map(Vec::len), Some(2));
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn wishlists_need_a_user() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    let uri = format!("/users/wishlists/retreats/{}/", retreat.retreat_id);
+
+    let response = app.request(Method::POST, &uri).send().await;
+    assert!(!response.status.is_success());
+
+    let response = app.post_as(&user, &uri, json!({})).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+
+    let response = app.get_as(&user, "/users/wishlists/retreats/").await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.data().as_array().map(Vec::len), Some(1));
+    app.teardown().await;
+}
+
+/// Days from today, as exchange rates take them.
+fn day(offset: i64) -> String {
+    (Utc::now().date_naive() + Duration::days(offset)).to_string()
+}
+
+async fn create_rate(app: &TestApp, admin: &UserModel, currency: &str, rate: &str, from: i64) {
+    let response = app
+        .post_as(
+            admin,
+            "/exchange-rates/",
+            json!({"currency": currency, "rate": rate, "effective_from": day(from)}),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED);
+}
+
+/// A retreat with the given budget, set by its owner.
+async fn retreat_with_budget(
+    app: &TestApp,
+    currency: &str,
+    budget_min: Option<&str>,
+    budget_max: Option<&str>,
+) -> i64 {
+    let (owner, retreat) = app.create_owned_retreat().await;
+    let response = app
+        .patch_as(
+            &owner,
+            &format!("/retreats/{}/", retreat.retreat_id),
+            json!({"currency": currency, "budget_min": budget_min, "budget_max": budget_max}),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    retreat.retreat_id
+}
+
+fn amount(value: &JsonValue) -> f64 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn converts_budgets_at_the_rates_in_effect() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let admin = app.create_admin().await;
+    create_rate(&app, &admin, "NPR", "130", -30).await;
+    // The later rate replaces the earlier one, the one yet to come is ignored
+    create_rate(&app, &admin, "NPR", "135", -1).await;
+    create_rate(&app, &admin, "NPR", "200", 10).await;
+    create_rate(&app, &admin, "EUR", "0.5", -30).await;
+    let in_dollars = retreat_with_budget(&app, "USD", Some("100"), Some("200")).await;
+    let in_euros = retreat_with_budget(&app, "EUR", Some("45"), None).await;
+    let in_pounds = retreat_with_budget(&app, "GBP", Some("80"), None).await;
+
+    let response = app.get(&format!("/retreats/{in_dollars}/?currency=NPR")).await;
+    assert_eq!(response.data()["currency"], "NPR");
+    assert_eq!(amount(&response.data()["budget_min"]), 13500.0);
+    assert_eq!(amount(&response.data()["budget_max"]), 27000.0);
+
+    // Between two other currencies through the base one
+    let response = app.get(&format!("/retreats/{in_euros}/?currency=NPR")).await;
+    assert_eq!(amount(&response.data()["budget_min"]), 12150.0);
+    assert!(response.data()["budget_max"].is_null());
+
+    // Without a rate budgets stay in their own currency, and none can be asked for
+    let response = app.get(&format!("/retreats/{in_pounds}/?currency=NPR")).await;
+    assert_eq!(response.data()["currency"], "GBP");
+    assert_eq!(amount(&response.data()["budget_min"]), 80.0);
+    let response = app.get(&format!("/retreats/{in_dollars}/?currency=GBP")).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn filters_budgets_across_currencies() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let admin = app.create_admin().await;
+    create_rate(&app, &admin, "NPR", "100", -30).await;
+    create_rate(&app, &admin, "NPR", "150", 0).await;
+    let bounded = retreat_with_budget(&app, "USD", Some("100"), Some("200")).await;
+    let from_only = retreat_with_budget(&app, "USD", Some("300"), None).await;
+    let up_to_only = retreat_with_budget(&app, "USD", None, Some("50")).await;
+    // 100 to 200 dollars at today's rate, 150 to 300 at the earlier one
+    let in_rupees = retreat_with_budget(&app, "NPR", Some("15000"), Some("30000")).await;
+    retreat_with_budget(&app, "USD", None, None).await;
+    retreat_with_budget(&app, "GBP", Some("100"), Some("200")).await;
+
+    let matching = |query: &'static str| {
+        let app = &app;
+        async move {
+            let response = app.get(&format!("/retreats/?{query}")).await;
+            assert_eq!(response.status, StatusCode::OK);
+            let mut retreat_ids: Vec<i64> = response
+                .data()
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|retreat| retreat["retreat_id"].as_i64().unwrap())
+                .collect();
+            retreat_ids.sort();
+            retreat_ids
+        }
+    };
+    assert_eq!(matching("budget_min=250").await, [from_only]);
+    assert_eq!(matching("budget_max=60").await, [up_to_only]);
+    let mut overlapping = vec![bounded, in_rupees];
+    overlapping.sort();
+    assert_eq!(matching("budget_min=150&budget_max=160").await, overlapping);
+    // Asked in rupees, 40000 are about 267 dollars
+    assert_eq!(matching("budget_min=40000&currency=NPR").await, [from_only]);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn admin_routes_reject_other_users() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let admin = app.create_admin().await;
+
+    let response = app.get_as(&user, "/admin/cache/").await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+
+    let response = app.get_as(&admin, "/admin/cache/").await;
+    assert_eq!(response.status, StatusCode::OK);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn lists_posted_reviews() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    let uri = format!("/retreats/{}/reviews/", retreat.retreat_id);
+
+    let response = app
+        .post_as(&user, &uri, json!({"rating": 4.5, "review": "Lovely."}))
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    let other = app.create_user().await;
+    app.create_review(&retreat, &other, 3.0).await;
+
+    let response = app.get(&uri).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.data().as_array().map(Vec::len), Some(2));
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn embeds_only_public_details_of_reviewers() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    app.create_review(&retreat, &user, 4.0).await;
+
+    let response = app
+        .get(&format!("/retreats/{}/reviews/?include=user", retreat.retreat_id))
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    let embedded = &response.data()[0]["user"];
+    assert_eq!(embedded["user_id"], user.user_id);
+    assert_eq!(embedded["name"], user.name);
+    assert!(embedded.get("email").is_none());
+    assert!(embedded.get("phone").is_none());
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn updates_move_the_etag() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    let review = app.create_review(&retreat, &user, 3.0).await;
+    let uri = format!("/retreats/{}/reviews/{}/", retreat.retreat_id, review.review_id);
+
+    let response = app.patch_as(&user, &uri, json!({"rating": 4.0})).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let etag: String = response.headers["etag"].to_str().unwrap().to_string();
+
+    let response = app
+        .request(Method::PATCH, &uri)
+        .user(&user)
+        .header("if-match", &etag)
+        .json(json!({"rating": 5.0}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let response = app
+        .request(Method::PATCH, &uri)
+        .user(&user)
+        .header("if-match", &etag)
+        .json(json!({"rating": 1.0}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::PRECONDITION_FAILED);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn reads_follow_the_writer_to_the_primary() {
+    let clock: Clock = Clock::fixed(Utc::now());
+    let Some(app) = TestApp::spawn_with_replica(clock.clone()).await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    let uri = format!("/retreats/{}/", retreat.retreat_id);
+
+    // The replica never caught up, so reads served from it miss the retreat
+    let response = app.get_as(&user, &uri).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+
+    let wishlist_uri = format!("/users/wishlists/retreats/{}/", retreat.retreat_id);
+    let response = app.post_as(&user, &wishlist_uri, json!({})).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    let response = app.get_as(&user, &uri).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let response = app.get(&uri).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+
+    clock.advance(Duration::seconds(
+        app.state.config.database_replica_stickiness_in_sec as i64 + 1,
+    ));
+    // Another route, as the cache would answer for the one read from the primary
+    let reviews_uri = format!("/retreats/{}/reviews/", retreat.retreat_id);
+    let response = app.get_as(&user, &reviews_uri).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn manages_staff() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let user = app.create_user().await;
+    let retreat = app.create_retreat().await;
+    let uri = format!("/retreats/{}/users/", retreat.retreat_id);
+
+    let response = app
+        .request(Method::POST, &uri)
+        .json(json!({"name": "New Staff", "email": "new-staff@example.com", "role": "Host"}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::CREATED);
+
+    let response = app
+        .request(Method::POST, &uri)
+        .json(json!({"name": "Someone Else", "email": user.email, "role": "Host"}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::ACCEPTED);
+
+    let staff = app.create_staff(&retreat, &user, false).await;
+    let staff_uri = format!("{uri}{}/", staff.retreat_user_id);
+    let response = app
+        .request(Method::PATCH, &staff_uri)
+        .json(json!({"role": "Cook"}))
+        .send()
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let response = app.request(Method::DELETE, &staff_uri).send().await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+    let response = app.request(Method::DELETE, &staff_uri).send().await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn stamps_who_created_and_changed_a_retreat() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let creator = app.create_user().await;
+    let editor = app.create_user().await;
+    let category = app.create_category().await;
+
+    let payload = json!({
+        "name": "Stamped",
+        "category_id": category.category_id,
+        "slug": "stamped",
+        "social_links": {}
+    });
+    let response = app.post_as(&creator, "/retreats/", payload).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    assert_eq!(response.data()["created_by"], creator.user_id);
+    assert_eq!(response.data()["created_by_name"], creator.name);
+    assert_eq!(response.data()["updated_by"], creator.user_id);
+
+    let uri = format!("/retreats/{}/", response.data()["retreat_id"]);
+    let response = app.patch_as(&editor, &uri, json!({"name": "Restamped"})).await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let response = app.get(&uri).await;
+    assert_eq!(response.data()["created_by"], creator.user_id);
+    assert_eq!(response.data()["updated_by"], editor.user_id);
+    assert_eq!(response.data()["updated_by_name"], editor.name);
+    app.teardown().await;
+}
+
+#[tokio::test]
+async fn keeps_the_history_of_a_retreat() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (owner, retreat) = app.create_owned_retreat().await;
+    let outsider = app.create_user().await;
+    let admin = app.create_admin().await;
+    let uri = format!("/retreats/{}/", retreat.retreat_id);
+    let budget_min = app.get(&uri).await.data()["budget_min"].clone();
+
+    let response = app.patch_as(&owner, &uri, json!({"budget_min": "100"})).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let new_budget_min = response.data()["budget_min"].clone();
+    // Changing nothing is not a change
+    let response = app.patch_as(&owner, &uri, json!({"budget_min": "100"})).await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let history_uri = format!("{uri}history/");
+    let response = app.get_as(&owner, &history_uri).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let events = response.data().as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["entity"], "retreat");
+    assert_eq!(events[0]["action"], "update");
+    assert_eq!(events[0]["actor_id"], owner.user_id);
+    assert_eq!(events[0]["actor_name"], owner.name);
+    let diff = events[0]["diff"].as_object().unwrap();
+    assert_eq!(diff.keys().collect::<Vec<_>>(), ["budget_min"]);
+    assert_eq!(diff["budget_min"], json!({"from": budget_min, "to": new_budget_min}));
+
+    let response = app.get_as(&outsider, &history_uri).await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+
+    let search_uri = format!("/admin/audit-events/?actor_id={}", owner.user_id);
+    let response = app.get_as(&owner, &search_uri).await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+    let response = app.get_as(&admin, &search_uri).await;
+    assert_eq!(response.data().as_array().unwrap().len(), 1);
+    let response = app
+        .get_as(&admin, "/admin/audit-events/?entity=retreat&action=delete")
+        .await;
+    assert!(response.data().as_array().unwrap().is_empty());
+    app.teardown().await;
+}
@@ -0,0 +1,167 @@
+use std::{collections::HashMap, fs, path::PathBuf, process};
+
+use my_retreat_nest::config::{Config, ConfigError, Profile};
+
+const ACCESS_KEY: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+const REFRESH_KEY: &str = "fedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210";
+
+/// A file written for one test, removed again when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, content: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("config-{}-{name}", process::id()));
+        fs::write(&path, content).unwrap();
+        TempFile(path)
+    }
+
+    fn path(&self) -> String {
+        self.0.display().to_string()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// The keys without a default, valid in every profile, read from `config_file`.
+fn variables(config_file: &TempFile) -> HashMap<&'static str, String> {
+    HashMap::from([
+        ("APP_CONFIG_FILE", config_file.path()),
+        ("DATABASE_URL", "postgres://localhost/retreats".to_string()),
+        ("PASSWORD_SALT", "salt-for-tests".to_string()),
+        ("JWT_ACCESS_KEY", ACCESS_KEY.to_string()),
+        ("JWT_REFRESH_KEY", REFRESH_KEY.to_string()),
+    ])
+}
+
+fn load(variables: &HashMap<&'static str, String>) -> Result<Config, ConfigError> {
+    Config::from_source(|key| variables.get(key).cloned())
+}
+
+#[test]
+fn defaults_fill_what_no_layer_sets() {
+    let config_file = TempFile::new("defaults.toml", "");
+    let mut variables = variables(&config_file);
+
+    let config = load(&variables).unwrap();
+    assert_eq!(config.profile, Profile::Dev);
+    assert_eq!(config.server_port, 8000);
+    assert_eq!(config.jwt_access_lifetime_in_min, 10);
+    // Profile defaults win over the plain ones
+    assert_eq!(config.server_host, "127.0.0.1");
+    assert_eq!(config.upload_dir, PathBuf::from("uploads"));
+
+    variables.insert("APP_PROFILE", "prod".to_string());
+    variables.insert("UPLOAD_DIR", "/srv/uploads".to_string());
+    let config = load(&variables).unwrap();
+    assert_eq!(config.profile, Profile::Prod);
+    assert_eq!(config.server_host, "0.0.0.0");
+}
+
+#[test]
+fn profile_tables_override_top_level_keys() {
+    let config_file = TempFile::new(
+        "profiles.toml",
+        "server_port = 8100\n\
+         jwt_access_lifetime_in_min = 20\n\
+         \n\
+         [profile.test]\n\
+         server_port = 8200\n\
+         \n\
+         [profile.prod]\n\
+         server_port = 8300\n",
+    );
+    let mut variables = variables(&config_file);
+
+    let config = load(&variables).unwrap();
+    assert_eq!(config.server_port, 8100);
+    assert_eq!(config.jwt_access_lifetime_in_min, 20);
+
+    variables.insert("APP_PROFILE", "test".to_string());
+    let config = load(&variables).unwrap();
+    assert_eq!(config.server_port, 8200);
+    assert_eq!(config.jwt_access_lifetime_in_min, 20);
+}
+
+#[test]
+fn environment_overrides_the_file() {
+    let config_file = TempFile::new(
+        "environment.toml",
+        "server_port = 8100\n\
+         \n\
+         [profile.dev]\n\
+         jwt_access_lifetime_in_min = 20\n",
+    );
+    let mut variables = variables(&config_file);
+    variables.insert("SERVER_PORT", "8400".to_string());
+    variables.insert("JWT_ACCESS_LIFETIME_IN_MIN", "30".to_string());
+
+    let config = load(&variables).unwrap();
+    assert_eq!(config.server_port, 8400);
+    assert_eq!(config.jwt_access_lifetime_in_min, 30);
+}
+
+#[test]
+fn file_secrets_override_the_file_and_drop_the_trailing_newline() {
+    let config_file = TempFile::new("secrets.toml", "password_salt = \"salt-from-the-file\"\n");
+    let secret = TempFile::new("password-salt", "salt-from-a-secret\n");
+    let mut variables = variables(&config_file);
+    variables.remove("PASSWORD_SALT");
+
+    assert_eq!(load(&variables).unwrap().password_salt, "salt-from-the-file");
+
+    variables.insert("PASSWORD_SALT_FILE", secret.path());
+    assert_eq!(load(&variables).unwrap().password_salt, "salt-from-a-secret");
+
+    // A key is set either directly or through its file, not both
+    variables.insert("PASSWORD_SALT", "salt-from-the-environment".to_string());
+    let error = load(&variables).unwrap_err();
+    assert_eq!(
+        error.problems,
+        ["PASSWORD_SALT and PASSWORD_SALT_FILE are both set, use only one"]
+    );
+}
+
+#[test]
+fn reports_every_problem_at_once() {
+    let config_file = TempFile::new(
+        "problems.toml",
+        "colour = \"blue\"\n\
+         \n\
+         [profile.staging]\n\
+         server_port = 8100\n",
+    );
+    let mut variables = variables(&config_file);
+    variables.insert("APP_PROFILE", "staging".to_string());
+    variables.remove("DATABASE_URL");
+    variables.insert("PASSWORD_SALT", "short".to_string());
+    variables.insert("JWT_REFRESH_KEY", ACCESS_KEY.to_string());
+    variables.insert("SERVER_PORT", "eighty".to_string());
+    variables.insert("JWT_ACCESS_LIFETIME_IN_MIN", "0".to_string());
+
+    let error = load(&variables).unwrap_err();
+    let source = config_file.path();
+    let expected = [
+        "APP_PROFILE must be one of dev, test or prod, got `staging`".to_string(),
+        format!("{source}: unknown key `colour`"),
+        format!("{source}: unknown profile `staging`"),
+        "SERVER_PORT must be a valid u16, got `eighty` (from environment)".to_string(),
+        "DATABASE_URL is required".to_string(),
+        "PASSWORD_SALT must be at least 8 characters long".to_string(),
+        "JWT_ACCESS_KEY and JWT_REFRESH_KEY must differ".to_string(),
+        "JWT_ACCESS_LIFETIME_IN_MIN must be between 1 and 60".to_string(),
+    ];
+    for problem in &expected {
+        assert!(
+            error.problems.contains(problem),
+            "missing `{problem}` in {:?}",
+            error.problems
+        );
+    }
+    assert_eq!(error.problems.len(), expected.len());
+    assert_eq!(error.profile, "dev");
+    assert!(error.to_string().contains("8 problem(s) found"));
+}
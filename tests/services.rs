@@ -0,0 +1,379 @@
+//! Service use cases against SeaORM's `MockDatabase`, which answers queries in the order
+//! they are appended and logs every statement.
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+use my_retreat_nest::{
+    services::{
+        ServiceError,
+        reviews::update_review,
+        staff::{AddedStaff, add_staff},
+        users::reset_password,
+        wishlists::add_to_wishlist,
+    },
+    state::Clock,
+    testing::{
+        AuditEventModel, JobModel, OutboxEventModel, RetreatModel, RetreatReviewModel,
+        RetreatUserModel, UserModel, WishlistModel, with_actor,
+    },
+};
+use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, Transaction};
+use serde_json::json;
+
+fn timestamp() -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339("2025-01-01T00:00:00+00:00").unwrap()
+}
+
+fn naive_timestamp() -> NaiveDateTime {
+    timestamp().naive_utc()
+}
+
+fn clock() -> Clock {
+    Clock::fixed(timestamp().to_utc())
+}
+
+fn retreat() -> RetreatModel {
+    RetreatModel {
+        retreat_id: 1,
+        name: "Retreat".to_string(),
+        description: None,
+        category_id: 1,
+        slug: "retreat".to_string(),
+        social_links: json!({}),
+        email: None,
+        phone: None,
+        logo: None,
+        latitude: None,
+        longitude: None,
+        address: None,
+        budget_min: None,
+        budget_max: None,
+        is_published: false,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        created_by: None,
+        updated_by: None,
+        deleted_at: None,
+        organization_id: None,
+        currency: "USD".to_string(),
+    }
+}
+
+fn user(name: &str) -> UserModel {
+    UserModel {
+        user_id: 2,
+        name: name.to_string(),
+        email: "staff@example.com".to_string(),
+        password: String::new(),
+        phone: None,
+        created_at: naive_timestamp(),
+        updated_at: naive_timestamp(),
+        deleted_at: None,
+        is_admin: false,
+    }
+}
+
+fn job() -> JobModel {
+    JobModel {
+        job_id: 3,
+        queue: "default".to_string(),
+        kind: "set_invited_user_password".to_string(),
+        payload: json!({"user_id": 2}),
+        status: "queued".to_string(),
+        attempts: 0,
+        max_attempts: 5,
+        run_at: timestamp(),
+        locked_at: None,
+        locked_by: None,
+        last_error: None,
+        finished_at: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+fn staff() -> RetreatUserModel {
+    RetreatUserModel {
+        retreat_user_id: 4,
+        retreat_id: 1,
+        user_id: 2,
+        is_owner: false,
+        role: Some("host".to_string()),
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        created_by: None,
+        updated_by: None,
+    }
+}
+
+fn event() -> OutboxEventModel {
+    OutboxEventModel {
+        outbox_event_id: 5,
+        event: "staff_added".to_string(),
+        payload: json!({}),
+        status: "pending".to_string(),
+        attempts: 0,
+        last_error: None,
+        dispatched_at: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+fn review() -> RetreatReviewModel {
+    RetreatReviewModel {
+        review_id: 6,
+        user_id: 2,
+        retreat_id: 1,
+        rating: 3.0,
+        review: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        deleted_at: None,
+    }
+}
+
+fn audit_event(entity: &str) -> AuditEventModel {
+    AuditEventModel {
+        audit_event_id: 8,
+        actor_id: None,
+        entity: entity.to_string(),
+        entity_id: 1,
+        retreat_id: Some(1),
+        action: "create".to_string(),
+        diff: json!({}),
+        created_at: timestamp(),
+    }
+}
+
+fn invitation() -> serde_json::Value {
+    json!({"name": "Staff", "email": "staff@example.com", "role": "host"})
+}
+
+/// The SQL of each transaction in the log, `BEGIN` and `COMMIT` or `ROLLBACK` included.
+fn statements(db: DatabaseConnection) -> Vec<Vec<String>> {
+    db.into_transaction_log()
+        .iter()
+        .map(Transaction::statements)
+        .map(|statements| statements.iter().map(|s| s.sql.clone()).collect())
+        .collect()
+}
+
+fn inserts(statements: &[String]) -> Vec<&str> {
+    statements
+        .iter()
+        .filter_map(|sql| sql.strip_prefix("INSERT INTO "))
+        .filter_map(|sql| sql.split('"').nth(1))
+        .collect()
+}
+
+/// The columns given in the `retreat_users` insert, those returned left out.
+fn staff_columns(statements: &[String]) -> &str {
+    statements
+        .iter()
+        .find(|sql| sql.starts_with(r#"INSERT INTO "retreat_users""#))
+        .and_then(|sql| sql.split(" VALUES ").next())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn inviting_staff_saves_everything_in_one_transaction() {
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([[retreat()]])
+        .append_query_results([Vec::<UserModel>::new()])
+        .append_query_results([Vec::<UserModel>::new()])
+        .append_query_results([[user("Staff")]])
+        .append_query_results([[audit_event("user")]])
+        .append_query_results([[job()]])
+        .append_query_results([[staff()]])
+        .append_query_results([[audit_event("staff")]])
+        .append_query_results([[event()]])
+        .into_connection();
+
+    let added = add_staff(&db, &clock(), 1, serde_json::from_value(invitation()).unwrap())
+        .await
+        .unwrap();
+    assert!(matches!(added, AddedStaff::Added(staff) if staff.retreat_user_id == 4));
+
+    let log = statements(db);
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].first().map(String::as_str), Some("BEGIN"));
+    assert_eq!(log[0].last().map(String::as_str), Some("COMMIT"));
+    assert_eq!(
+        inserts(&log[0]),
+        [
+            "users",
+            "audit_events",
+            "jobs",
+            "retreat_users",
+            "audit_events",
+            "outbox_events"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn a_failed_staff_insert_rolls_back_the_invited_user() {
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([[retreat()]])
+        .append_query_results([Vec::<UserModel>::new()])
+        .append_query_results([Vec::<UserModel>::new()])
+        .append_query_results([[user("Staff")]])
+        .append_query_results([[audit_event("user")]])
+        .append_query_results([[job()]])
+        .append_query_errors([DbErr::Custom("duplicate key".to_string())])
+        .into_connection();
+
+    let result = add_staff(&db, &clock(), 1, serde_json::from_value(invitation()).unwrap()).await;
+    assert!(matches!(result, Err(ServiceError::Database(_))));
+
+    let log = statements(db);
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].last().map(String::as_str), Some("ROLLBACK"));
+    assert!(!log[0].iter().any(|sql| sql == "COMMIT"));
+}
+
+#[tokio::test]
+async fn staff_going_by_another_name_is_not_added() {
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([[retreat()]])
+        .append_query_results([[user("Someone Else")]])
+        .into_connection();
+
+    let added = add_staff(&db, &clock(), 1, serde_json::from_value(invitation()).unwrap())
+        .await
+        .unwrap();
+    assert!(matches!(added, AddedStaff::NameMismatch(name) if name == "Someone Else"));
+
+    let log = statements(db);
+    assert!(inserts(&log[0]).is_empty());
+    assert_eq!(log[0].last().map(String::as_str), Some("ROLLBACK"));
+}
+
+#[tokio::test]
+async fn staff_of_a_missing_retreat_is_not_found() {
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([Vec::<RetreatModel>::new()])
+        .into_connection();
+
+    let result = add_staff(&db, &clock(), 1, serde_json::from_value(invitation()).unwrap()).await;
+    assert!(matches!(
+        result,
+        Err(ServiceError::NotFound("Retreat not found."))
+    ));
+}
+
+#[tokio::test]
+async fn wishlisting_twice_keeps_the_first_item() {
+    let existing = WishlistModel {
+        wishlist_id: 7,
+        user_id: 2,
+        retreat_id: 1,
+        created_at: timestamp(),
+    };
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([[retreat()]])
+        .append_query_results([[existing.clone()]])
+        .into_connection();
+
+    let instance = add_to_wishlist(&db, 1, 2).await.unwrap();
+    assert_eq!(instance, existing);
+    assert!(inserts(&statements(db)[0]).is_empty());
+}
+
+#[tokio::test]
+async fn a_review_changed_meanwhile_is_modified() {
+    // `UPDATE ... RETURNING` finds no row still holding the `updated_at` read
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([Vec::<RetreatReviewModel>::new()])
+        .into_connection();
+
+    let payload = serde_json::from_value(json!({"rating": 4.0})).unwrap();
+    let result = update_review(&db, review(), payload).await;
+    assert!(matches!(result, Err(ServiceError::Modified)));
+    assert!(inserts(&statements(db)[0]).is_empty());
+}
+
+#[tokio::test]
+async fn a_review_update_is_audited_with_its_diff() {
+    let updated = RetreatReviewModel {
+        rating: 4.0,
+        ..review()
+    };
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([[updated]])
+        .append_query_results([[audit_event("review")]])
+        .into_connection();
+
+    let payload = serde_json::from_value(json!({"rating": 4.0})).unwrap();
+    with_actor(Some(9), update_review(&db, review(), payload))
+        .await
+        .unwrap();
+
+    let log = db.into_transaction_log();
+    let audit = log[0]
+        .statements()
+        .iter()
+        .find(|statement| statement.sql.starts_with(r#"INSERT INTO "audit_events""#))
+        .unwrap();
+    let values = audit.values.as_ref().unwrap().0.clone();
+    assert!(values.contains(&Some(9i64).into()));
+    assert!(values.contains(&json!({"rating": {"from": 3.0, "to": 4.0}}).into()));
+}
+
+#[tokio::test]
+async fn a_password_reset_is_audited_without_the_hash() {
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([[user("Staff")]])
+        .append_query_results([[audit_event("user")]])
+        .into_connection();
+
+    reset_password(&db, user("Staff"), "new-hash".to_string())
+        .await
+        .unwrap();
+
+    let log = db.into_transaction_log();
+    let audit = log[0]
+        .statements()
+        .iter()
+        .find(|statement| statement.sql.starts_with(r#"INSERT INTO "audit_events""#))
+        .unwrap();
+    let values = audit.values.as_ref().unwrap().0.clone();
+    let redacted = json!({"password": {"from": "[redacted]", "to": "[redacted]"}});
+    assert!(values.contains(&redacted.into()));
+    assert!(!values.contains(&"new-hash".into()));
+}
+
+#[tokio::test]
+async fn staff_is_stamped_with_the_acting_user() {
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([[retreat()]])
+        .append_query_results([[user("Staff")]])
+        .append_query_results([[staff()]])
+        .append_query_results([[audit_event("staff")]])
+        .append_query_results([[event()]])
+        .into_connection();
+
+    let payload = serde_json::from_value(invitation()).unwrap();
+    with_actor(Some(9), add_staff(&db, &clock(), 1, payload)).await.unwrap();
+
+    let log = statements(db);
+    assert!(staff_columns(&log[0]).contains(r#""created_by", "updated_by""#));
+}
+
+#[tokio::test]
+async fn nothing_is_stamped_without_an_acting_user() {
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([[retreat()]])
+        .append_query_results([[user("Staff")]])
+        .append_query_results([[staff()]])
+        .append_query_results([[audit_event("staff")]])
+        .append_query_results([[event()]])
+        .into_connection();
+
+    add_staff(&db, &clock(), 1, serde_json::from_value(invitation()).unwrap())
+        .await
+        .unwrap();
+
+    let log = statements(db);
+    assert!(!staff_columns(&log[0]).contains("created_by"));
+}
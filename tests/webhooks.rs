@@ -0,0 +1,195 @@
+use std::{net::SocketAddr, time::Duration};
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use my_retreat_nest::webhooks::delivery::{
+    DELIVERY_HEADER, DeliveryOutcome, EVENT_HEADER, SIGNATURE_HEADER, WebhookRequest,
+    check_target, http_client, retry_delay, send_webhook, sign_payload, verify_signature,
+};
+use tokio::{net::TcpListener, sync::mpsc};
+
+const SECRET: &str = "whsec_test";
+
+/// A request as the subscriber saw it.
+struct Received {
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+/// Local stand-in for a subscriber, answering every POST with `status`.
+async fn stand_in(status: StatusCode) -> (SocketAddr, mpsc::UnboundedReceiver<Received>) {
+    let (sender, receiver) = mpsc::unbounded_channel::<Received>();
+    let app = Router::new()
+        .route(
+            "/hook",
+            post(
+                move |State(sender): State<mpsc::UnboundedSender<Received>>,
+                      headers: HeaderMap,
+                      body: Bytes| async move {
+                    let _ = sender.send(Received { headers, body });
+                    (status, "busy")
+                },
+            ),
+        )
+        .with_state(sender);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (address, receiver)
+}
+
+fn request<'a>(url: &'a str, body: &'a [u8]) -> WebhookRequest<'a> {
+    WebhookRequest {
+        url,
+        secret: SECRET,
+        event: "retreat.published",
+        delivery_id: 42,
+        body,
+        timestamp: chrono::Utc::now().timestamp(),
+    }
+}
+
+#[tokio::test]
+async fn delivers_signed_payload() {
+    let (address, mut received) = stand_in(StatusCode::OK).await;
+    let url = format!("http://{address}/hook");
+    let body = br#"{"event":"retreat.published","data":{"retreat_id":1}}"#;
+
+    let outcome = send_webhook(&http_client(Duration::from_secs(5)), &request(&url, body)).await;
+    assert_eq!(outcome, DeliveryOutcome::Delivered { status_code: 200 });
+
+    let received = received.recv().await.unwrap();
+    assert_eq!(received.body.as_ref(), body);
+    assert_eq!(received.headers[EVENT_HEADER], "retreat.published");
+    assert_eq!(received.headers[DELIVERY_HEADER], "42");
+    assert_eq!(received.headers["content-type"], "application/json");
+    let signature = received.headers[SIGNATURE_HEADER].to_str().unwrap();
+    assert!(verify_signature(
+        SECRET,
+        signature,
+        &received.body,
+        chrono::Utc::now().timestamp(),
+        Duration::from_secs(300),
+    ));
+    assert!(!verify_signature(
+        "whsec_other",
+        signature,
+        &received.body,
+        chrono::Utc::now().timestamp(),
+        Duration::from_secs(300),
+    ));
+}
+
+#[tokio::test]
+async fn reports_error_responses() {
+    let (address, mut received) = stand_in(StatusCode::SERVICE_UNAVAILABLE).await;
+    let url = format!("http://{address}/hook");
+
+    let outcome = send_webhook(&http_client(Duration::from_secs(5)), &request(&url, b"{}")).await;
+    assert_eq!(
+        outcome,
+        DeliveryOutcome::Failed {
+            status_code: Some(503),
+            error: "HTTP 503".to_string(),
+        }
+    );
+    assert!(received.recv().await.is_some());
+}
+
+#[tokio::test]
+async fn reports_unreachable_subscribers() {
+    // Grab a free port, then close it again so nothing is listening
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+    let url = format!("http://{address}/hook");
+
+    let outcome = send_webhook(&http_client(Duration::from_secs(5)), &request(&url, b"{}")).await;
+    assert!(matches!(
+        outcome,
+        DeliveryOutcome::Failed {
+            status_code: None,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn rejects_tampered_and_stale_signatures() {
+    let signature = sign_payload(SECRET, 1_700_000_000, b"{\"a\":1}");
+    let verify = |signature: &str, body: &[u8], now: i64| {
+        verify_signature(SECRET, signature, body, now, Duration::from_secs(300))
+    };
+
+    assert!(verify(&signature, b"{\"a\":1}", 1_700_000_100));
+    // Body changed on the way
+    assert!(!verify(&signature, b"{\"a\":2}", 1_700_000_100));
+    // Replayed long after it was signed
+    assert!(!verify(&signature, b"{\"a\":1}", 1_700_001_000));
+    assert!(!verify("v1=deadbeef", b"{\"a\":1}", 1_700_000_000));
+}
+
+#[test]
+fn backs_off_exponentially_up_to_the_cap() {
+    let base = Duration::from_secs(30);
+    let max = Duration::from_secs(600);
+
+    let delays: Vec<u64> = (1..=7)
+        .map(|attempts| retry_delay(attempts, base, max).as_secs())
+        .collect();
+    assert_eq!(delays, vec![30, 60, 120, 240, 480, 600, 600]);
+    assert_eq!(retry_delay(200, base, max), max);
+}
+
+#[tokio::test]
+async fn refuses_targets_inside_the_network() {
+    for url in [
+        "http://127.0.0.1/hook",
+        "http://localhost:8080/hook",
+        "http://10.0.0.5/hook",
+        "http://169.254.169.254/latest/meta-data/",
+        "http://[::1]/hook",
+        "http://[::ffff:192.168.1.1]/hook",
+        "http://0.0.0.0/hook",
+        "http://0.1.2.3/hook",
+        "http://192.0.0.8/hook",
+        "http://198.18.0.1/hook",
+        "http://198.19.255.254/hook",
+        "http://[::]/hook",
+        "http://[::10.0.0.1]/hook",
+        "http://[64:ff9b::a9fe:a9fe]/hook",
+        "http://[2002:a00:1::1]/hook",
+        "ftp://example.com/hook",
+    ] {
+        assert!(check_target(url).await.is_err(), "{url} was allowed");
+    }
+    for url in [
+        "https://93.184.215.14/hook",
+        "https://198.20.0.1/hook",
+        "https://[2606:4700:4700::1111]/hook",
+    ] {
+        assert!(check_target(url).await.is_ok(), "{url} was refused");
+    }
+}
+
+#[tokio::test]
+async fn refuses_hosts_resolving_inside_the_network_when_sending() {
+    let (address, mut received) = stand_in(StatusCode::OK).await;
+    // Whatever a host resolved to when checked, the client resolves it again itself
+    let url = format!("http://localhost:{}/hook", address.port());
+
+    let outcome = send_webhook(&http_client(Duration::from_secs(5)), &request(&url, b"{}")).await;
+    assert!(matches!(
+        outcome,
+        DeliveryOutcome::Failed {
+            status_code: None,
+            ..
+        }
+    ));
+    assert!(received.try_recv().is_err());
+}